@@ -0,0 +1,65 @@
+use semver::{Version, VersionReq};
+
+use crate::prelude::*;
+
+/// A named release channel that can be given in place of a version, e.g. `wasmedgeup install lts`.
+#[derive(Debug, Clone, Copy)]
+pub struct Channel {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Stable releases matching this requirement belong to the channel; `None` means all of them.
+    requirement: Option<&'static str>,
+}
+
+/// Channel definitions. `lts` tracks the release line designated for long-term support.
+pub const CHANNELS: &[Channel] = &[
+    Channel {
+        name: "latest",
+        description: "newest stable release",
+        requirement: None,
+    },
+    Channel {
+        name: "stable",
+        description: "newest stable release (same as `latest`)",
+        requirement: None,
+    },
+    Channel {
+        name: "lts",
+        description: "newest release of the long-term-support line (0.14.x)",
+        requirement: Some("~0.14"),
+    },
+];
+
+impl Channel {
+    pub fn find(name: &str) -> Option<&'static Channel> {
+        CHANNELS.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Picks the newest stable release in `releases` that belongs to this channel.
+    pub fn select(&self, releases: &[Version]) -> Result<Version> {
+        let req = self
+            .requirement
+            .map(VersionReq::parse)
+            .transpose()
+            .expect("channel requirements are valid");
+
+        releases
+            .iter()
+            .filter(|v| v.pre.is_empty())
+            .filter(|v| req.as_ref().is_none_or(|req| req.matches(v)))
+            .max()
+            .cloned()
+            .ok_or_else(|| Error::VersionNotFound {
+                version: self.name.to_string(),
+            })
+    }
+}
+
+/// Comma-separated list of channel names, for error messages.
+pub fn channel_names() -> String {
+    CHANNELS
+        .iter()
+        .map(|c| c.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,376 @@
+//! Where the expected SHA-256 of a release asset comes from.
+//!
+//! Each source implements [`ChecksumSource`]; [`WasmEdgeApiClient::checksum_source`] picks the
+//! one an install uses.
+
+use std::future::Future;
+
+use semver::Version;
+use serde::Deserialize;
+use snafu::ResultExt;
+
+use super::{
+    probe_asset, releases, signature, Asset, WasmEdgeApiClient, CHECKSUM_FILE_NAME,
+    CHECKSUM_SIGNATURE_FILE_NAME,
+};
+use crate::prelude::*;
+
+/// A source of expected checksums for release assets.
+pub trait ChecksumSource {
+    /// What the checksum is read from, for logs, e.g. `SHA256SUM`.
+    fn name(&self) -> &'static str;
+
+    /// The lowercase hex SHA-256 `asset` of release `version` is expected to have.
+    fn checksum(
+        &self,
+        client: &WasmEdgeApiClient,
+        version: &Version,
+        asset: &Asset,
+    ) -> impl Future<Output = Result<String>> + Send;
+}
+
+/// The `SHA256SUM` file published with each release, listing `<checksum> <file name>` lines.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SumsFile;
+
+impl SumsFile {
+    /// The names of the files release `version` lists checksums for, or `None` when it has no
+    /// checksum file.
+    pub async fn listed(
+        &self,
+        client: &WasmEdgeApiClient,
+        version: &Version,
+    ) -> Result<Option<Vec<String>>> {
+        let content = Self::fetch(client, version).await?;
+        Ok(content.map(|content| {
+            content
+                .lines()
+                .filter_map(
+                    |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                        [_, name] => Some(name.to_string()),
+                        _ => None,
+                    },
+                )
+                .collect()
+        }))
+    }
+
+    /// The checksum file of release `version`, or `None` when the server has none.
+    ///
+    /// With a [`WasmEdgeApiClient::checksums_key`], its signature is checked before it is
+    /// returned.
+    async fn fetch(client: &WasmEdgeApiClient, version: &Version) -> Result<Option<String>> {
+        client.require_network("checksum file download")?;
+        let url = client.checksum_url(version)?;
+
+        tracing::debug!(%url, CHECKSUM_FILE_NAME, "Trying checksum file");
+
+        let http = client.http_client()?;
+        let response = client
+            .requests
+            .send(http.get(url))
+            .await
+            .context(RequestSnafu {
+                resource: "checksums",
+            })?;
+
+        if !response.status().is_success() {
+            tracing::debug!(
+                status = %response.status(),
+                file = CHECKSUM_FILE_NAME,
+                "Checksum file not found"
+            );
+            return Ok(None);
+        }
+
+        let content = response.text().await.context(RequestSnafu {
+            resource: "checksums",
+        })?;
+
+        tracing::debug!(
+            lines = content.lines().count(),
+            file = CHECKSUM_FILE_NAME,
+            "Got checksum file content"
+        );
+        if let Some(key) = &client.checksums_key {
+            Self::verify_signature(client, version, key, &content).await?;
+        }
+        Ok(Some(content))
+    }
+
+    /// Checks `content`, the checksum file of `version`, against its detached signature.
+    async fn verify_signature(
+        client: &WasmEdgeApiClient,
+        version: &Version,
+        key: &std::path::Path,
+        content: &str,
+    ) -> Result<()> {
+        let invalid = |reason: String| Error::SignatureInvalid {
+            file: CHECKSUM_FILE_NAME.to_string(),
+            reason,
+        };
+        client.require_network("checksum signature download")?;
+        let url = client.checksum_signature_url(version)?;
+        tracing::debug!(%url, "Fetching checksum file signature");
+
+        let http = client.http_client()?;
+        let response = client
+            .requests
+            .send(http.get(url.clone()))
+            .await
+            .context(RequestSnafu {
+                resource: "checksum signature",
+            })?;
+        if !response.status().is_success() {
+            return Err(invalid(format!(
+                "no {CHECKSUM_SIGNATURE_FILE_NAME} at {url} ({})",
+                response.status()
+            )));
+        }
+        let detached = response.bytes().await.context(RequestSnafu {
+            resource: "checksum signature",
+        })?;
+
+        let (key, content) = (key.to_path_buf(), content.to_string());
+        tokio::task::spawn_blocking(move || {
+            signature::verify_detached(&key, content.as_bytes(), &detached)
+        })
+        .await
+        .map_err(|_| Error::Unknown)?
+        .map_err(invalid)?;
+        tracing::debug!(%version, "Checksum file signature verified");
+        Ok(())
+    }
+}
+
+impl ChecksumSource for SumsFile {
+    fn name(&self) -> &'static str {
+        CHECKSUM_FILE_NAME
+    }
+
+    async fn checksum(
+        &self,
+        client: &WasmEdgeApiClient,
+        version: &Version,
+        asset: &Asset,
+    ) -> Result<String> {
+        let Some(content) = Self::fetch(client, version).await? else {
+            // Old releases shipped archives before checksum files; tell that apart from a
+            // version or mirror that has neither.
+            let asset_url = client.asset_url(asset)?;
+            if probe_asset(&client.http_client()?, &client.requests, &asset_url)
+                .await
+                .is_ok()
+            {
+                return Err(Error::ChecksumsUnpublished {
+                    version: version.to_string(),
+                    asset: asset.archive_name.clone(),
+                });
+            }
+            return Err(Error::ChecksumNotFound {
+                version: version.to_string(),
+                asset: asset.archive_name.clone(),
+                listed: Vec::new(),
+            });
+        };
+
+        let mut listed = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            tracing::debug!(line_num = i, line = line, "Processing checksum line");
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() == 2 {
+                tracing::debug!(checksum = parts[0], file = parts[1], "Found checksum entry");
+
+                if parts[1] == asset.archive_name {
+                    tracing::debug!(checksum = parts[0], "Found matching checksum");
+                    return Ok(parts[0].to_ascii_lowercase());
+                }
+                listed.push(parts[1].to_string());
+            }
+        }
+
+        tracing::error!(
+            version = %version,
+            asset = %asset.archive_name,
+            "No checksum found in any file"
+        );
+        // A naming mismatch (e.g. on a mirror) is easiest to spot next to what the file does list.
+        tracing::debug!(
+            file = CHECKSUM_FILE_NAME,
+            ?listed,
+            "Files listed in checksum file"
+        );
+
+        Err(Error::ChecksumNotFound {
+            version: version.to_string(),
+            asset: asset.archive_name.clone(),
+            listed,
+        })
+    }
+}
+
+/// A checksum known in advance, e.g. from `--expected-checksum` or a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explicit(String);
+
+impl Explicit {
+    pub fn new(checksum: &str) -> Self {
+        Self(checksum.to_ascii_lowercase())
+    }
+}
+
+impl ChecksumSource for Explicit {
+    fn name(&self) -> &'static str {
+        "expected checksum"
+    }
+
+    async fn checksum(&self, _: &WasmEdgeApiClient, _: &Version, _: &Asset) -> Result<String> {
+        Ok(self.0.clone())
+    }
+}
+
+/// The `digest` the GitHub REST API reports for each asset of a release, e.g.
+/// `sha256:0123…`.
+///
+/// GitHub only records digests for assets uploaded since it introduced them, so this is no
+/// replacement for the checksum file, but it covers some releases that lack one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiDigest;
+
+#[derive(Debug, Deserialize)]
+struct ApiReleaseAssets {
+    #[serde(default)]
+    assets: Vec<ApiAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAsset {
+    name: String,
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+impl ChecksumSource for ApiDigest {
+    fn name(&self) -> &'static str {
+        "GitHub asset digest"
+    }
+
+    async fn checksum(
+        &self,
+        client: &WasmEdgeApiClient,
+        version: &Version,
+        asset: &Asset,
+    ) -> Result<String> {
+        client.require_network("asset digest lookup")?;
+        let url = format!(
+            "{}/tags/{version}",
+            client.releases_api_url.trim_end_matches('/')
+        );
+        tracing::debug!(%url, "Trying GitHub asset digests");
+
+        let http = client.http_client()?;
+        let response = client
+            .requests
+            .send(releases::api_get(&http, &url, client.github_token.as_ref()))
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .context(RequestSnafu {
+                resource: "release assets",
+            })?;
+        let body = response.text().await.context(RequestSnafu {
+            resource: "release assets",
+        })?;
+        let release: ApiReleaseAssets = serde_json::from_str(&body).context(ReleaseListingSnafu)?;
+
+        let digest = release
+            .assets
+            .iter()
+            .find(|a| a.name == asset.archive_name)
+            .and_then(|a| a.digest.as_deref()?.strip_prefix("sha256:"))
+            .filter(|hex| hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit()));
+        match digest {
+            Some(hex) => {
+                tracing::debug!(checksum = hex, "Found asset digest");
+                Ok(hex.to_ascii_lowercase())
+            }
+            None => Err(Error::ChecksumNotFound {
+                version: version.to_string(),
+                asset: asset.archive_name.clone(),
+                listed: release
+                    .assets
+                    .into_iter()
+                    .filter(|a| a.digest.is_some())
+                    .map(|a| a.name)
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// The checksums a release publishes: its [`SumsFile`], or the [`ApiDigest`] for releases that
+/// predate checksum files.
+///
+/// When neither has one, the checksum file's error is returned, as it explains the failure best.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Published;
+
+impl ChecksumSource for Published {
+    fn name(&self) -> &'static str {
+        CHECKSUM_FILE_NAME
+    }
+
+    async fn checksum(
+        &self,
+        client: &WasmEdgeApiClient,
+        version: &Version,
+        asset: &Asset,
+    ) -> Result<String> {
+        match SumsFile.checksum(client, version, asset).await {
+            // The digests are not signed, so they cannot stand in for a signed checksum file.
+            Err(unpublished @ Error::ChecksumsUnpublished { .. })
+                if client.checksums_key.is_none() =>
+            {
+                match ApiDigest.checksum(client, version, asset).await {
+                    Ok(checksum) => {
+                        tracing::info!(%version, "Release has no checksum file; verifying against GitHub's asset digest");
+                        Ok(checksum)
+                    }
+                    Err(e) => {
+                        tracing::debug!(error = %e, "No GitHub asset digest either");
+                        Err(unpublished)
+                    }
+                }
+            }
+            checksum => checksum,
+        }
+    }
+}
+
+/// The source [`WasmEdgeApiClient::checksum_source`] picked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectedSource {
+    Explicit(Explicit),
+    Published(Published),
+}
+
+impl ChecksumSource for SelectedSource {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Explicit(source) => source.name(),
+            Self::Published(source) => source.name(),
+        }
+    }
+
+    async fn checksum(
+        &self,
+        client: &WasmEdgeApiClient,
+        version: &Version,
+        asset: &Asset,
+    ) -> Result<String> {
+        match self {
+            Self::Explicit(source) => source.checksum(client, version, asset).await,
+            Self::Published(source) => source.checksum(client, version, asset).await,
+        }
+    }
+}
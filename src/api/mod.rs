@@ -1,21 +1,39 @@
 use std::{
     fmt::Write,
+    future::Future,
     io::{Read, Seek},
-    path::Path,
-    sync::OnceLock,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    time::Duration,
 };
 
 use crate::{
-    http::HttpClientConfig,
+    cache::ListingCache,
+    events::ProgressEvent,
+    http::{HttpClientConfig, ProxyConfig, Secret, DEFAULT_USER_AGENT},
     prelude::*,
     target::{TargetArch, TargetOS},
+    units::{ByteSize, Seconds},
 };
+pub mod channels;
+pub mod checksums;
 pub mod releases;
-use indicatif::{ProgressBar, ProgressState, ProgressStyle};
-pub use releases::ReleasesFilter;
+pub mod repo;
+pub mod request_log;
+mod signature;
+pub use channels::Channel;
+pub use checksums::{ApiDigest, ChecksumSource, Explicit, Published, SelectedSource, SumsFile};
+use indicatif::{MultiProgress, ProgressBar, ProgressState, ProgressStyle};
+pub use releases::{ReleasesFilter, ResolveVia};
+pub use repo::RepoSlug;
+pub use request_log::{RequestLog, RequestRecord};
 
-use reqwest::{Client, Response};
+use reqwest::{
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, RANGE},
+    Client, Response, StatusCode,
+};
 use semver::{Comparator, Prerelease, Version, VersionReq};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use snafu::ResultExt;
 use tempfile::NamedTempFile;
@@ -29,142 +47,525 @@ use url::Url;
 pub struct WasmEdgeApiClient {
     /// Connection timeout in seconds
     pub connect_timeout: u64,
-    /// Request timeout in seconds
+    /// Request timeout in seconds, bounding a whole operation including its retries
     pub request_timeout: u64,
+    /// Timeout in seconds for a single attempt; a stalled attempt is aborted and retried
+    pub attempt_timeout: Option<u64>,
+    /// Number of times a transiently failing operation is retried
+    pub retries: u32,
+    /// GitHub repository releases are sourced from
+    pub repo: RepoSlug,
+    /// Git repository used to list releases (via its tags)
+    pub git_url: String,
+    /// GitHub REST endpoint used to list releases
+    pub releases_api_url: String,
+    /// Which of the two release listings to use
+    pub resolve_via: ResolveVia,
+    /// Base URL that release assets and checksum files are downloaded from
+    pub release_base_url: String,
+    /// User-Agent sent with every request; [`DEFAULT_USER_AGENT`] when unset
+    pub user_agent: Option<String>,
+    /// PEM file with extra root certificates to trust, for TLS-inspecting proxies
+    pub ca_cert: Option<PathBuf>,
+    /// Accept any TLS certificate, leaving integrity to the checksum verification
+    pub danger_insecure: bool,
+    /// Display that download progress bars are added to, when several downloads run at once
+    pub progress: Option<MultiProgress>,
+    /// Report download progress as `--json-lines` events on stdout instead of bars or lines
+    pub progress_events: bool,
+    /// Public key the checksum file's detached signature must verify against before any of its
+    /// entries is trusted; unset, the checksum file is read unsigned
+    pub checksums_key: Option<PathBuf>,
+    /// Bypass every cache: the download and listing caches are skipped and servers are asked
+    /// for fresh responses
+    pub refresh: bool,
+    /// Cache release listings are read from and stored in; unset, every listing is fetched
+    pub listing_cache: Option<ListingCache>,
+    /// Token sent to the GitHub REST API, which raises its rate limit; never sent to release
+    /// downloads or mirrors
+    pub github_token: Option<Secret>,
+    /// Proxies to use instead of those of the process environment
+    pub proxy: Option<ProxyConfig>,
+    /// Fail every operation that would reach the network with [`Error::Offline`] instead
+    pub offline: bool,
+    /// HTTP client built on first use and shared by clones, so that all requests of one run
+    /// reuse its connection pool
+    http: Arc<OnceLock<Client>>,
+    /// Requests sent and retries made by this client and its clones, for failure diagnostics
+    requests: Arc<RequestLog>,
 }
 
-const WASM_EDGE_GIT_URL: &str = "https://github.com/WasmEdge/WasmEdge.git";
-const WASM_EDGE_RELEASE_ASSET_BASE_URL: &str =
+pub const WASM_EDGE_GIT_URL: &str = "https://github.com/WasmEdge/WasmEdge.git";
+pub const WASM_EDGE_RELEASE_ASSET_BASE_URL: &str =
     "https://github.com/WasmEdge/WasmEdge/releases/download";
-const CHECKSUM_FILE_NAME: &str = "SHA256SUM";
+/// The checksum file attached to each release, listing the SHA-256 of every archive.
+pub const CHECKSUM_FILE_NAME: &str = "SHA256SUM";
+/// Detached, ASCII-armored signature of [`CHECKSUM_FILE_NAME`], published next to it.
+pub const CHECKSUM_SIGNATURE_FILE_NAME: &str = "SHA256SUM.asc";
 const BUFFER_SIZE: usize = 8 * 1024; // 8KB
+/// Size from which [`sha256_hex_file`] overlaps reads with hashing
+pub const PIPELINED_HASH_THRESHOLD: u64 = 64 * 1024 * 1024; // 64MB
+const PIPELINE_CHUNK_SIZE: usize = 1024 * 1024; // 1MB
+const PIPELINE_DEPTH: usize = 4;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
 
 impl WasmEdgeApiClient {
-    fn http_client(&self) -> Result<Client> {
-        HttpClientConfig::new()
+    /// An HTTP client with this client's timeouts and User-Agent.
+    ///
+    /// It is built once and then shared, including by clones of this client, so requests to the
+    /// same host reuse pooled connections (negotiating HTTP/2 where the server offers it)
+    /// instead of each opening a new one.
+    ///
+    /// Fails with [`Error::Offline`] in offline mode, so no request slips through unchecked.
+    pub(crate) fn http_client(&self) -> Result<Client> {
+        self.require_network("a network request")?;
+        if let Some(client) = self.http.get() {
+            return Ok(client.clone());
+        }
+        let mut config = HttpClientConfig::new()
             .with_connect_timeout(self.connect_timeout)
             .with_request_timeout(self.request_timeout)
-            .build()
+            .with_user_agent(self.user_agent())
+            .with_danger_accept_invalid_certs(self.danger_insecure)
+            .with_no_cache(self.refresh);
+        if let Some(path) = &self.ca_cert {
+            config = config.with_ca_cert(path);
+        }
+        if let Some(proxy) = &self.proxy {
+            config = config.with_proxy(proxy.clone());
+        }
+        let client = config.build()?;
+        Ok(self.http.get_or_init(|| client).clone())
     }
 
-    pub fn releases(&self, filter: ReleasesFilter, num_releases: usize) -> Result<Vec<Version>> {
-        let releases = releases::get_all(WASM_EDGE_GIT_URL, filter)?;
-        Ok(releases.into_iter().take(num_releases).collect())
+    /// Fails with [`Error::Offline`] when `operation` is attempted in offline mode.
+    pub(crate) fn require_network(&self, operation: &'static str) -> Result<()> {
+        if self.offline {
+            return Err(Error::Offline { operation });
+        }
+        Ok(())
     }
 
-    pub fn latest_release(&self) -> Result<Version> {
-        let releases = releases::get_all(WASM_EDGE_GIT_URL, ReleasesFilter::Stable)?;
-        releases.into_iter().next().ok_or(Error::Unknown)
+    /// Drops the shared HTTP client after a setting it is built from changed.
+    fn reconfigured(mut self) -> Self {
+        self.http = Arc::default();
+        self
     }
 
-    pub fn resolve_version(&self, version: &str) -> Result<Version> {
-        if version == "latest" {
-            self.latest_release()
-        } else {
-            Version::parse(version).context(SemVerSnafu {})
-        }
+    /// The requests this client and its clones sent so far.
+    pub fn request_log(&self) -> &RequestLog {
+        &self.requests
     }
 
-    pub async fn download_asset(
-        &self,
-        asset: &Asset,
-        tmpdir: impl AsRef<Path>,
-        no_progress: bool,
-    ) -> Result<NamedTempFile> {
-        let url = asset.url()?;
-        tracing::debug!(%url, "Starting download for asset");
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT)
+    }
 
-        let client = self.http_client()?;
-        let response = client.get(url).send().await.context(RequestSnafu {
-            resource: "asset download",
-        })?;
+    /// Runs `attempt` until it succeeds, fails permanently, or the retry budget is spent.
+    ///
+    /// Each attempt is bounded by `attempt_timeout` (when set) and the whole operation,
+    /// retries and back-off included, by `request_timeout`. Only errors for which
+    /// [`Error::is_transient`] holds are retried.
+    async fn retrying<T, F, Fut>(&self, operation: &'static str, mut attempt: F) -> Result<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let run = async {
+            let mut attempt_num = 0;
+            loop {
+                attempt_num += 1;
+                let result = match self.attempt_timeout {
+                    Some(secs) => {
+                        tokio::time::timeout(Duration::from_secs(secs), attempt(attempt_num))
+                            .await
+                            .unwrap_or(Err(Error::AttemptTimeout { operation, secs }))
+                    }
+                    None => attempt(attempt_num).await,
+                };
 
-        let named = NamedTempFile::new_in(tmpdir)?;
-        let mut async_file = OpenOptions::new().write(true).open(named.path()).await?;
+                match result {
+                    Err(e) if e.is_transient() && attempt_num <= self.retries => {
+                        let delay = RETRY_BASE_DELAY
+                            .saturating_mul(1 << (attempt_num - 1).min(4))
+                            .min(RETRY_MAX_DELAY);
+                        tracing::warn!(
+                            error = %e,
+                            attempt = attempt_num,
+                            retries = self.retries,
+                            "{operation} failed, retrying in {}",
+                            Seconds(delay)
+                        );
+                        self.requests.retried();
+                        tokio::time::sleep(delay).await;
+                    }
+                    other => return other,
+                }
+            }
+        };
 
-        download_asset(no_progress, response, &mut async_file).await?;
-        drop(async_file);
+        tokio::time::timeout(Duration::from_secs(self.request_timeout), run)
+            .await
+            .unwrap_or(Err(Error::Timeout {
+                operation,
+                secs: self.request_timeout,
+            }))
+    }
 
-        Ok(named)
+    /// Lists releases, from the [listing cache](Self::with_listing_cache) while it is fresh.
+    async fn fetch_releases(&self, filter: ReleasesFilter) -> Result<Vec<Version>> {
+        self.fetch_releases_with(filter, true).await
     }
 
-    pub async fn get_release_checksum(&self, version: &Version, asset: &Asset) -> Result<String> {
-        let mut url = Url::parse(WASM_EDGE_RELEASE_ASSET_BASE_URL)
-            .expect("WASM_EDGE_RELEASE_ASSET_BASE_URL must be a valid URL");
+    /// Lists releases, reading the listing cache only if `cached`; a listing that is fetched
+    /// is stored in it either way, unless it is empty or `--refresh` is given.
+    async fn fetch_releases_with(
+        &self,
+        filter: ReleasesFilter,
+        cached: bool,
+    ) -> Result<Vec<Version>> {
+        self.require_network("release listing")?;
+        if cached {
+            if let Some(releases) = self.cached_releases(filter).await {
+                return Ok(releases);
+            }
+        }
+        let releases = self.fetch_releases_uncached(filter).await?;
+        if let (Some(cache), false) = (self.listing_cache(), releases.is_empty()) {
+            cache
+                .store(&self.listing_cache_key(filter), &releases)
+                .await;
+        }
+        Ok(releases)
+    }
+
+    /// The fresh cached listing for `filter`, if any.
+    async fn cached_releases(&self, filter: ReleasesFilter) -> Option<Vec<Version>> {
+        let cache = self.listing_cache()?;
+        let key = self.listing_cache_key(filter);
+        let releases = cache.lookup(&key).await?;
+        tracing::debug!(path = %cache.listing_path(&key).display(), "Using cached release listing");
+        Some(releases)
+    }
+
+    /// The listing cache, unless `--refresh` bypasses it.
+    fn listing_cache(&self) -> Option<&ListingCache> {
+        self.listing_cache.as_ref().filter(|_| !self.refresh)
+    }
 
-        url.path_segments_mut()
-            .expect("base is valid URL")
-            .extend(&[&version.to_string(), CHECKSUM_FILE_NAME]);
+    /// What a listing is cached under: everything that decides which releases it holds.
+    pub fn listing_cache_key(&self, filter: ReleasesFilter) -> String {
+        format!(
+            "{:?} {} {} {filter:?}",
+            self.resolve_via, self.releases_api_url, self.git_url
+        )
+    }
 
-        tracing::debug!(%url, CHECKSUM_FILE_NAME, "Trying checksum file");
+    async fn fetch_releases_uncached(&self, filter: ReleasesFilter) -> Result<Vec<Version>> {
+        match self.resolve_via {
+            ResolveVia::Api => self.fetch_releases_from_api(filter).await,
+            ResolveVia::Git => self.fetch_releases_from_git(filter).await,
+            ResolveVia::Auto => match self.fetch_releases_from_api(filter).await {
+                Ok(releases) => Ok(releases),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Listing releases via the GitHub API failed; falling back to git tags");
+                    self.fetch_releases_from_git(filter).await
+                }
+            },
+        }
+    }
 
+    async fn fetch_releases_from_api(&self, filter: ReleasesFilter) -> Result<Vec<Version>> {
         let client = self.http_client()?;
-        let response = client.get(url).send().await.context(RequestSnafu {
-            resource: "checksums",
-        })?;
+        self.retrying("release listing", |_| {
+            releases::get_all_from_api(
+                &client,
+                &self.requests,
+                &self.releases_api_url,
+                self.github_token.as_ref(),
+                filter,
+            )
+        })
+        .await
+    }
 
-        if !response.status().is_success() {
-            tracing::debug!(
-                status = %response.status(),
-                file = CHECKSUM_FILE_NAME,
-                "Checksum file not found"
-            );
-            return Err(Error::ChecksumNotFound {
+    async fn fetch_releases_from_git(&self, filter: ReleasesFilter) -> Result<Vec<Version>> {
+        self.retrying("release listing", |_| {
+            let git_url = self.git_url.clone();
+            let user_agent = self.user_agent().to_string();
+            let ca_cert = self.ca_cert.clone();
+            let danger_insecure = self.danger_insecure;
+            async move {
+                let url = git_url.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    releases::set_user_agent(&user_agent)?;
+                    if let Some(path) = &ca_cert {
+                        releases::set_ca_cert(path)?;
+                    }
+                    releases::get_all_with(&git_url, filter, danger_insecure)
+                })
+                .await
+                .map_err(|_| Error::Unknown)?;
+                let outcome = match &result {
+                    Ok(_) => "ok".to_string(),
+                    Err(e) => format!("error: {e}"),
+                };
+                self.requests.record("git ls-remote", url, outcome);
+                result
+            }
+        })
+        .await
+    }
+
+    /// Checks the git tag of `version` with [`releases::verify_tag`]: that it is annotated and,
+    /// with a `key`, that its signature was made with that key.
+    pub async fn verify_release_tag(&self, version: &Version, key: Option<&Path>) -> Result<()> {
+        self.require_network("release tag verification")?;
+        let git_url = self.git_url.clone();
+        let user_agent = self.user_agent().to_string();
+        let ca_cert = self.ca_cert.clone();
+        let danger_insecure = self.danger_insecure;
+        let tag = version.to_string();
+        let key = key.map(Path::to_path_buf);
+        tokio::task::spawn_blocking(move || {
+            releases::set_user_agent(&user_agent)?;
+            if let Some(path) = &ca_cert {
+                releases::set_ca_cert(path)?;
+            }
+            releases::verify_tag(&git_url, &tag, key.as_deref(), danger_insecure)
+        })
+        .await
+        .map_err(|_| Error::Unknown)?
+    }
+
+    pub async fn releases(
+        &self,
+        filter: ReleasesFilter,
+        num_releases: usize,
+    ) -> Result<Vec<Version>> {
+        let releases = self.fetch_releases(filter).await?;
+        Ok(releases.into_iter().take(num_releases).collect())
+    }
+
+    /// Returns the newest stable release.
+    ///
+    /// The lookup is retried and bounded like a download (see [`Self::retrying`]) and is
+    /// abandoned with [`Error::Interrupted`] on Ctrl-C. Any other failure is reported as
+    /// [`Error::LatestVersionUnresolved`].
+    pub async fn latest_release(&self) -> Result<Version> {
+        let releases = self.fetch_stable_releases().await?;
+        releases.into_iter().next().ok_or(Error::VersionNotFound {
+            version: "latest".to_string(),
+        })
+    }
+
+    /// Lists stable releases for resolving a channel, cancellable with Ctrl-C.
+    async fn fetch_stable_releases(&self) -> Result<Vec<Version>> {
+        // Left unwrapped, so the offline hint is not followed by a second one.
+        self.require_network("release listing")?;
+        let releases = tokio::select! {
+            releases = self.fetch_releases(ReleasesFilter::Stable) => releases,
+            _ = tokio::signal::ctrl_c() => return Err(Error::Interrupted),
+        };
+        releases.map_err(|e| Error::LatestVersionUnresolved {
+            source: Box::new(e),
+        })
+    }
+
+    /// Resolves a version specifier: a channel name such as `latest` or `lts` (see
+    /// [`channels::CHANNELS`]), a partial version such as `0.14` (see [`partial_version_req`])
+    /// or an exact semantic version.
+    pub async fn resolve_version(&self, version: &str) -> Result<Version> {
+        if let Some(channel) = Channel::find(version) {
+            let releases = self.fetch_stable_releases().await?;
+            return channel.select(&releases);
+        }
+        if let Some(req) = partial_version_req(version) {
+            let releases = self.fetch_stable_releases().await?;
+            return newest_matching(&releases, &req).ok_or_else(|| Error::VersionNotFound {
                 version: version.to_string(),
-                asset: asset.archive_name.clone(),
             });
         }
 
-        let content = response.text().await.context(RequestSnafu {
-            resource: "checksums",
-        })?;
+        match Version::parse(version) {
+            Ok(version) => Ok(version),
+            // Anything that does not even start like a version was meant as a channel name.
+            Err(_)
+                if version.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && !version.contains('.') =>
+            {
+                Err(Error::UnknownChannel {
+                    name: version.to_string(),
+                    valid: channels::channel_names(),
+                })
+            }
+            Err(source) => Err(Error::SemVer { source }),
+        }
+    }
 
-        tracing::debug!(
-            lines = content.lines().count(),
-            file = CHECKSUM_FILE_NAME,
-            "Got checksum file content"
-        );
+    /// Fails with [`Error::VersionNotPublished`] when the release listing lacks `version`,
+    /// so that a mistyped exact version is reported before any download 404s.
+    ///
+    /// A listing that cannot be fetched skips the check, leaving the download to tell whether
+    /// the release exists; that is only logged, so offline mirrors keep working. In offline
+    /// mode the check is skipped.
+    pub async fn ensure_published(&self, version: &Version) -> Result<()> {
+        if self.offline {
+            tracing::debug!(%version, "Offline; not checking that the version is published");
+            return Ok(());
+        }
+        // A release published since the listing was cached is not in it yet, so a cached
+        // listing can only confirm the version, while a fetched one can also rule it out.
+        let cached = self.cached_releases(ReleasesFilter::All).await;
+        if cached.is_some_and(|releases| releases.contains(version)) {
+            return Ok(());
+        }
+        let releases = tokio::select! {
+            releases = self.fetch_releases_with(ReleasesFilter::All, false) => releases,
+            _ = tokio::signal::ctrl_c() => return Err(Error::Interrupted),
+        };
+        let releases = match releases {
+            Ok(releases) if !releases.is_empty() => releases,
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                tracing::debug!(error = %e, %version, "Could not list releases; not checking that the version is published");
+                return Ok(());
+            }
+        };
+        if releases.contains(version) {
+            return Ok(());
+        }
+        Err(Error::VersionNotPublished {
+            version: version.to_string(),
+            close: close_versions(version, &releases),
+        })
+    }
+
+    /// URL [`Self::download_asset`] fetches `asset` from.
+    pub fn asset_url(&self, asset: &Asset) -> Result<Url> {
+        asset.download_url(&self.release_base_url)
+    }
 
-        for (i, line) in content.lines().enumerate() {
-            tracing::debug!(line_num = i, line = line, "Processing checksum line");
+    /// URL of the checksum file [`Self::get_release_checksum`] reads for `version`.
+    pub fn checksum_url(&self, version: &Version) -> Result<Url> {
+        release_file_url(&self.release_base_url, version, CHECKSUM_FILE_NAME)
+    }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() == 2 {
-                tracing::debug!(checksum = parts[0], file = parts[1], "Found checksum entry");
+    /// URL of the detached signature of the checksum file of `version`.
+    pub fn checksum_signature_url(&self, version: &Version) -> Result<Url> {
+        release_file_url(
+            &self.release_base_url,
+            version,
+            CHECKSUM_SIGNATURE_FILE_NAME,
+        )
+    }
 
-                if parts[1] == asset.archive_name {
-                    tracing::debug!(checksum = parts[0], "Found matching checksum");
-                    return Ok(parts[0].to_string());
-                }
-            }
+    pub async fn download_asset(
+        &self,
+        asset: &Asset,
+        tmpdir: impl AsRef<Path>,
+        no_progress: bool,
+    ) -> Result<NamedTempFile> {
+        let url = self.asset_url(asset)?;
+        self.download_from(&url, tmpdir, no_progress).await
+    }
+
+    /// Downloads `url` into a temporary file in `tmpdir`, retrying like [`Self::download_asset`].
+    ///
+    /// A `file://` URL is copied from the local file system instead, also in offline mode.
+    pub async fn download_from(
+        &self,
+        url: &Url,
+        tmpdir: impl AsRef<Path>,
+        no_progress: bool,
+    ) -> Result<NamedTempFile> {
+        let named = NamedTempFile::new_in(tmpdir)?;
+        if url.scheme() == "file" {
+            let source = url.to_file_path().map_err(|()| Error::Io {
+                action: "read local archive".to_string(),
+                path: url.to_string(),
+                source: std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "not a local file path",
+                ),
+            })?;
+            tracing::debug!(path = %source.display(), "Copying local archive");
+            tokio::fs::copy(&source, named.path())
+                .await
+                .context(IoSnafu {
+                    action: "copy local archive".to_string(),
+                    path: source.display().to_string(),
+                })?;
+            return Ok(named);
         }
+        self.require_network("asset download")?;
+        tracing::debug!(%url, "Starting download for asset");
 
-        tracing::error!(
-            version = %version,
-            asset = %asset.archive_name,
-            "No checksum found in any file"
-        );
+        let client = self.http_client()?;
+        let (client, log, path) = (&client, self.request_log(), named.path());
+        let progress = (!no_progress || self.progress_events).then(|| DownloadProgress {
+            multi: self.progress.as_ref(),
+            label: url
+                .path_segments()
+                .and_then(|mut s| s.next_back())
+                .unwrap_or_default(),
+            draw: !self.progress_events && can_draw_progress_bars(),
+            events: self.progress_events,
+        });
+        let progress = progress.as_ref();
 
-        Err(Error::ChecksumNotFound {
-            version: version.to_string(),
-            asset: asset.archive_name.clone(),
+        // Fails fast on a missing asset, before a temporary file is written to.
+        let probe = self
+            .retrying("asset check", |_| probe_asset(client, log, url))
+            .await?;
+        let probe = &probe;
+
+        // Each retry resumes from whatever the previous attempts left in the temporary file.
+        self.retrying("asset download", |attempt| async move {
+            let resume_from = if attempt > 1 && !probe.ranges_refused {
+                tokio::fs::metadata(path)
+                    .await
+                    .map(|m| m.len())
+                    .unwrap_or(0)
+            } else {
+                0
+            };
+
+            let (response, offset) = request_asset(client, log, url, resume_from).await?;
+            let mut async_file = if offset > 0 {
+                OpenOptions::new().append(true).open(path).await?
+            } else {
+                OpenOptions::new()
+                    .write(true)
+                    .truncate(true)
+                    .open(path)
+                    .await?
+            };
+            download_asset(progress, response, &mut async_file, offset, probe.length).await
         })
+        .await?;
+
+        Ok(named)
     }
 
-    pub async fn verify_file_checksum(file: &mut std::fs::File, expected: &str) -> Result<()> {
-        let mut hasher = Sha256::new();
-        let mut buffer = vec![0; BUFFER_SIZE];
+    /// The checksum release `version` publishes for `asset`; see [`Published`].
+    pub async fn get_release_checksum(&self, version: &Version, asset: &Asset) -> Result<String> {
+        Published.checksum(self, version, asset).await
+    }
 
-        loop {
-            let count = file.read(&mut buffer)?;
-            if count == 0 {
-                break;
-            }
-            hasher.update(&buffer[..count]);
+    /// Where an install reads the expected checksum from: `expected` when given (e.g.
+    /// `--expected-checksum` or a lockfile), the release's published checksums otherwise.
+    pub fn checksum_source(&self, expected: Option<&str>) -> SelectedSource {
+        match expected {
+            Some(checksum) => SelectedSource::Explicit(Explicit::new(checksum)),
+            None => SelectedSource::Published(Published),
         }
+    }
 
-        let actual = hex::encode(hasher.finalize());
-        if actual != expected {
+    pub async fn verify_file_checksum(file: &mut std::fs::File, expected: &str) -> Result<()> {
+        let actual = sha256_hex_file(file)?;
+        if !actual.eq_ignore_ascii_case(expected) {
             return Err(Error::ChecksumMismatch {
                 expected: expected.to_string(),
                 actual,
@@ -181,16 +582,147 @@ impl WasmEdgeApiClient {
         Self {
             connect_timeout: 15, // 15 seconds for connection
             request_timeout: 90, // 90 seconds for request
+            attempt_timeout: None,
+            retries: 3,
+            repo: RepoSlug::default(),
+            git_url: WASM_EDGE_GIT_URL.to_string(),
+            releases_api_url: RepoSlug::default().releases_api_url(),
+            resolve_via: ResolveVia::default(),
+            release_base_url: WASM_EDGE_RELEASE_ASSET_BASE_URL.to_string(),
+            user_agent: None,
+            ca_cert: None,
+            danger_insecure: false,
+            progress: None,
+            progress_events: false,
+            checksums_key: None,
+            refresh: false,
+            listing_cache: None,
+            github_token: None,
+            proxy: None,
+            offline: false,
+            http: Arc::default(),
+            requests: Arc::default(),
         }
     }
 
     pub fn with_connect_timeout(mut self, timeout: u64) -> Self {
         self.connect_timeout = timeout;
-        self
+        self.reconfigured()
     }
 
     pub fn with_request_timeout(mut self, timeout: u64) -> Self {
         self.request_timeout = timeout;
+        self.reconfigured()
+    }
+
+    pub fn with_attempt_timeout(mut self, timeout: u64) -> Self {
+        self.attempt_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sources releases, assets and checksums from `repo` instead of the canonical repository.
+    pub fn with_repo(mut self, repo: RepoSlug) -> Self {
+        self.git_url = repo.git_url();
+        self.releases_api_url = repo.releases_api_url();
+        self.release_base_url = repo.release_base_url();
+        self.repo = repo;
+        self
+    }
+
+    pub fn with_git_url(mut self, url: impl Into<String>) -> Self {
+        self.git_url = url.into();
+        self
+    }
+
+    pub fn with_releases_api_url(mut self, url: impl Into<String>) -> Self {
+        self.releases_api_url = url.into();
+        self
+    }
+
+    pub fn with_resolve_via(mut self, resolve_via: ResolveVia) -> Self {
+        self.resolve_via = resolve_via;
+        self
+    }
+
+    pub fn with_release_base_url(mut self, url: impl Into<String>) -> Self {
+        self.release_base_url = url.into();
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self.reconfigured()
+    }
+
+    pub fn with_ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_cert = Some(path.into());
+        self.reconfigured()
+    }
+
+    pub fn with_danger_insecure(mut self, insecure: bool) -> Self {
+        self.danger_insecure = insecure;
+        self.reconfigured()
+    }
+
+    /// Shows download progress in `progress`, one labelled bar per download, instead of a
+    /// standalone bar.
+    pub fn with_progress(mut self, progress: MultiProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Reports download progress as `--json-lines` `download` events, also when progress
+    /// output is otherwise disabled.
+    pub fn with_progress_events(mut self, events: bool) -> Self {
+        self.progress_events = events;
+        self
+    }
+
+    /// Bypasses the download cache and asks servers and proxies for fresh responses.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self.reconfigured()
+    }
+
+    /// Authenticates GitHub REST API requests with `token`, e.g. `GITHUB_TOKEN`.
+    pub fn with_github_token(mut self, token: impl Into<String>) -> Self {
+        self.github_token = Some(Secret::new(token));
+        self
+    }
+
+    /// Connects through `proxy` instead of the proxies of the process environment.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self.reconfigured()
+    }
+
+    /// Logs the metadata of every request and response, with credentials redacted; see
+    /// [`RequestLog::with_verbose`]. Starts a new request log, so call it before sending any.
+    pub fn with_verbose_http(mut self, verbose: bool) -> Self {
+        self.requests = Arc::new(RequestLog::default().with_verbose(verbose));
+        self
+    }
+
+    /// Reads release listings from `cache` while they are fresh, and stores those it fetches.
+    pub fn with_listing_cache(mut self, cache: ListingCache) -> Self {
+        self.listing_cache = Some(cache);
+        self
+    }
+
+    /// Refuses network access, so that only the download cache and local files are used.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Requires the checksum file to carry a detached signature made with `key`.
+    pub fn with_checksums_key(mut self, key: impl Into<PathBuf>) -> Self {
+        self.checksums_key = Some(key.into());
         self
     }
 }
@@ -201,67 +733,407 @@ impl Default for WasmEdgeApiClient {
     }
 }
 
-#[tracing::instrument(level = tracing::Level::DEBUG, skip(response, target_file), fields(size = response.content_length()))]
+/// Requests `url`, asking for the bytes from `resume_from` onwards when it is non-zero.
+///
+/// Returns the response together with the offset its body starts at. The offset falls back to
+/// zero, i.e. a full download, when the server ignores the range, answers with a different
+/// one, or rejects it.
+///
+/// Resumes always request `url` itself rather than where it redirected to: GitHub's assets
+/// redirect to presigned CDN URLs that expire, and one reused after that is answered with a
+/// `403`. Following the redirect again gets a fresh one for the `Range` request.
+async fn request_asset(
+    client: &Client,
+    log: &RequestLog,
+    url: &Url,
+    resume_from: u64,
+) -> Result<(Response, u64)> {
+    let send = |range: Option<u64>| {
+        let mut request = client.get(url.clone());
+        if let Some(start) = range {
+            request = request.header(RANGE, format!("bytes={start}-"));
+        }
+        async move {
+            log.send(request).await.context(RequestSnafu {
+                resource: "asset download",
+            })
+        }
+    };
+
+    if resume_from > 0 {
+        tracing::debug!(resume_from, "Resuming partial download");
+        let response = send(Some(resume_from)).await?;
+        let status = response.status();
+        if status == StatusCode::PARTIAL_CONTENT
+            && content_range_start(&response) == Some(resume_from)
+        {
+            return Ok((response, resume_from));
+        }
+        if status.is_success() && status != StatusCode::PARTIAL_CONTENT {
+            tracing::debug!(%status, "Server does not support range requests; restarting download");
+            let response = response.error_for_status().context(RequestSnafu {
+                resource: "asset download",
+            })?;
+            return Ok((response, 0));
+        }
+        tracing::debug!(%status, "Unable to resume download; restarting");
+    }
+
+    let response = send(None).await?.error_for_status().context(RequestSnafu {
+        resource: "asset download",
+    })?;
+    Ok((response, 0))
+}
+
+/// What a `HEAD` request told about an asset before downloading it.
+#[derive(Debug, Clone, Copy, Default)]
+struct AssetProbe {
+    /// `Content-Length` of the whole asset, if the server gave one
+    length: Option<u64>,
+    /// Whether the server said `Accept-Ranges: none`, in which case a download that broke off
+    /// starts over rather than asking to resume
+    ranges_refused: bool,
+}
+
+/// Checks with a `HEAD` request that `url` exists, failing with [`Error::AssetNotFound`] on a
+/// 404 and with a transient error on a server error.
+///
+/// Servers that do not answer `HEAD` otherwise (e.g. a `405`) are left to the download itself.
+async fn probe_asset(client: &Client, log: &RequestLog, url: &Url) -> Result<AssetProbe> {
+    let response = log
+        .send(client.head(url.clone()))
+        .await
+        .context(RequestSnafu {
+            resource: "asset check",
+        })?;
+    let status = response.status();
+    if matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE) {
+        return Err(Error::AssetNotFound {
+            url: url.to_string(),
+        });
+    }
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(response.error_for_status().unwrap_err()).context(RequestSnafu {
+            resource: "asset check",
+        });
+    }
+    if !status.is_success() {
+        tracing::debug!(%status, "Asset check not answered; downloading without it");
+        return Ok(AssetProbe::default());
+    }
+
+    // Read the header itself: for a HEAD response the body, and so `content_length()`, is empty.
+    let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok());
+    let probe = AssetProbe {
+        length: header(CONTENT_LENGTH).and_then(|v| v.trim().parse().ok()),
+        ranges_refused: header(ACCEPT_RANGES)
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("none")),
+    };
+    tracing::debug!(
+        length = probe.length,
+        ranges_refused = probe.ranges_refused,
+        "Asset is available"
+    );
+    Ok(probe)
+}
+
+/// The first byte position of a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_start(response: &Response) -> Option<u64> {
+    let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// Where a download shows its progress bar.
+#[derive(Debug, Clone, Copy)]
+struct DownloadProgress<'a> {
+    /// Shared display of concurrent downloads; the bar is drawn on its own when `None`
+    multi: Option<&'a MultiProgress>,
+    /// Shown in front of the bar in a shared display, e.g. the archive name
+    label: &'a str,
+    /// Whether bars can be drawn; otherwise plain progress lines are printed instead
+    draw: bool,
+    /// Print `download` events every percent instead of bars or lines
+    events: bool,
+}
+
+/// Whether progress bars can be drawn on stderr, where indicatif draws them.
+///
+/// Redirected output (e.g. CI logs) and `TERM=dumb` terminals cannot redraw a line in place
+/// and would collect every frame of the bar.
+fn can_draw_progress_bars() -> bool {
+    use std::io::IsTerminal;
+
+    std::io::stderr().is_terminal() && std::env::var_os("TERM").is_none_or(|term| term != "dumb")
+}
+
+/// Progress of a download that cannot draw a bar, reported as a plain line every quarter.
+#[derive(Debug)]
+struct PlainProgress<'a> {
+    label: &'a str,
+    total: u64,
+    done: u64,
+    /// Percentage points between reports
+    step: u64,
+    /// Last percentage reported, a multiple of `step`
+    reported: u64,
+}
+
+impl<'a> PlainProgress<'a> {
+    const STEP: u64 = 25;
+
+    fn new(label: &'a str, done: u64, total: u64) -> Self {
+        Self::with_step(label, done, total, Self::STEP)
+    }
+
+    fn with_step(label: &'a str, done: u64, total: u64, step: u64) -> Self {
+        let mut progress = Self {
+            label,
+            total,
+            done,
+            step,
+            reported: 0,
+        };
+        progress.reported = progress.percent() / step * step;
+        progress
+    }
+
+    fn percent(&self) -> u64 {
+        (self.done.min(self.total) * 100)
+            .checked_div(self.total)
+            .unwrap_or(100)
+    }
+
+    /// Adds `bytes` and returns the line to print, if another step was reached.
+    fn advance(&mut self, bytes: u64) -> Option<String> {
+        self.advance_percent(bytes)
+            .map(|step| format!("{}: downloaded {step}%", self.label))
+    }
+
+    /// Adds `bytes` and returns the percentage reached, if another step was reached.
+    fn advance_percent(&mut self, bytes: u64) -> Option<u64> {
+        self.done += bytes;
+        let step = self.percent() / self.step * self.step;
+        (step > self.reported).then(|| {
+            self.reported = step;
+            step
+        })
+    }
+
+    /// The event reporting `pct` percent done.
+    fn event(&self, pct: u64) -> ProgressEvent<'a> {
+        ProgressEvent::Download {
+            asset: self.label,
+            pct,
+            downloaded: self.done.min(self.total),
+            total: self.total,
+        }
+    }
+}
+
+/// Streams `response` into `target_file`, whose first `offset` bytes were written earlier.
+///
+/// `total` is the size of the whole asset as far as known beforehand, used when the response
+/// itself does not say how long it is.
+///
+/// No progress is shown when `progress` is `None`, and plain lines such as
+/// `WasmEdge-0.14.1-Linux.tar.gz: downloaded 50%` are printed where bars cannot be drawn.
+#[tracing::instrument(level = tracing::Level::DEBUG, skip_all, fields(size = response.content_length()))]
 async fn download_asset(
-    no_progress: bool,
+    progress: Option<&DownloadProgress<'_>>,
     mut response: Response,
     target_file: &mut File,
+    offset: u64,
+    total: Option<u64>,
 ) -> Result<()> {
-    let content_length = response.content_length().unwrap_or(0);
+    let content_length = response
+        .content_length()
+        .or_else(|| total.map(|total| total.saturating_sub(offset)))
+        .unwrap_or(0);
 
-    let pb = if !no_progress && content_length > 0 {
-        Some(download_progress_bar(
-            response.content_length().unwrap_or_default(),
-        ))
-    } else {
-        None
+    let pb = match progress {
+        Some(progress) if progress.draw && content_length > 0 => {
+            let pb = download_progress_bar(offset + content_length);
+            pb.set_position(offset);
+            Some(match progress.multi {
+                Some(multi) => {
+                    pb.set_prefix(format!("{} ", progress.label));
+                    multi.add(pb)
+                }
+                None => pb,
+            })
+        }
+        _ => None,
     };
+    let mut plain = progress
+        .filter(|progress| !progress.draw && content_length > 0)
+        .map(|progress| {
+            let total = offset + content_length;
+            if progress.events {
+                PlainProgress::with_step(progress.label, offset, total, 1)
+            } else {
+                PlainProgress::new(progress.label, offset, total)
+            }
+        });
+    let events = progress.is_some_and(|progress| progress.events);
 
-    while let Some(mut chunk) = response
-        .chunk()
-        .await
-        .context(RequestSnafu { resource: "chunk" })?
-    {
-        if let Some(ref pb) = pb {
-            pb.inc(chunk.len() as u64)
+    let mut written: u64 = 0;
+    let streamed = async {
+        while let Some(mut chunk) = response
+            .chunk()
+            .await
+            .context(RequestSnafu { resource: "chunk" })?
+        {
+            if let Some(ref pb) = pb {
+                pb.inc(chunk.len() as u64)
+            }
+            let bytes = chunk.len() as u64;
+            if let (true, Some(plain)) = (events, plain.as_mut()) {
+                if let Some(pct) = plain.advance_percent(bytes) {
+                    crate::events::emit(&plain.event(pct));
+                }
+            } else if let Some(line) = plain.as_mut().and_then(|p| p.advance(bytes)) {
+                eprintln!("{line}");
+            }
+            written += chunk.len() as u64;
+            target_file.write_buf(&mut chunk).await?;
         }
-        target_file.write_buf(&mut chunk).await?;
+        Ok::<_, Error>(())
     }
+    .await;
 
+    // Flush even when the transfer broke off, so a retry can resume after the bytes we got.
     target_file.flush().await?;
 
     if let Some(ref pb) = pb {
         pb.finish_and_clear();
     }
+    streamed?;
+
+    // A connection dropped at EOF without a transport error still ends the chunk stream,
+    // so compare against the advertised length to tell truncation apart from corruption.
+    if content_length > 0 && written != content_length {
+        tracing::debug!(
+            expected = content_length,
+            actual = written,
+            "Downloaded size does not match Content-Length"
+        );
+        return Err(Error::IncompleteDownload {
+            expected: offset + content_length,
+            actual: offset + written,
+        });
+    }
 
     Ok(())
 }
 
-#[derive(Debug, Clone)]
+/// Whether a release build links its dependencies dynamically or statically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Linkage {
+    #[default]
+    Dynamic,
+    Static,
+}
+
+/// A release archive of the WasmEdge runtime: what `install` downloads for a version and
+/// target, and the name of the directory it unpacks into.
+///
+/// The names follow the WasmEdge release conventions, including the manylinux and Ubuntu
+/// variants of older releases, so packaging tools can compute the exact files the installer
+/// would fetch without reimplementing them. A name computed for a given version, OS,
+/// architecture and linkage only changes with a new major version of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Asset {
     pub version: Version,
+    /// The file attached to the release, e.g. `WasmEdge-0.14.1-manylinux_2_28_x86_64.tar.gz`
     pub archive_name: String,
+    /// The directory the archive wraps its files in, e.g. `WasmEdge-0.14.1-Linux`, which also
+    /// names the staging directory of an install
     pub install_name: String,
+    pub linkage: Linkage,
 }
 
 impl Asset {
+    /// The dynamically-linked build of `version` for `os` and `arch`.
     pub fn new(version: &Version, os: &TargetOS, arch: &TargetArch) -> Self {
         Self {
             version: version.clone(),
             archive_name: Self::format_archive_name(version, os, arch),
             install_name: Self::format_install_name(version, os),
+            linkage: Linkage::Dynamic,
+        }
+    }
+
+    /// The statically-linked variant of [`Asset::new`], or `None` where the release line
+    /// publishes none. Static builds exist for Linux only and do not depend on the distro, so
+    /// Ubuntu maps to the same manylinux archive.
+    pub fn new_static(version: &Version, os: &TargetOS, arch: &TargetArch) -> Option<Self> {
+        if !matches!(os, TargetOS::Linux | TargetOS::Ubuntu) {
+            return None;
+        }
+        let dynamic = Self::format_archive_name(version, &TargetOS::Linux, arch);
+        let stem = dynamic.strip_suffix(".tar.gz")?;
+        Some(Self {
+            version: version.clone(),
+            archive_name: format!("{stem}_static.tar.gz"),
+            install_name: Self::format_install_name(version, os),
+            linkage: Linkage::Static,
+        })
+    }
+
+    /// [`Asset::new`] or [`Asset::new_static`], by `linkage`.
+    pub fn with_linkage(
+        version: &Version,
+        os: &TargetOS,
+        arch: &TargetArch,
+        linkage: Linkage,
+    ) -> Option<Self> {
+        match linkage {
+            Linkage::Dynamic => Some(Self::new(version, os, arch)),
+            Linkage::Static => Self::new_static(version, os, arch),
+        }
+    }
+
+    /// The archive of plugin `name` at `version` for `platform_key` (see
+    /// [`plugin_platform_key`](crate::system::plugins::plugin_platform_key)), e.g.
+    /// `WasmEdge-plugin-wasi_logging-0.14.1-manylinux_2_28_x86_64.tar.gz`. It is listed in the
+    /// release's checksum file like the runtime archives, and its staging directory is named
+    /// `WasmEdge-plugin-<name>-<version>`.
+    pub fn plugin(version: &Version, name: &str, platform_key: &str, os: &TargetOS) -> Self {
+        let ext = match os {
+            TargetOS::Windows => "zip",
+            _ => "tar.gz",
+        };
+        Self {
+            version: version.clone(),
+            archive_name: format!("WasmEdge-plugin-{name}-{version}-{platform_key}.{ext}"),
+            install_name: format!("WasmEdge-plugin-{name}-{version}"),
+            linkage: Linkage::Dynamic,
         }
     }
 
+    /// The archive's URL on the official WasmEdge releases.
     pub fn url(&self) -> Result<Url> {
-        let mut url = Url::parse(WASM_EDGE_RELEASE_ASSET_BASE_URL)
-            .expect("WASM_EDGE_RELEASE_ASSET_BASE_URL must be a valid URL");
+        self.download_url(WASM_EDGE_RELEASE_ASSET_BASE_URL)
+    }
 
-        url.path_segments_mut()
-            .expect("base is valid URL")
-            .extend(&[&self.version.to_string(), &self.archive_name]);
+    /// The archive's URL under `base`, which holds one directory per version as the GitHub
+    /// release downloads and `--repo`/`WASMEDGEUP_MIRROR` mirrors do, e.g.
+    /// `https://github.com/WasmEdge/WasmEdge/releases/download`.
+    pub fn download_url(&self, base: &str) -> Result<Url> {
+        release_file_url(base, &self.version, &self.archive_name)
+    }
 
-        Ok(url)
+    /// The URL under `base` of the checksum file listing this archive.
+    pub fn checksum_url(&self, base: &str) -> Result<Url> {
+        release_file_url(base, &self.version, CHECKSUM_FILE_NAME)
+    }
+
+    /// The URL under `base` of the detached signature of [`Asset::checksum_url`].
+    pub fn checksum_signature_url(&self, base: &str) -> Result<Url> {
+        release_file_url(base, &self.version, CHECKSUM_SIGNATURE_FILE_NAME)
     }
 
     fn format_archive_name(version: &Version, os: &TargetOS, arch: &TargetArch) -> String {
@@ -310,6 +1182,80 @@ impl Asset {
     }
 }
 
+/// Hex-encoded SHA-256 digest of everything read from `reader`.
+pub fn sha256_hex(reader: &mut impl Read) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    loop {
+        let count = reader.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[..count]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hex-encoded SHA-256 digest of `file` from its current position.
+///
+/// Files of at least [`PIPELINED_HASH_THRESHOLD`] bytes are hashed with
+/// [`sha256_hex_pipelined`], smaller ones with [`sha256_hex`].
+pub fn sha256_hex_file(file: &mut std::fs::File) -> Result<String> {
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len >= PIPELINED_HASH_THRESHOLD {
+        tracing::debug!(len, "Hashing large file with pipelined reads");
+        sha256_hex_pipelined(file)
+    } else {
+        sha256_hex(file)
+    }
+}
+
+/// Like [`sha256_hex`], but reads on a separate thread so disk I/O overlaps with hashing.
+///
+/// SHA-256 itself is sequential, and release checksums are plain SHA-256, so the digest is
+/// still computed in order; only waiting on reads is taken off the critical path.
+pub fn sha256_hex_pipelined(reader: &mut (impl Read + Send)) -> Result<String> {
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Vec<u8>>(PIPELINE_DEPTH);
+
+    std::thread::scope(|scope| {
+        let producer = scope.spawn(move || -> std::io::Result<()> {
+            loop {
+                let mut chunk = vec![0; PIPELINE_CHUNK_SIZE];
+                let count = reader.read(&mut chunk)?;
+                if count == 0 {
+                    return Ok(());
+                }
+                chunk.truncate(count);
+                if tx.send(chunk).is_err() {
+                    return Ok(());
+                }
+            }
+        });
+
+        let mut hasher = Sha256::new();
+        for chunk in rx {
+            hasher.update(&chunk);
+        }
+        producer.join().map_err(|_| Error::Unknown)??;
+
+        Ok(hex::encode(hasher.finalize()))
+    })
+}
+
+/// Builds `<base>/<version>/<file_name>`, the download URL of a file attached to a release.
+fn release_file_url(base: &str, version: &Version, file_name: &str) -> Result<Url> {
+    let mut url = Url::parse(base).context(UrlSnafu)?;
+    url.path_segments_mut()
+        .map_err(|_| Error::Url {
+            source: url::ParseError::RelativeUrlWithCannotBeABaseBase,
+        })?
+        .pop_if_empty()
+        .extend(&[&version.to_string(), file_name]);
+    Ok(url)
+}
+
 static MANYLINUX2014_SUPPORTED_VERSIONS: OnceLock<VersionReq> = OnceLock::new();
 
 fn is_manylinux2014_supported(version: &Version) -> bool {
@@ -323,23 +1269,38 @@ fn is_manylinux2014_supported(version: &Version) -> bool {
         }],
     });
 
-    req.matches(version)
+    req.matches(&release_of(version))
 }
 
 fn is_arm_ubuntu_supported(version: &Version) -> bool {
     // ARM-based Ubuntu 20.04 is supported after 0.13.5
-    version >= &Version::new(0, 13, 5)
+    release_of(version) >= Version::new(0, 13, 5)
+}
+
+/// `version` without prerelease and build metadata, for deciding which asset naming applies.
+///
+/// Prereleases are published with the naming of the release they lead up to, but a
+/// `VersionReq` never matches a prerelease, so `0.14.1-rc.1` would otherwise fall out of
+/// `<=0.14` and be looked up under the wrong archive name.
+fn release_of(version: &Version) -> Version {
+    Version::new(version.major, version.minor, version.patch)
 }
 
 fn download_progress_bar(size: u64) -> ProgressBar {
     let pb = ProgressBar::new(size);
     pb.set_style(
         ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})",
+            "{prefix}{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})",
         )
         .expect("progress bar template is valid")
+        .with_key("bytes", |state: &ProgressState, w: &mut dyn Write| {
+            let _ = write!(w, "{}", ByteSize(state.pos()));
+        })
+        .with_key("total_bytes", |state: &ProgressState, w: &mut dyn Write| {
+            let _ = write!(w, "{}", ByteSize(state.len().unwrap_or(0)));
+        })
         .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
-            let _ = write!(w, "{:.1}s", state.eta().as_secs_f64());
+            let _ = write!(w, "{}", Seconds(state.eta()));
         })
         .progress_chars("#>-"),
     );
@@ -347,9 +1308,150 @@ fn download_progress_bar(size: u64) -> ProgressBar {
     pb
 }
 
+/// The release line a partial version names: `0.14` is `~0.14` (newest `0.14.x`) and `0` is
+/// `^0`. Anything else, including a full `0.14.1`, is not partial and yields `None`.
+pub fn partial_version_req(version: &str) -> Option<VersionReq> {
+    let parts = version.split('.').collect::<Vec<_>>();
+    if parts.len() > 2
+        || parts
+            .iter()
+            .any(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    let operator = if parts.len() == 2 { '~' } else { '^' };
+    VersionReq::parse(&format!("{operator}{version}")).ok()
+}
+
+/// The newest stable version in `versions` that `req` matches.
+pub fn newest_matching(versions: &[Version], req: &VersionReq) -> Option<Version> {
+    versions
+        .iter()
+        .filter(|v| v.pre.is_empty() && req.matches(v))
+        .max()
+        .cloned()
+}
+
+/// Up to three versions in `versions` that `version` is a likely typo of, i.e. at most two
+/// character edits away from it, closest and then newest first.
+pub fn close_versions(version: &Version, versions: &[Version]) -> Vec<String> {
+    let wanted = version.to_string();
+    let mut close: Vec<(usize, &Version)> = versions
+        .iter()
+        .map(|v| (edit_distance(&wanted, &v.to_string()), v))
+        .filter(|(distance, _)| *distance <= 2)
+        .collect();
+    close.sort_by(|(d1, v1), (d2, v2)| d1.cmp(d2).then_with(|| v2.cmp(v1)));
+    close
+        .into_iter()
+        .take(3)
+        .map(|(_, v)| v.to_string())
+        .collect()
+}
+
+/// An inclusive end of a version window such as `list --remote --since 0.13.0`: an exact
+/// version, or a partial one such as `0.14` that stands for its whole release line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionBound {
+    /// The bound with missing components as zero
+    version: Version,
+    /// How many of `major.minor.patch` were given; 3 for an exact version
+    components: usize,
+}
+
+impl VersionBound {
+    /// The lowest version the bound covers, e.g. `0.14.0` for `0.14`.
+    pub fn lowest(&self) -> Version {
+        self.version.clone()
+    }
+
+    /// How `version` compares to this bound, looking only at the components the bound gives.
+    pub fn compare(&self, version: &Version) -> std::cmp::Ordering {
+        if self.components == 3 {
+            return version.cmp_precedence(&self.version);
+        }
+        let parts = |v: &Version| [v.major, v.minor, v.patch];
+        parts(version)[..self.components].cmp(&parts(&self.version)[..self.components])
+    }
+}
+
+impl std::str::FromStr for VersionBound {
+    type Err = String;
+
+    /// Accepts `0.14.1`, `0.15.0-rc.1`, `0.14` or `0`, with an optional leading `v`.
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let value = value.strip_prefix('v').unwrap_or(value);
+        if let Ok(version) = Version::parse(value) {
+            return Ok(Self {
+                version,
+                components: 3,
+            });
+        }
+        let invalid = || format!("must be a version such as 0.14.1 or 0.14, got {value}");
+        let parts = value
+            .split('.')
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        match parts[..] {
+            [major] => Ok(Self {
+                version: Version::new(major, 0, 0),
+                components: 1,
+            }),
+            [major, minor] => Ok(Self {
+                version: Version::new(major, minor, 0),
+                components: 2,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for VersionBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Version { major, minor, .. } = self.version;
+        match self.components {
+            1 => write!(f, "{major}"),
+            2 => write!(f, "{major}.{minor}"),
+            _ => write!(f, "{}", self.version),
+        }
+    }
+}
+
+/// Whether `version` lies within `since..=until`; an absent bound leaves that side open.
+pub fn in_version_window(
+    version: &Version,
+    since: Option<&VersionBound>,
+    until: Option<&VersionBound>,
+) -> bool {
+    since.is_none_or(|since| since.compare(version).is_ge())
+        && until.is_none_or(|until| until.compare(version).is_le())
+}
+
+/// The Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 pub fn latest_installed_version(versions_dir: &Path) -> Result<Option<Version>> {
+    let mut versions = installed_versions(versions_dir)?;
+    versions.sort_by(|a, b| b.cmp(a));
+    Ok(versions.into_iter().next())
+}
+
+/// The versions installed in `versions_dir`, in no particular order.
+pub fn installed_versions(versions_dir: &Path) -> Result<Vec<Version>> {
     if !versions_dir.exists() {
-        return Ok(None);
+        return Ok(Vec::new());
     }
 
     let mut versions = Vec::new();
@@ -363,9 +1465,7 @@ pub fn latest_installed_version(versions_dir: &Path) -> Result<Option<Version>>
             }
         }
     }
-
-    versions.sort_by(|a, b| b.cmp(a));
-    Ok(versions.into_iter().next())
+    Ok(versions)
 }
 
 pub fn runtime_ge_015(runtime: &str) -> bool {
@@ -373,3 +1473,51 @@ pub fn runtime_ge_015(runtime: &str) -> bool {
         .map(|v| v >= semver::Version::new(0, 15, 0))
         .unwrap_or(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_progress_reports_each_quarter_once() {
+        let mut progress = PlainProgress::new("asset", 0, 1000);
+        assert_eq!(progress.advance(100), None);
+        assert_eq!(
+            progress.advance(200),
+            Some("asset: downloaded 25%".to_string())
+        );
+        assert_eq!(progress.advance(10), None);
+        assert_eq!(
+            progress.advance(500),
+            Some("asset: downloaded 75%".to_string())
+        );
+        assert_eq!(
+            progress.advance(190),
+            Some("asset: downloaded 100%".to_string())
+        );
+        assert_eq!(progress.advance(0), None);
+    }
+
+    #[test]
+    fn test_event_progress_reports_every_percent() {
+        let mut progress = PlainProgress::with_step("asset", 0, 1000, 1);
+        assert_eq!(progress.advance_percent(5), None);
+        assert_eq!(progress.advance_percent(5), Some(1));
+        assert_eq!(progress.advance_percent(25), Some(3));
+        let event = serde_json::to_value(progress.event(3)).unwrap();
+        assert_eq!(
+            event,
+            serde_json::json!({"event": "download", "asset": "asset", "pct": 3, "downloaded": 35, "total": 1000})
+        );
+    }
+
+    #[test]
+    fn test_plain_progress_resumed_download_starts_at_offset() {
+        let mut progress = PlainProgress::new("asset", 600, 1000);
+        assert_eq!(progress.advance(50), None);
+        assert_eq!(
+            progress.advance(100),
+            Some("asset: downloaded 75%".to_string())
+        );
+    }
+}
@@ -1,8 +1,20 @@
-use git2::{Direction, Remote, RemoteHead};
+use std::{
+    ffi::{c_int, CString},
+    path::Path,
+    sync::OnceLock,
+};
+
+use clap::ValueEnum;
+use git2::{
+    CertificateCheckStatus, Direction, FetchOptions, ObjectType, Remote, RemoteCallbacks,
+    RemoteHead, Repository,
+};
+use reqwest::{Client, RequestBuilder};
 use semver::Version;
+use serde::Deserialize;
 use snafu::ResultExt as _;
 
-use crate::prelude::*;
+use crate::{api::RequestLog, http::Secret, prelude::*};
 
 #[derive(Debug, Clone, Copy)]
 pub enum ReleasesFilter {
@@ -10,6 +22,30 @@ pub enum ReleasesFilter {
     Stable,
 }
 
+/// Where the list of releases is read from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ResolveVia {
+    /// The GitHub REST API, falling back to git tags if it fails (e.g. when rate-limited)
+    #[default]
+    Auto,
+    /// The GitHub REST API only
+    Api,
+    /// The repository's git tags only
+    Git,
+}
+
+/// Releases requested per page from the GitHub API, which allows at most 100.
+const API_PAGE_SIZE: usize = 100;
+/// Upper bound on pages fetched, in case a server keeps returning full pages.
+const API_MAX_PAGES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct ApiRelease {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+}
+
 impl ReleasesFilter {
     pub fn matches(self, semver: &semver::Version) -> bool {
         match self {
@@ -19,14 +55,78 @@ impl ReleasesFilter {
     }
 }
 
+/// Makes libgit2 trust the root certificates in the PEM file at `path` when listing tags.
+///
+/// Like [`set_user_agent`], this is process-wide libgit2 state.
+pub fn set_ca_cert(path: &Path) -> Result<()> {
+    libgit2_sys::init();
+    // SAFETY: libgit2 is initialized and no other thread changes its TLS settings concurrently;
+    // git listings run one at a time.
+    unsafe { git2::opts::set_ssl_cert_file(path) }.context(GitSnafu {
+        resource: "CA certificate",
+    })
+}
+
+/// Sets the User-Agent libgit2 sends when listing tags over HTTPS.
+///
+/// This is process-wide libgit2 state, so only the first call takes effect: a run has one
+/// User-Agent, and setting it again while another thread lists tags (e.g. `install --multi`)
+/// would race with libgit2 reading it.
+pub fn set_user_agent(user_agent: &str) -> Result<()> {
+    static APPLIED: OnceLock<(String, c_int)> = OnceLock::new();
+
+    let c_user_agent = CString::new(user_agent).map_err(|_| Error::HttpClientBuild {
+        reason: "User-Agent must not contain NUL bytes".to_string(),
+    })?;
+    let (applied, code) = APPLIED.get_or_init(|| {
+        libgit2_sys::init();
+        // SAFETY: libgit2 is initialized and copies the string before returning. This runs once,
+        // and other callers wait for it to finish, so no git operation reads the option while
+        // it changes.
+        let code = unsafe {
+            libgit2_sys::git_libgit2_opts(
+                libgit2_sys::GIT_OPT_SET_USER_AGENT as c_int,
+                c_user_agent.as_ptr(),
+            )
+        };
+        (user_agent.to_string(), code)
+    });
+    if applied != user_agent {
+        tracing::debug!(%applied, requested = user_agent, "libgit2 User-Agent was already set");
+    }
+    let code = *code;
+    if code < 0 {
+        return Err(git2::Error::last_error(code)).context(GitSnafu {
+            resource: "user agent",
+        });
+    }
+    Ok(())
+}
+
 /// Get all releases sorted from newest to oldest.
 pub fn get_all(url: &str, filter: ReleasesFilter) -> Result<Vec<Version>> {
+    get_all_with(url, filter, false)
+}
+
+/// Like [`get_all`], but with `accept_invalid_certs` the server's TLS certificate is not
+/// checked at all (`--danger-insecure`).
+pub fn get_all_with(
+    url: &str,
+    filter: ReleasesFilter,
+    accept_invalid_certs: bool,
+) -> Result<Vec<Version>> {
     let mut remote = Remote::create_detached(url).context(GitSnafu { resource: "remote" })?;
-    remote.connect(Direction::Fetch).context(GitSnafu {
-        resource: "remote/connect",
-    })?;
+    let mut callbacks = RemoteCallbacks::new();
+    if accept_invalid_certs {
+        callbacks.certificate_check(|_, _| Ok(CertificateCheckStatus::CertificateOk));
+    }
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(callbacks), None)
+        .context(GitSnafu {
+            resource: "remote/connect",
+        })?;
 
-    let list = remote.list().context(GitSnafu {
+    let list = connection.list().context(GitSnafu {
         resource: "remote/list",
     })?;
     let mut heads = list
@@ -39,10 +139,147 @@ pub fn get_all(url: &str, filter: ReleasesFilter) -> Result<Vec<Version>> {
     Ok(heads)
 }
 
+/// A GET request of the GitHub REST API at `url`, authenticated with `token` if there is one.
+pub(crate) fn api_get(client: &Client, url: &str, token: Option<&Secret>) -> RequestBuilder {
+    let request = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+    match token {
+        Some(token) => request.bearer_auth(token.expose()),
+        None => request,
+    }
+}
+
+/// Get all releases sorted from newest to oldest from the GitHub releases endpoint at `url`,
+/// e.g. `https://api.github.com/repos/WasmEdge/WasmEdge/releases`. Drafts are skipped.
+pub async fn get_all_from_api(
+    client: &Client,
+    log: &RequestLog,
+    url: &str,
+    token: Option<&Secret>,
+    filter: ReleasesFilter,
+) -> Result<Vec<Version>> {
+    let mut versions = Vec::new();
+    for page in 1..=API_MAX_PAGES {
+        let request =
+            api_get(client, url, token).query(&[("per_page", API_PAGE_SIZE), ("page", page)]);
+        let response = log
+            .send(request)
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .context(RequestSnafu {
+                resource: "release listing",
+            })?;
+        let body = response.text().await.context(RequestSnafu {
+            resource: "release listing",
+        })?;
+        let releases: Vec<ApiRelease> = serde_json::from_str(&body).context(ReleaseListingSnafu)?;
+
+        let full_page = releases.len() >= API_PAGE_SIZE;
+        versions.extend(
+            releases
+                .into_iter()
+                .filter(|release| !release.draft)
+                .filter_map(|release| tag_to_version(&release.tag_name))
+                .filter(|version| filter.matches(version)),
+        );
+        if !full_page {
+            break;
+        }
+    }
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+
+    Ok(versions)
+}
+
+/// Start of the PGP signature git appends to the body of a signed tag.
+const PGP_SIGNATURE_START: &[u8] = b"-----BEGIN PGP SIGNATURE-----";
+
+/// Checks that `tag` in the repository at `url` is an annotated tag and, with a `key`, that it
+/// carries a PGP signature made by that (ASCII-armored or binary) public key.
+///
+/// Only the tag and the commit it points to are fetched, into a scratch repository. Checking
+/// the signature requires `gpg`, see [`super::signature::verify_detached`].
+pub fn verify_tag(
+    url: &str,
+    tag: &str,
+    key: Option<&Path>,
+    accept_invalid_certs: bool,
+) -> Result<()> {
+    let scratch = tempfile::tempdir()?;
+    let repo = Repository::init_bare(scratch.path().join("repo.git"))
+        .context(GitSnafu { resource: "tag" })?;
+    let refname = format!("refs/tags/{tag}");
+
+    let mut callbacks = RemoteCallbacks::new();
+    if accept_invalid_certs {
+        callbacks.certificate_check(|_, _| Ok(CertificateCheckStatus::CertificateOk));
+    }
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    // libgit2 cannot fetch shallowly from a path, where a full fetch is cheap anyway.
+    if url.contains("://") && !url.starts_with("file://") {
+        options.depth(1);
+    }
+    repo.remote_anonymous(url)
+        .and_then(|mut remote| {
+            remote.fetch(&[format!("+{refname}:{refname}")], Some(&mut options), None)
+        })
+        .context(GitSnafu {
+            resource: "tag fetch",
+        })?;
+
+    let oid = repo
+        .refname_to_id(&refname)
+        .context(GitSnafu { resource: "tag" })?;
+    let object = repo
+        .find_object(oid, None)
+        .context(GitSnafu { resource: "tag" })?;
+    if object.kind() != Some(ObjectType::Tag) {
+        return Err(tag_error(format!(
+            "{tag} is a lightweight tag; only annotated tags can be verified"
+        )));
+    }
+    tracing::debug!(tag, %oid, "Release tag is annotated");
+
+    let Some(key) = key else {
+        return Ok(());
+    };
+    let odb = repo.odb().context(GitSnafu { resource: "tag" })?;
+    let raw = odb.read(oid).context(GitSnafu { resource: "tag" })?;
+    let data = raw.data();
+    let Some(start) = data
+        .windows(PGP_SIGNATURE_START.len())
+        .position(|window| window == PGP_SIGNATURE_START)
+    else {
+        return Err(tag_error(format!("{tag} is not signed")));
+    };
+
+    super::signature::verify_detached(key, &data[..start], &data[start..])
+        .map_err(|reason| tag_error(format!("{tag}: {reason}")))?;
+    tracing::debug!(tag, key = %key.display(), "Release tag signature verified");
+    Ok(())
+}
+
+fn tag_error(message: String) -> Error {
+    Error::Git {
+        source: git2::Error::from_str(&message),
+        resource: "signed tag",
+    }
+}
+
 fn remote_head_to_version(head: &'_ RemoteHead<'_>) -> Option<Version> {
     let name = head.name().strip_prefix("refs/tags/")?;
     if name.ends_with("^{}") {
         return None;
     }
-    Version::parse(name).ok()
+    tag_to_version(name)
+}
+
+fn tag_to_version(name: &str) -> Option<Version> {
+    // Release URLs and asset names are built from the version's `Display`, so only tags it
+    // reproduces exactly can be installed.
+    Version::parse(name)
+        .ok()
+        .filter(|version| version.to_string() == name)
 }
@@ -0,0 +1,68 @@
+use std::{fmt, str::FromStr};
+
+use crate::prelude::*;
+
+pub const WASM_EDGE_REPO: &str = "WasmEdge/WasmEdge";
+
+/// A GitHub repository in `owner/name` form that releases are sourced from, e.g. a fork.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSlug(String);
+
+impl RepoSlug {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn git_url(&self) -> String {
+        format!("https://github.com/{}.git", self.0)
+    }
+
+    pub fn release_base_url(&self) -> String {
+        format!("https://github.com/{}/releases/download", self.0)
+    }
+
+    /// Base of the GitHub REST endpoints for this repository's releases.
+    pub fn releases_api_url(&self) -> String {
+        format!("https://api.github.com/repos/{}/releases", self.0)
+    }
+}
+
+impl Default for RepoSlug {
+    fn default() -> Self {
+        Self(WASM_EDGE_REPO.to_string())
+    }
+}
+
+impl fmt::Display for RepoSlug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for RepoSlug {
+    type Err = Error;
+
+    /// Accepts `owner/name` where both parts use GitHub's allowed characters
+    /// (ASCII alphanumerics, `-`, `_` and `.`) and the owner does not start with `-`.
+    fn from_str(s: &str) -> Result<Self> {
+        let valid_part = |part: &str| {
+            !part.is_empty()
+                && part != "."
+                && part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+
+        match s.split_once('/') {
+            Some((owner, name))
+                if valid_part(owner) && valid_part(name) && !owner.starts_with('-') =>
+            {
+                Ok(Self(s.to_string()))
+            }
+            _ => Err(Error::InvalidRepo {
+                slug: s.to_string(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+};
+
+use reqwest::{
+    header::{
+        HeaderMap, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, ETAG, PROXY_AUTHORIZATION,
+        RETRY_AFTER, SET_COOKIE,
+    },
+    RequestBuilder, Response,
+};
+use serde::Serialize;
+use url::Url;
+
+/// Target of the `--verbose-http` log lines, e.g. for `RUST_LOG`-style filtering.
+const HTTP_TARGET: &str = "wasmedgeup::http";
+
+/// Query parameters of presigned and token URLs whose values are credentials.
+const SECRET_QUERY_HINTS: [&str; 4] = ["sig", "token", "credential", "key"];
+
+/// One HTTP request a client sent, as listed in a failed install's diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequestRecord {
+    pub method: String,
+    pub url: String,
+    /// The response status, e.g. `404 Not Found`, or the error when no response arrived
+    pub outcome: String,
+}
+
+/// The requests and retries of one run, shared by clones of a [`super::WasmEdgeApiClient`].
+#[derive(Debug, Default)]
+pub struct RequestLog {
+    records: Mutex<Vec<RequestRecord>>,
+    retries: AtomicU32,
+    verbose: bool,
+}
+
+impl RequestLog {
+    /// Also logs every request as it completes, as `--verbose-http` asks: its method, URL and
+    /// headers, and the response status with selected headers. Credentials are redacted and
+    /// bodies never logged.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Sends `request`, recording its URL and the status or error it ended with.
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let (method, url) = (request.method().to_string(), request.url().to_string());
+        let sent = self
+            .verbose
+            .then(|| (redact_url(request.url()), redact_headers(request.headers())));
+        let result = client.execute(request).await;
+        let outcome = match &result {
+            Ok(response) => response.status().to_string(),
+            Err(e) => format!("error: {}", error_chain(e)),
+        };
+        if let Some((url, headers)) = sent {
+            match &result {
+                Ok(response) => {
+                    let header = |name| {
+                        response
+                            .headers()
+                            .get(name)
+                            .and_then(|v| v.to_str().ok())
+                            .unwrap_or("-")
+                    };
+                    tracing::info!(
+                        target: HTTP_TARGET,
+                        %method,
+                        %url,
+                        request_headers = %headers,
+                        final_url = %redact_url(response.url()),
+                        status = %outcome,
+                        content_length = header(CONTENT_LENGTH),
+                        content_type = header(CONTENT_TYPE),
+                        etag = header(ETAG),
+                        retry_after = header(RETRY_AFTER),
+                        "HTTP response"
+                    );
+                }
+                Err(_) => {
+                    tracing::info!(target: HTTP_TARGET, %method, %url, request_headers = %headers, error = %outcome, "HTTP request failed")
+                }
+            }
+        }
+        self.push(method, url, outcome);
+        result
+    }
+
+    /// Records a request sent some other way, e.g. a git tag listing.
+    pub(crate) fn record(
+        &self,
+        method: impl Into<String>,
+        url: impl Into<String>,
+        outcome: impl Into<String>,
+    ) {
+        let (method, url, outcome) = (method.into(), url.into(), outcome.into());
+        if self.verbose {
+            let shown = Url::parse(&url).map_or_else(|_| url.clone(), |url| redact_url(&url));
+            tracing::info!(target: HTTP_TARGET, %method, url = %shown, status = %outcome, "HTTP response");
+        }
+        self.push(method, url, outcome);
+    }
+
+    fn push(&self, method: String, url: String, outcome: String) {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(RequestRecord {
+                method,
+                url,
+                outcome,
+            });
+    }
+
+    /// Counts a retried attempt.
+    pub(crate) fn retried(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn records(&self) -> Vec<RequestRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries.load(Ordering::Relaxed)
+    }
+}
+
+/// `url` with its password and the values of credential-like query parameters, e.g. the
+/// signature of a presigned download URL, replaced by `<redacted>`.
+pub fn redact_url(url: &Url) -> String {
+    let mut url = url.clone();
+    if url.password().is_some() {
+        let _ = url.set_password(Some("<redacted>"));
+    }
+    if url.query().is_some() {
+        let pairs = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let lower = name.to_ascii_lowercase();
+                let secret = SECRET_QUERY_HINTS.iter().any(|hint| lower.contains(hint));
+                let value = if secret { "<redacted>".into() } else { value };
+                (name.into_owned(), value.into_owned())
+            })
+            .collect::<Vec<_>>();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+/// `headers` as `name: value` pairs separated by `, `, with the values of credentials such as
+/// `Authorization` and cookies replaced by `<redacted>`.
+pub fn redact_headers(headers: &HeaderMap) -> String {
+    const SECRET: [reqwest::header::HeaderName; 4] =
+        [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE];
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET.contains(name) {
+                "<redacted>"
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            format!("{name}: {value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `e` followed by its sources, which hold the actual cause, e.g. `connection refused`.
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
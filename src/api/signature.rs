@@ -0,0 +1,52 @@
+//! Checks detached OpenPGP signatures with `gpg`.
+
+use std::{ffi::OsStr, path::Path, process::Output};
+
+/// Checks that `signature` is a detached signature of `data` made with `key`, an ASCII-armored
+/// or binary public key.
+///
+/// `gpg` runs against a throwaway keyring holding just `key`. A failure is described in a
+/// phrase for the caller's error, e.g. `the signature was not made with the key in key.asc`.
+pub(crate) fn verify_detached(
+    key: &Path,
+    data: &[u8],
+    signature: &[u8],
+) -> std::result::Result<(), String> {
+    let scratch = tempfile::tempdir().map_err(|e| format!("could not create a keyring: {e}"))?;
+    let (payload, detached) = (scratch.path().join("data"), scratch.path().join("data.asc"));
+    let home = scratch.path().join("gnupg");
+    std::fs::write(&payload, data)
+        .and_then(|()| std::fs::write(&detached, signature))
+        .and_then(|()| std::fs::create_dir(&home))
+        .map_err(|e| format!("could not create a keyring: {e}"))?;
+
+    let gpg = |args: &[&OsStr]| -> std::result::Result<Output, String> {
+        std::process::Command::new("gpg")
+            .arg("--homedir")
+            .arg(&home)
+            .args(["--batch", "--quiet"])
+            .args(args)
+            .output()
+            .map_err(|e| format!("could not run gpg to check the signature: {e}"))
+    };
+    let imported = gpg(&["--import".as_ref(), key.as_os_str()])?;
+    if !imported.status.success() {
+        return Err(format!(
+            "could not import the key in {}: {}",
+            key.display(),
+            String::from_utf8_lossy(&imported.stderr).trim()
+        ));
+    }
+    let verified = gpg(&[
+        "--verify".as_ref(),
+        detached.as_os_str(),
+        payload.as_os_str(),
+    ])?;
+    if !verified.status.success() {
+        return Err(format!(
+            "the signature was not made with the key in {}",
+            key.display()
+        ));
+    }
+    Ok(())
+}
@@ -1,31 +1,67 @@
 use clap::Parser;
-use tracing::Level;
-use wasmedgeup::cli::Cli;
+use tracing::level_filters::LevelFilter;
 use wasmedgeup::cli::CommandExecutor;
+use wasmedgeup::cli::{Cli, Commands};
+use wasmedgeup::commands::check_update::CheckUpdateArgs;
 use wasmedgeup::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
-    let ctx = cli.context();
 
-    init_tracing(cli.verbose);
+    // Keep stdout to the events alone, so that tools can parse every line of it.
+    let logs_to_stderr = matches!(&cli.commands, Some(Commands::Install(args)) if args.json_lines);
+    init_tracing(cli.verbose, cli.quiet_errors, logs_to_stderr);
+    let environment = cli.environment().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(e.exit_code());
+    });
+    let _environment = environment.activate();
+    let ctx = cli.context();
+    if cli.xdg {
+        wasmedgeup::commands::use_xdg_layout();
+    }
+    if cli.quiet_errors {
+        wasmedgeup::commands::use_quiet_errors();
+    }
+    if cli.danger_insecure {
+        tracing::warn!(
+            "--danger-insecure: TLS certificates are NOT verified for this run; the integrity of \
+             downloads now rests solely on checksum verification"
+        );
+    }
 
-    if let Some(command) = cli.commands {
-        if let Err(e) = command.execute(ctx).await {
-            eprintln!("{e}");
-            std::process::exit(1);
-        }
+    let result = if cli.check_update {
+        let args = CheckUpdateArgs {
+            json: cli.json,
+            ..Default::default()
+        };
+        args.execute(ctx).await
+    } else if let Some(command) = cli.commands {
+        command.execute(ctx).await
+    } else {
+        Ok(())
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(e.exit_code());
     }
 
     Ok(())
 }
 
-fn init_tracing(verbosity: u8) {
+fn init_tracing(verbosity: u8, quiet_errors: bool, to_stderr: bool) {
+    // With `--quiet-errors` only the final error, printed by `main`, is shown.
     let level = match verbosity {
-        0 => Level::INFO,
-        1 => Level::DEBUG,
-        2.. => Level::TRACE,
+        _ if quiet_errors => LevelFilter::OFF,
+        0 => LevelFilter::INFO,
+        1 => LevelFilter::DEBUG,
+        2.. => LevelFilter::TRACE,
     };
-    tracing_subscriber::fmt().with_max_level(level).init();
+    let subscriber = tracing_subscriber::fmt().with_max_level(level);
+    if to_stderr {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 }
@@ -0,0 +1,299 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use semver::Version;
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+use tokio::fs;
+
+use crate::{
+    api::{Asset, WASM_EDGE_RELEASE_ASSET_BASE_URL},
+    environment::Environment,
+    prelude::*,
+};
+
+const CHECKSUM_SUFFIX: &str = ".sha256";
+const LISTING_SUFFIX: &str = ".versions";
+const PARTIAL_SUFFIX: &str = ".part";
+
+/// A previously downloaded and verified archive together with its expected checksum.
+#[derive(Debug, Clone)]
+pub struct CachedArchive {
+    pub path: PathBuf,
+    pub checksum: String,
+    /// Whether the entry is older than the cache's [TTL](DownloadCache::with_ttl), so its
+    /// checksum should be compared with the published one before it is used
+    pub expired: bool,
+}
+
+/// On-disk cache of release archives, keyed by source, version and archive name.
+///
+/// Every archive is stored next to a `<archive>.sha256` file holding the checksum it was
+/// verified against, so a cache hit needs neither the download nor the checksum file.
+/// Layout: `<root>/<version>/<archive_name>` and `<root>/<version>/<archive_name>.sha256`
+/// for the official releases; archives from any other [source](Self::with_source) live under
+/// `<root>/source-<hash>/` instead, so a fork or mirror never gets another source's archive.
+///
+/// Entries never expire by default: an archive is identified by its checksum, so it cannot go
+/// stale unless the release is republished with different files.
+#[derive(Debug, Clone)]
+pub struct DownloadCache {
+    root: PathBuf,
+    ttl: Option<Duration>,
+    /// `source-<hash>` directory of a non-default source
+    namespace: Option<String>,
+}
+
+impl DownloadCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ttl: None,
+            namespace: None,
+        }
+    }
+
+    /// Keeps the entries of archives downloaded from `source`, e.g. a `--repo` fork's or a
+    /// mirror's release base URL, apart from those of every other source.
+    pub fn with_source(mut self, source: &str) -> Self {
+        let source = source.trim_end_matches('/');
+        self.namespace =
+            (source != WASM_EDGE_RELEASE_ASSET_BASE_URL.trim_end_matches('/')).then(|| {
+                let digest = hex::encode(Sha256::digest(source.as_bytes()));
+                format!("source-{}", &digest[..16])
+            });
+        self
+    }
+
+    /// Reports entries stored (or [renewed](Self::renew)) longer than `ttl` ago as expired;
+    /// `None` never expires them.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The per-user cache location, e.g. `~/.cache/wasmedgeup/downloads` on Linux.
+    ///
+    /// With `--xdg` it is under `$XDG_CACHE_HOME` (default `~/.cache`) on every platform.
+    pub fn default_location() -> Option<Self> {
+        user_cache_dir().map(|dir| Self::new(dir.join("downloads")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn archive_path(&self, asset: &Asset) -> PathBuf {
+        self.version_dir(asset).join(&asset.archive_name)
+    }
+
+    pub fn checksum_path(&self, asset: &Asset) -> PathBuf {
+        let mut name = asset.archive_name.clone();
+        name.push_str(CHECKSUM_SUFFIX);
+        self.version_dir(asset).join(name)
+    }
+
+    fn version_dir(&self, asset: &Asset) -> PathBuf {
+        let dir = match &self.namespace {
+            Some(namespace) => self.root.join(namespace),
+            None => self.root.clone(),
+        };
+        dir.join(asset.version.to_string())
+    }
+
+    /// Returns the cached archive for `asset`, if both the archive and its checksum are present.
+    pub async fn lookup(&self, asset: &Asset) -> Option<CachedArchive> {
+        let path = self.archive_path(asset);
+        if !fs::try_exists(&path).await.unwrap_or(false) {
+            return None;
+        }
+
+        let checksum_path = self.checksum_path(asset);
+        let checksum = fs::read_to_string(&checksum_path).await.ok()?;
+        let checksum = checksum.trim();
+        if checksum.is_empty() {
+            return None;
+        }
+
+        let expired = match self.ttl {
+            Some(ttl) => fs::metadata(&checksum_path)
+                .await
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|stored| stored.elapsed().ok())
+                .is_none_or(|age| age >= ttl),
+            None => false,
+        };
+        Some(CachedArchive {
+            path,
+            checksum: checksum.to_string(),
+            expired,
+        })
+    }
+
+    /// Restarts the TTL of the entry for `asset`, after its checksum was found to still be the
+    /// published one.
+    pub async fn renew(&self, asset: &Asset, checksum: &str) {
+        let path = self.checksum_path(asset);
+        if let Err(e) = fs::write(&path, checksum).await {
+            tracing::debug!(error = %e, path = %path.display(), "Failed to renew cache entry");
+        }
+    }
+
+    /// Stores a verified archive and the checksum it matched.
+    ///
+    /// The archive is copied under a temporary name and renamed into place, so a concurrent
+    /// reader never sees a partially written entry.
+    pub async fn store(&self, asset: &Asset, archive: &Path, checksum: &str) -> Result<()> {
+        let dest = self.archive_path(asset);
+        let dir = dest.parent().unwrap_or(&self.root);
+        fs::create_dir_all(dir).await.context(IoSnafu {
+            action: "create cache directory".to_string(),
+            path: dir.display().to_string(),
+        })?;
+
+        let mut partial = dest.clone().into_os_string();
+        partial.push(PARTIAL_SUFFIX);
+        let partial = PathBuf::from(partial);
+
+        fs::copy(archive, &partial).await.context(IoSnafu {
+            action: "copy archive into cache".to_string(),
+            path: partial.display().to_string(),
+        })?;
+        fs::rename(&partial, &dest).await.context(IoSnafu {
+            action: "move archive into cache".to_string(),
+            path: dest.display().to_string(),
+        })?;
+
+        let checksum_path = self.checksum_path(asset);
+        fs::write(&checksum_path, checksum).await.context(IoSnafu {
+            action: "write cached checksum".to_string(),
+            path: checksum_path.display().to_string(),
+        })?;
+
+        tracing::debug!(path = %dest.display(), "Stored archive in download cache");
+        Ok(())
+    }
+
+    /// Removes the cached entry for `asset`, e.g. after it failed verification.
+    pub async fn evict(&self, asset: &Asset) {
+        for path in [self.archive_path(asset), self.checksum_path(asset)] {
+            if let Err(e) = fs::remove_file(&path).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::debug!(error = %e, path = %path.display(), "Failed to evict cache entry");
+                }
+            }
+        }
+    }
+}
+
+/// How long a cached release listing is used before it is fetched again, unless
+/// `--listing-cache-ttl` says otherwise.
+pub const DEFAULT_LISTING_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// On-disk cache of release listings, so that resolving `latest` or a partial version does not
+/// list the releases again on every run.
+///
+/// Each listing is stored as one version per line in `<root>/<hash>.versions`, where the hash
+/// is that of the key the listing was stored under, i.e. of where and how it was listed.
+/// Unlike archives, listings go stale as soon as a release is published, so entries expire
+/// after [`DEFAULT_LISTING_TTL`] by default.
+#[derive(Debug, Clone)]
+pub struct ListingCache {
+    root: PathBuf,
+    ttl: Option<Duration>,
+}
+
+impl ListingCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ttl: Some(DEFAULT_LISTING_TTL),
+        }
+    }
+
+    /// Ignores listings stored longer than `ttl` ago; `None` never expires them.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The per-user cache location next to the [download cache](DownloadCache::default_location),
+    /// e.g. `~/.cache/wasmedgeup/listings` on Linux.
+    pub fn default_location() -> Option<Self> {
+        user_cache_dir().map(|dir| Self::new(dir.join("listings")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn listing_path(&self, key: &str) -> PathBuf {
+        let digest = hex::encode(Sha256::digest(key.as_bytes()));
+        let mut name = digest[..16].to_string();
+        name.push_str(LISTING_SUFFIX);
+        self.root.join(name)
+    }
+
+    /// Returns the listing stored under `key`, unless there is none, it expired or it cannot be
+    /// read back.
+    pub async fn lookup(&self, key: &str) -> Option<Vec<Version>> {
+        let path = self.listing_path(key);
+        if let Some(ttl) = self.ttl {
+            let age = fs::metadata(&path)
+                .await
+                .and_then(|meta| meta.modified())
+                .ok()?
+                .elapsed()
+                .ok()?;
+            if age >= ttl {
+                return None;
+            }
+        }
+        let listing = fs::read_to_string(&path).await.ok()?;
+        let releases: Vec<Version> = listing
+            .lines()
+            .map(|line| Version::parse(line.trim()).ok())
+            .collect::<Option<_>>()?;
+        (!releases.is_empty()).then_some(releases)
+    }
+
+    /// Stores `releases` under `key`, replacing any listing stored before.
+    ///
+    /// A listing that cannot be written is only logged, as it is fetched again next time.
+    pub async fn store(&self, key: &str, releases: &[Version]) {
+        let path = self.listing_path(key);
+        let mut partial = path.clone().into_os_string();
+        partial.push(PARTIAL_SUFFIX);
+        let partial = PathBuf::from(partial);
+
+        let listing: String = releases.iter().map(|v| format!("{v}\n")).collect();
+        let written = async {
+            fs::create_dir_all(&self.root).await?;
+            fs::write(&partial, listing).await?;
+            fs::rename(&partial, &path).await
+        };
+        match written.await {
+            Ok(()) => tracing::debug!(path = %path.display(), "Stored release listing in cache"),
+            Err(e) => {
+                tracing::debug!(error = %e, path = %path.display(), "Failed to cache release listing")
+            }
+        }
+    }
+}
+
+/// The per-user `wasmedgeup` cache directory, e.g. `~/.cache/wasmedgeup` on Linux.
+///
+/// With `--xdg` it is under `$XDG_CACHE_HOME` (default `~/.cache`) on every platform.
+fn user_cache_dir() -> Option<PathBuf> {
+    let env = Environment::current();
+    let dir = if crate::commands::xdg_layout() {
+        env.xdg_dir("XDG_CACHE_HOME")
+            .or_else(|| env.home_dir().map(|home| home.join(".cache")))
+    } else {
+        env.cache_dir()
+    };
+    dir.map(|dir| dir.join("wasmedgeup"))
+}
@@ -1,12 +1,24 @@
-use std::future::Future;
+use std::{future::Future, path::PathBuf};
 
-use crate::api::WasmEdgeApiClient;
+use crate::api::{RepoSlug, ResolveVia, WasmEdgeApiClient};
+use crate::cache::{ListingCache, DEFAULT_LISTING_TTL};
+use crate::commands::clean::CleanArgs;
+use crate::commands::files::FilesArgs;
 use crate::commands::install::InstallArgs;
 use crate::commands::list::ListArgs;
 use crate::commands::plugin::PluginCli;
+use crate::commands::prune::PruneArgs;
+use crate::commands::reinstall::ReinstallArgs;
 use crate::commands::remove::RemoveArgs;
+use crate::commands::targets::TargetsArgs;
 use crate::commands::use_cmd::UseArgs;
+use crate::commands::verify::VerifyArgs;
+use crate::commands::verify_file::VerifyFileArgs;
+use crate::commands::version::VersionArgs;
+use crate::environment::Environment;
+use crate::http::ProxyConfig;
 use crate::prelude::*;
+use crate::units::Ttl;
 use clap::builder::styling::AnsiColor;
 use clap::{builder::Styles, Parser, Subcommand};
 
@@ -38,10 +50,108 @@ pub struct Cli {
     #[arg(long)]
     pub request_timeout: Option<u64>,
 
+    /// Timeout in seconds for a single network attempt.
+    /// A stalled attempt is aborted and retried (see `--retries`) instead of consuming the
+    /// whole request timeout, which still bounds the operation including all retries.
+    /// Default: none
+    #[arg(long)]
+    pub attempt_timeout: Option<u64>,
+
+    /// Number of retries for transient network failures.
+    /// Default: 3
+    #[arg(long)]
+    pub retries: Option<u32>,
+
+    /// GitHub repository (`owner/name`) to install releases and plugins from, e.g. a fork.
+    /// Default: WasmEdge/WasmEdge
+    #[arg(long, visible_alias = "channel", value_name = "OWNER/NAME")]
+    pub repo: Option<RepoSlug>,
+
+    /// Where to list releases from: the GitHub API, git tags, or the API with git as fallback.
+    /// Default: auto
+    #[arg(long, value_enum)]
+    pub resolve_via: Option<ResolveVia>,
+
+    /// User-Agent header sent with every request to GitHub.
+    /// Default: wasmedgeup/<version> (+https://github.com/WasmEdge/wasmedgeup)
+    #[arg(long, value_parser = parse_user_agent)]
+    pub user_agent: Option<String>,
+
+    /// PEM file with extra root certificates to trust, e.g. a TLS-inspecting proxy's CA.
+    /// Default: `SSL_CERT_FILE` when set, otherwise only the system certificates
+    #[arg(long, env = "WASMEDGEUP_CACERT", value_name = "PEM")]
+    pub cacert: Option<PathBuf>,
+
+    /// Do not verify TLS certificates, e.g. for an internal mirror with a self-signed one.
+    /// Downloads are then only protected by checksum verification. Applies to this run only
+    #[arg(long)]
+    pub danger_insecure: bool,
+
+    /// Bypass every cache for this run, e.g. to reproduce a stale-cache problem: the download
+    /// and release-listing caches are neither read nor written (as with `install --no-cache`),
+    /// and servers and proxies are asked for fresh responses with `Cache-Control: no-cache`
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// How long a cached release listing is used for resolving `latest`, a channel or a
+    /// partial version before the releases are listed again: `never` to keep it until the next
+    /// `--refresh`, `0` to always list them. An exact version missing from the cached listing
+    /// is always looked up afresh. `--refresh` bypasses the cache whatever the lifetime.
+    /// Default: 1h
+    #[arg(long, env = "WASMEDGEUP_LISTING_CACHE_TTL", value_name = "TTL")]
+    pub listing_cache_ttl: Option<Ttl>,
+
+    /// Never touch the network: fail any release listing, download or checksum lookup with an
+    /// error instead, so installs are served from the download cache, a lockfile's pinned
+    /// checksum (`--from-manifest`) or a local archive (`install --url file://...`) only
+    #[arg(long, env = "WASMEDGEUP_OFFLINE", conflicts_with = "refresh")]
+    pub offline: bool,
+
+    /// Log every HTTP request's method, URL and headers with the response status and its
+    /// length, content type, ETag and Retry-After, e.g. to diagnose proxy, mirror or token
+    /// problems. Credentials (`Authorization`, cookies, URL passwords and signatures) are
+    /// redacted and bodies are never logged
+    #[arg(long, conflicts_with = "quiet_errors")]
+    pub verbose_http: bool,
+
+    /// Default to the XDG Base Directory layout: install to `$XDG_DATA_HOME/wasmedge` and cache
+    /// downloads under `$XDG_CACHE_HOME`.
+    /// Without it the default install location is `$HOME/.wasmedge`
+    #[arg(long, env = "WASMEDGEUP_XDG")]
+    pub xdg: bool,
+
+    /// Load proxy, GitHub token and mirror settings from a dotenv file, `.env` if no path is
+    /// given: `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`, `NO_PROXY`, `GITHUB_TOKEN` and
+    /// `WASMEDGEUP_MIRROR`. Variables already set in the environment take precedence
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ".env"
+    )]
+    pub env_file: Option<PathBuf>,
+
     /// Disable progress output
     #[arg(short, long, conflicts_with = "verbose")]
     pub quiet: bool,
 
+    /// Suppress all logging and progress output, but still print the final error, if any, to
+    /// stderr. The exit status reflects the failure as usual; for wrappers that only care about
+    /// failures
+    #[arg(long, conflicts_with = "verbose")]
+    pub quiet_errors: bool,
+
+    /// Check whether a newer stable release than the active version exists, without installing.
+    /// Prints the newer version and exits with status 1 if there is one, exits 0 otherwise
+    #[arg(long)]
+    pub check_update: bool,
+
+    /// Print the result of `--check-update` as a JSON object with `current`, `latest` and
+    /// `update_available`
+    #[arg(long, requires = "check_update")]
+    pub json: bool,
+
     #[command(subcommand)]
     pub commands: Option<Commands>,
 }
@@ -53,26 +163,101 @@ pub struct CommandContext {
 }
 
 impl Cli {
+    /// The [`Environment`] commands run in: the current one plus what `--env-file` loads.
+    pub fn environment(&self) -> Result<Environment> {
+        let env = Environment::current();
+        match &self.env_file {
+            Some(path) => env.with_env_file(path),
+            None => Ok(env),
+        }
+    }
+
+    /// The client and options every command gets, configured from the global options and the
+    /// [current environment](Environment::current).
     pub fn context(&self) -> CommandContext {
+        let env = Environment::current();
         let mut client = WasmEdgeApiClient::default();
+        if let Some(repo) = &self.repo {
+            client = client.with_repo(repo.clone());
+        }
         if let Some(timeout) = self.connect_timeout {
             client = client.with_connect_timeout(timeout);
         }
         if let Some(timeout) = self.request_timeout {
             client = client.with_request_timeout(timeout);
         }
+        if let Some(timeout) = self.attempt_timeout {
+            client = client.with_attempt_timeout(timeout);
+        }
+        if let Some(retries) = self.retries {
+            client = client.with_retries(retries);
+        }
+        if let Some(resolve_via) = self.resolve_via {
+            client = client.with_resolve_via(resolve_via);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            client = client.with_user_agent(user_agent);
+        }
+        let cacert = self.cacert.clone().or_else(|| {
+            env.var_os("SSL_CERT_FILE")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        });
+        if let Some(path) = cacert {
+            client = client.with_ca_cert(path);
+        }
+        if self.danger_insecure {
+            client = client.with_danger_insecure(true);
+        }
+        if self.refresh {
+            client = client.with_refresh(true);
+        } else if let Some(cache) = ListingCache::default_location() {
+            let ttl = self
+                .listing_cache_ttl
+                .map_or(Some(DEFAULT_LISTING_TTL), |ttl| ttl.0);
+            client = client.with_listing_cache(cache.with_ttl(ttl));
+        }
+        if self.offline {
+            client = client.with_offline(true);
+        }
+        if self.verbose_http {
+            client = client.with_verbose_http(true);
+        }
+        if let Some(token) = env.var("GITHUB_TOKEN").filter(|t| !t.is_empty()) {
+            client = client.with_github_token(token);
+        }
+        // Proxies of the process environment are picked up by reqwest itself.
+        let proxy_vars = ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY"];
+        if proxy_vars
+            .iter()
+            .any(|name| env.has_override(name) || env.has_override(&name.to_ascii_lowercase()))
+        {
+            client = client.with_proxy(ProxyConfig::from_environment(&env));
+        }
+        if let (None, true) = (&self.repo, env.has_override("WASMEDGEUP_MIRROR")) {
+            if let Some(mirror) = env.var("WASMEDGEUP_MIRROR").filter(|m| !m.is_empty()) {
+                client = client.with_release_base_url(mirror.trim_end_matches('/'));
+            }
+        }
         CommandContext {
             client,
-            no_progress: self.quiet,
+            no_progress: self.quiet || self.quiet_errors,
         }
     }
 }
 
+fn parse_user_agent(value: &str) -> std::result::Result<String, String> {
+    reqwest::header::HeaderValue::from_str(value)
+        .map(|_| value.to_string())
+        .map_err(|_| "must be a valid HTTP header value".to_string())
+}
+
 pub trait CommandExecutor {
     fn execute(self, ctx: CommandContext) -> impl Future<Output = Result<()>> + Send;
 }
 
 #[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once per run; boxing would only complicate matching
 pub enum Commands {
     /// Install a specified WasmEdge runtime version
     Install(InstallArgs),
@@ -82,9 +267,28 @@ pub enum Commands {
     /// By default, only stable releases are shown.
     List(ListArgs),
     /// Uninstall a specific version of WasmEdge from the system
+    #[command(visible_alias = "uninstall")]
     Remove(RemoveArgs),
     /// Manage WasmEdge plugins
+    #[command(visible_alias = "plugins")]
     Plugin(PluginCli),
+    /// Re-check the files of installed versions against their install manifest
+    Verify(VerifyArgs),
+    /// Check a downloaded release archive against its published checksum, without installing
+    VerifyFile(VerifyFileArgs),
+    /// Download a version again and overwrite its files (defaults to the active version)
+    Reinstall(ReinstallArgs),
+    /// List the files an installed version owns, from its install manifest
+    Files(FilesArgs),
+    /// List the supported OS/arch/libc combinations, and which a release publishes
+    #[command(visible_alias = "list-targets")]
+    Targets(TargetsArgs),
+    /// Remove staging directories left behind by interrupted installs
+    Clean(CleanArgs),
+    /// Remove the installed versions that are neither active nor pinned
+    Prune(PruneArgs),
+    /// Print the wasmedgeup version and the active WasmEdge version and path, e.g. for bug reports
+    Version(VersionArgs),
 }
 
 impl CommandExecutor for Commands {
@@ -97,6 +301,14 @@ impl CommandExecutor for Commands {
             Use(args) => args.execute(ctx).await,
             Remove(args) => args.execute(ctx).await,
             Plugin(args) => args.execute(ctx).await,
+            Verify(args) => args.execute(ctx).await,
+            VerifyFile(args) => args.execute(ctx).await,
+            Reinstall(args) => args.execute(ctx).await,
+            Files(args) => args.execute(ctx).await,
+            Targets(args) => args.execute(ctx).await,
+            Clean(args) => args.execute(ctx).await,
+            Prune(args) => args.execute(ctx).await,
+            Version(args) => args.execute(ctx).await,
         }
     }
 }
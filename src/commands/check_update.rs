@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use semver::Version;
+use serde::Serialize;
+
+use crate::{
+    cli::{CommandContext, CommandExecutor},
+    commands::default_path,
+    prelude::*,
+};
+
+/// Options of `wasmedgeup --check-update`.
+#[derive(Debug, Default)]
+pub struct CheckUpdateArgs {
+    /// Install location to read the active version from; defaults to `$HOME/.wasmedge`.
+    pub path: Option<PathBuf>,
+
+    /// Print the result as a JSON object.
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateCheck {
+    /// `None` when no version is active.
+    current: Option<String>,
+    latest: String,
+    update_available: bool,
+}
+
+impl CommandExecutor for CheckUpdateArgs {
+    /// Compares the active version with the newest stable release, without installing anything.
+    ///
+    /// A newer release is printed and returned as [`Error::UpdateAvailable`], so that the exit
+    /// status tells scripts whether to upgrade. No active version at all counts as an update.
+    #[tracing::instrument(name = "check-update", skip_all)]
+    async fn execute(self, ctx: CommandContext) -> Result<()> {
+        let target_dir = match self.path {
+            Some(p) => p,
+            None => default_path()?,
+        };
+        let current = crate::fs::active_version(&target_dir).await;
+        let latest = ctx.client.latest_release().await?;
+
+        let update_available = match current.as_deref().map(Version::parse) {
+            Some(Ok(current)) => latest > current,
+            Some(Err(_)) => {
+                tracing::debug!(?current, "Active version is not a semantic version");
+                true
+            }
+            None => true,
+        };
+        tracing::debug!(?current, %latest, update_available, "Checked for updates");
+
+        if self.json {
+            let check = UpdateCheck {
+                current: current.clone(),
+                latest: latest.to_string(),
+                update_available,
+            };
+            let json = serde_json::to_string_pretty(&check).map_err(|_| Error::Unknown)?;
+            println!("{json}");
+        } else if update_available {
+            println!("{latest}");
+        }
+
+        if update_available {
+            return Err(Error::UpdateAvailable {
+                current: current.unwrap_or_else(|| "none".to_string()),
+                latest: latest.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
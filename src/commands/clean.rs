@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use walkdir::WalkDir;
+
+use crate::{
+    cli::{CommandContext, CommandExecutor},
+    commands::{default_path, install::TARGET_STAGING_DIR},
+    environment::Environment,
+    fs::{staging_marker, STAGING_DIR_PREFIX, STAGING_MARKER_SUFFIX},
+    prelude::*,
+    units::ByteSize,
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct CleanArgs {
+    /// Temporary directory to look for staging directories in, as given to `install --tmpdir`
+    ///
+    /// Defaults to the system temporary directory.
+    #[arg(short, long)]
+    pub tmpdir: Option<PathBuf>,
+
+    /// Install location whose `--temp-on-target` staging directory is cleaned too
+    ///
+    /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// List the directories that would be removed and the space they hold, without removing them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// A staging directory left behind by an install that did not finish.
+struct Stale {
+    dir: PathBuf,
+    marker: PathBuf,
+    size: u64,
+}
+
+impl CommandExecutor for CleanArgs {
+    /// Removes the staging directories of interrupted installs, printing each and the space
+    /// reclaimed.
+    ///
+    /// Only directories named `WasmEdge-*` with a [`staging_marker`] next to them are
+    /// considered, and those whose marker names a running process are skipped, as their
+    /// install may still be going on.
+    #[tracing::instrument(name = "clean", skip_all)]
+    async fn execute(self, _ctx: CommandContext) -> Result<()> {
+        let mut bases = vec![self
+            .tmpdir
+            .unwrap_or_else(|| Environment::current().temp_dir())];
+        match self.path.map_or_else(default_path, Ok) {
+            Ok(target_dir) => bases.push(target_dir.join(TARGET_STAGING_DIR)),
+            Err(e) => tracing::debug!(error = %e, "No install location to clean"),
+        }
+
+        let mut stale = Vec::new();
+        for base in &bases {
+            stale.extend(find_stale(base)?);
+        }
+
+        let verb = if self.dry_run {
+            "Would remove"
+        } else {
+            "Removed"
+        };
+        let mut reclaimed = 0;
+        for Stale { dir, marker, size } in &stale {
+            if !self.dry_run {
+                match crate::fs::remove_dir_all_safe(dir).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(Error::Io {
+                            action: "remove staging directory".to_string(),
+                            path: dir.display().to_string(),
+                            source: e,
+                        })
+                    }
+                    _ => {}
+                }
+                let _ = std::fs::remove_file(marker);
+            }
+            reclaimed += size;
+            println!("{verb} {} ({})", dir.display(), ByteSize(*size));
+        }
+
+        match stale.len() {
+            0 => println!("No staging directories to clean"),
+            count => println!(
+                "{verb} {count} staging director{}, {} reclaimed",
+                if count == 1 { "y" } else { "ies" },
+                ByteSize(reclaimed)
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// The staging directories in `base` whose marker does not name a running process. Markers
+/// whose directory is already gone are included, with a size of zero.
+fn find_stale(base: &Path) -> Result<Vec<Stale>> {
+    let entries = match std::fs::read_dir(base) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(Error::Io {
+                action: "list temporary directory".to_string(),
+                path: base.display().to_string(),
+                source,
+            })
+        }
+    };
+
+    let mut system = System::new();
+    let mut stale = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let file_name = entry.file_name();
+        let Some(name) = file_name
+            .to_str()
+            .and_then(|name| name.strip_suffix(STAGING_MARKER_SUFFIX))
+            .filter(|name| name.starts_with(STAGING_DIR_PREFIX))
+        else {
+            continue;
+        };
+        let dir = base.join(name);
+        let marker = staging_marker(&dir);
+        if std::fs::symlink_metadata(&dir).is_ok_and(|meta| !meta.is_dir()) {
+            tracing::debug!(path = %dir.display(), "Not a directory; leaving it alone");
+            continue;
+        }
+
+        let pid = std::fs::read_to_string(&marker)
+            .ok()
+            .and_then(|pid| pid.trim().parse::<u32>().ok());
+        if let Some(pid) = pid.filter(|pid| *pid != std::process::id()) {
+            let pid = Pid::from_u32(pid);
+            system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+            if system.process(pid).is_some() {
+                tracing::info!(path = %dir.display(), %pid, "Skipping staging directory of a running process");
+                continue;
+            }
+        }
+
+        let size = WalkDir::new(&dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|meta| meta.is_file())
+            .map(|meta| meta.len())
+            .sum();
+        stale.push(Stale { dir, marker, size });
+    }
+    stale.sort_by(|a, b| a.dir.cmp(&b.dir));
+    Ok(stale)
+}
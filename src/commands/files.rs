@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    cli::{CommandContext, CommandExecutor},
+    commands::default_path,
+    manifest::InstallManifest,
+    prelude::*,
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct FilesArgs {
+    /// Installed WasmEdge version to list the files of, e.g. `0.14.1`
+    ///
+    /// Defaults to the currently active version.
+    pub version: Option<String>,
+
+    /// Set the install location for the WasmEdge runtime
+    ///
+    /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// Print absolute paths instead of paths relative to the version directory
+    #[arg(long)]
+    pub absolute: bool,
+}
+
+impl CommandExecutor for FilesArgs {
+    /// Prints the files and symlinks a version installed, one per line, as recorded in its
+    /// install manifest.
+    ///
+    /// Files added to the version afterwards (e.g. plugins) are not listed. Versions without a
+    /// manifest yield [`Error::ManifestNotFound`].
+    #[tracing::instrument(name = "files", skip_all, fields(version = self.version))]
+    async fn execute(self, _ctx: CommandContext) -> Result<()> {
+        let target_dir = match self.path {
+            Some(p) => p,
+            None => default_path()?,
+        };
+
+        let version = match self.version {
+            Some(version) => version.trim_start_matches('v').to_string(),
+            None => crate::fs::active_version(&target_dir)
+                .await
+                .ok_or_else(|| Error::InvalidPath {
+                    path: target_dir.display().to_string(),
+                    reason: "no active version found; provide a version to list".to_string(),
+                })?,
+        };
+        let version_dir = target_dir.join("versions").join(&version);
+        if !version_dir.is_dir() {
+            return Err(Error::VersionNotFound { version });
+        }
+
+        let manifest = InstallManifest::load(&version_dir, &version)?;
+        let mut names = manifest
+            .files
+            .keys()
+            .chain(&manifest.links)
+            .collect::<Vec<_>>();
+        names.sort();
+
+        let root = self
+            .absolute
+            .then(|| std::path::absolute(&version_dir).unwrap_or(version_dir));
+        for name in names {
+            match &root {
+                Some(root) => println!("{}", root.join(name).display()),
+                None => println!("{name}"),
+            }
+        }
+        Ok(())
+    }
+}
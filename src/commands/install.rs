@@ -1,49 +1,539 @@
-use std::path::PathBuf;
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use clap::{Parser, ValueEnum};
+use glob::Pattern;
+use indicatif::MultiProgress;
+use snafu::ResultExt;
+use tokio::{fs, sync::Semaphore, task::JoinSet};
+use tracing::Instrument;
+use url::Url;
 
-use clap::Parser;
-use tokio::fs;
+use semver::Version;
+use serde::Serialize;
 
 use crate::{
-    api::{Asset, WasmEdgeApiClient},
+    api::{
+        Asset, ChecksumSource, Linkage, ReleasesFilter, RepoSlug, RequestLog, WasmEdgeApiClient,
+    },
+    cache::DownloadCache,
     cli::{CommandContext, CommandExecutor},
     commands::default_path,
+    environment::Environment,
+    events::{self, ProgressEvent},
+    fs::{CopyFilter, CopyStats, TempDirGuard},
+    manifest::{InstallLock, InstallManifest, SbomChecksum, SignatureStatus},
     prelude::*,
     shell_utils,
-    target::{TargetArch, TargetOS},
+    target::{TargetArch, TargetArchParser, TargetOS},
+    units::{ByteSize, Seconds, Ttl},
 };
 
+/// Directory of the install location that `--temp-on-target` stages downloads in.
+pub(crate) const TARGET_STAGING_DIR: &str = ".wasmedgeup-tmp";
+
+/// Accepts a hex-encoded checksum of an algorithm archives can be verified with, i.e. SHA-256.
+fn parse_checksum(value: &str) -> std::result::Result<String, String> {
+    if value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Ok(value.to_ascii_lowercase())
+    } else {
+        Err(format!(
+            "must be a SHA-256 checksum of 64 hex digits, got {} characters",
+            value.len()
+        ))
+    }
+}
+
+/// How many versions `--multi` installs at the same time unless `--jobs` says otherwise.
+const DEFAULT_JOBS: usize = 3;
+
+fn parse_label(value: &str) -> std::result::Result<Version, String> {
+    Version::parse(value.trim_start_matches('v'))
+        .map_err(|e| format!("must be a semantic version such as 0.15.0-ci.1: {e}"))
+}
+
+/// Accepts an octal mode such as `755` or `0750` that leaves the owner full access, which
+/// later installs and removals need.
+fn parse_mode(value: &str) -> std::result::Result<u32, String> {
+    let mode = u32::from_str_radix(value.strip_prefix("0o").unwrap_or(value), 8)
+        .ok()
+        .filter(|mode| *mode <= 0o777)
+        .ok_or_else(|| format!("must be an octal mode such as 755, got {value}"))?;
+    if mode & 0o700 != 0o700 {
+        return Err(format!(
+            "must give the owner read, write and execute permission (7xx), got {value}"
+        ));
+    }
+    Ok(mode)
+}
+
 fn default_tmpdir() -> PathBuf {
-    std::env::temp_dir()
+    Environment::current().temp_dir()
 }
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Default, Parser)]
 pub struct InstallArgs {
     /// WasmEdge version to install, e.g. `latest`, `0.14.1`, `0.14.1-rc.1`, etc.
+    ///
+    /// A partial version such as `0.14` installs the newest `0.14.x` release, and `0` the
+    /// newest `0.x.y`.
+    #[arg(
+        default_value = "",
+        hide_default_value = true,
+        required_unless_present_any = ["from_manifest", "url", "interactive", "from_env"]
+    )]
     pub version: String,
 
+    /// Further versions to install alongside the first one; requires `--multi`
+    #[arg(value_name = "VERSION", requires = "multi")]
+    pub versions: Vec<String>,
+
+    /// Install every version given, several at a time, e.g. `install 0.13.5 0.14.0 --multi`
+    ///
+    /// Each version goes into its own versioned directory. A version that fails does not stop
+    /// the others; a summary of each version's result is printed at the end. The last version
+    /// listed that installed successfully becomes the active one.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "prefix", "ephemeral", "ephemeral_run", "print_url", "manifest_only",
+            "from_manifest", "sbom"
+        ]
+    )]
+    pub multi: bool,
+
+    /// How many versions `--multi` installs at the same time [default: 3]
+    #[arg(short, long, value_name = "N", requires = "multi", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+
+    /// Run this shell command after a successful install, e.g. to register the install elsewhere
+    ///
+    /// It runs through `sh -c` (`cmd /C` on Windows) with `WASMEDGE_INSTALL_DIR` and
+    /// `WASMEDGE_VERSION` set to what was installed. A non-zero exit fails the install unless
+    /// `--ignore-hook-errors` is given; the files stay installed either way.
+    #[arg(
+        long,
+        value_name = "CMD",
+        conflicts_with_all = ["ephemeral_run", "print_url", "manifest_only"]
+    )]
+    pub after_install: Option<String>,
+
+    /// Only warn when the `--after-install` command fails
+    #[arg(long, requires = "after_install")]
+    pub ignore_hook_errors: bool,
+
+    /// With `--multi`, stop starting further versions once one fails
+    ///
+    /// Installs already underway are finished; the versions not started are reported as skipped.
+    #[arg(long, requires = "multi")]
+    pub fail_fast: bool,
+
     /// Set the install location for the WasmEdge runtime
     ///
     /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
     #[arg(short, long)]
     pub path: Option<PathBuf>,
 
+    /// Install directly into a prefix shared with other software, e.g. `/usr/local`
+    ///
+    /// Files go into `<prefix>/bin`, `<prefix>/lib` and `<prefix>/include` rather than a version
+    /// directory, and are listed in a manifest under `<prefix>/share/wasmedgeup` so that
+    /// `remove --prefix` deletes only them. Nothing else in the prefix is removed, and PATH
+    /// setup is skipped.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["path", "ephemeral", "ephemeral_run"])]
+    pub prefix: Option<PathBuf>,
+
     /// Set the temporary directory for staging downloaded assets
     ///
     /// Defaults to the system temporary directory, this differs between operating systems.
     #[arg(short, long)]
     pub tmpdir: Option<PathBuf>,
 
+    /// Stage the download in a hidden directory of the install location instead
+    ///
+    /// Keeps the staged files on the install's filesystem, so they are moved into place with a
+    /// rename rather than copied across devices.
+    #[arg(long, conflicts_with_all = ["tmpdir", "ephemeral", "ephemeral_run"])]
+    pub temp_on_target: bool,
+
+    /// Remove an existing staging directory for this version before staging into it
+    ///
+    /// The staging directory is named after the release archive, so one left behind by a
+    /// crashed install is reused, with whatever partial files it holds. Without this flag such
+    /// a directory is only warned about, since it may belong to a concurrent install.
+    #[arg(long)]
+    pub force_fresh_tmp: bool,
+
     /// Set the target OS for the WasmEdge runtime
     ///
     /// `wasmedgeup` will detect the OS of your host system by default.
-    #[arg(short, long)]
+    #[arg(short, long, ignore_case = true)]
     pub os: Option<TargetOS>,
 
     /// Set the target architecture for the WasmEdge runtime
     ///
-    /// `wasmedgeup` will detect the architecture of your host system by default.
-    #[arg(short, long)]
+    /// `wasmedgeup` will detect the architecture of your host system by default. Required when
+    /// installing for a different OS than the host's; `native` selects the host architecture.
+    #[arg(short, long, ignore_case = true, value_parser = TargetArchParser)]
     pub arch: Option<TargetArch>,
+
+    /// Whether to add the install to PATH (shell rc files, or the registry on Windows)
+    ///
+    /// On by default; `--setup-path=false` or `--no-setup-path` turns it off, e.g. for
+    /// scripted installs that manage the environment themselves.
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        overrides_with = "no_setup_path"
+    )]
+    pub setup_path: Option<bool>,
+
+    /// Do not add the install to PATH; same as `--setup-path=false`
+    #[arg(long, overrides_with = "setup_path")]
+    pub no_setup_path: bool,
+
+    /// Put the install first on PATH instead of adding it only when it is missing
+    ///
+    /// Another `wasmedge` earlier on PATH, e.g. from a system package, would otherwise keep
+    /// shadowing this one; new shells then run this install's even when PATH lists it already.
+    #[arg(long, conflicts_with = "no_setup_path")]
+    pub prepend_path: bool,
+
+    /// Install into a fresh temporary directory instead of the install location
+    ///
+    /// The directory is kept and its path printed so the version can be tried out without
+    /// touching an existing installation. PATH setup is skipped.
+    #[arg(long, conflicts_with = "path")]
+    pub ephemeral: bool,
+
+    /// Like `--ephemeral`, but run `wasmedge` with the arguments after `--` and clean up afterwards
+    ///
+    /// e.g. `wasmedgeup install 0.14.1 --ephemeral-run -- --version`
+    #[arg(long, conflicts_with_all = ["path", "ephemeral"])]
+    pub ephemeral_run: bool,
+
+    /// Arguments passed to `wasmedge` by `--ephemeral-run`
+    #[arg(last = true, requires = "ephemeral_run", value_name = "ARGS")]
+    pub run_args: Vec<String>,
+
+    /// Do not read from or write to the download cache
+    ///
+    /// Verified archives are cached together with their checksum, so repeated installs of the
+    /// same version skip both the download and the checksum file fetch.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// How long a cached archive is used without checking its checksum against the release
+    ///
+    /// `never` (the default) trusts cached archives indefinitely, as they are stored by their
+    /// checksum; a lifetime such as `12h` or `7d` re-fetches the published checksum for older
+    /// entries and downloads the archive again only when it changed. `--refresh` and
+    /// `--no-cache` bypass the cache whatever the lifetime.
+    #[arg(long, value_name = "TTL", conflicts_with = "no_cache")]
+    pub checksum_cache_ttl: Option<Ttl>,
+
+    /// Remove any existing files of this version before installing it
+    ///
+    /// The old files are only removed once the new archive has been downloaded and unpacked.
+    /// Plugins installed into the version are removed too.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Strip this many leading directories from the archive's paths, as tar does
+    ///
+    /// Defaults to the number of directories wrapping the release layout (`bin`, `lib64`,
+    /// `include`), such as `WasmEdge-0.14.1-Linux/`.
+    #[arg(long, value_name = "N")]
+    pub strip_components: Option<usize>,
+
+    /// Leave files that are already installed with identical contents untouched
+    ///
+    /// A file is only written when no file with the same size and SHA-256 exists at its
+    /// destination, so repeated installs, e.g. into a shared `--prefix`, keep the mtimes of
+    /// unchanged files. The number of written and unchanged files is logged.
+    #[arg(long, conflicts_with = "force")]
+    pub preserve_existing: bool,
+
+    /// Remove write permission from the installed files, and with `=all` from their directories
+    ///
+    /// Guards against accidental edits only; it is no substitute for file ownership or other
+    /// OS-level protections. `remove`, `reinstall` and `--force` restore write permission before
+    /// deleting. With `=all`, plugins can no longer be added to the version.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SCOPE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "files",
+        conflicts_with = "prefix"
+    )]
+    pub read_only: Option<ReadOnlyScope>,
+
+    /// After installing, remove all but the N newest installed versions (never the active one)
+    ///
+    /// Versions are ranked by semantic version; those pinned in the install location's
+    /// `.wasmedgeup-pins` are kept too. Only versions with an install manifest are removed;
+    /// each one pruned is printed with the space it took.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with_all = [
+            "prefix", "ephemeral", "ephemeral_run", "print_url", "manifest_only", "json",
+            "json_lines"
+        ]
+    )]
+    pub keep_versions: Option<u16>,
+
+    /// Mode of the directories the install creates, in octal (default `755`)
+    ///
+    /// Installed files get the same mode without its execute bits, unless they are
+    /// executable: `--mode 750` installs `0750` directories and executables and `0640` other
+    /// files, whatever the process umask or the archive say. Ignored on Windows.
+    #[arg(long, value_name = "OCTAL", value_parser = parse_mode)]
+    pub mode: Option<u32>,
+
+    /// Only install files matching this glob, relative to the archive root (repeatable)
+    ///
+    /// e.g. `--include 'bin/*' --include 'lib64/**'`. `*` does not match `/`, `**` matches
+    /// across directories. Defaults to every file.
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<Pattern>,
+
+    /// Do not install files matching this glob, e.g. `'include/**'` (repeatable)
+    ///
+    /// Takes precedence over `--include`.
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<Pattern>,
+
+    /// Prefer the statically-linked build where the release offers one
+    ///
+    /// Useful for minimal or distroless container images. Falls back to the dynamically-linked
+    /// build, with a warning, when no static build exists for the target.
+    #[arg(long)]
+    pub prefer_static: bool,
+
+    /// On Apple Silicon, install the x86_64 build when the release has no native one
+    ///
+    /// Older releases predate native ARM builds for macOS; their x86_64 build runs under
+    /// Rosetta 2. A warning is printed and the fallback is recorded in the install manifest.
+    #[arg(long, conflicts_with = "from_manifest")]
+    pub allow_rosetta: bool,
+
+    /// Which PATH to add the install to: the current user's, or the system-wide one
+    ///
+    /// `machine` writes to HKLM and requires administrator privileges.
+    #[cfg(windows)]
+    #[arg(long, value_enum, default_value_t)]
+    pub scope: shell_utils::PathScope,
+
+    /// Write an SBOM-like JSON record of the install to this file
+    ///
+    /// It lists the version, asset and its URL, the checksum and algorithm, the target
+    /// os/arch and the install time.
+    #[arg(long, value_name = "FILE")]
+    pub sbom: Option<PathBuf>,
+
+    /// Write a file that sets `WASMEDGE_VERSION` and puts the installed `bin` directory on PATH
+    ///
+    /// Meant to be sourced by later steps, e.g. of a CI pipeline. With `--multi` it describes the
+    /// version that ends up active.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["ephemeral_run", "print_url", "manifest_only"]
+    )]
+    pub write_env_file: Option<PathBuf>,
+
+    /// Shell syntax of the `--write-env-file` file
+    ///
+    /// Defaults to `sh` on Unix-like systems and `powershell` on Windows.
+    #[arg(long, value_enum, requires = "write_env_file")]
+    pub shell: Option<shell_utils::EnvFileShell>,
+
+    /// Print the asset and checksum file URLs that would be requested, then exit
+    ///
+    /// The version is resolved and the asset chosen as for a real install, but nothing is
+    /// downloaded; useful when troubleshooting mirrors and proxies.
+    #[arg(long, conflicts_with = "from_manifest")]
+    pub print_url: bool,
+
+    /// Resolve the version and its checksum, write them to this lockfile and exit
+    ///
+    /// The lockfile records the version, target, linkage, asset name and URL and the SHA-256
+    /// checksum, so that `--from-manifest` can reproduce the install on another machine.
+    /// Nothing is downloaded besides the checksum file.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["from_manifest", "print_url"])]
+    pub manifest_only: Option<PathBuf>,
+
+    /// Install exactly the archive pinned by a lockfile from `--manifest-only`
+    ///
+    /// The version, OS, architecture and linkage are taken from the lockfile, and the archive
+    /// is downloaded from the recorded URL and checked against the recorded checksum.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["version", "os", "arch", "prefer_static"]
+    )]
+    pub from_manifest: Option<PathBuf>,
+
+    /// Check that the release's git tag is an annotated tag before installing
+    ///
+    /// With `--tag-key`, its signature must also verify against that key. The tag is fetched
+    /// from the release repository's git remote (see `--repo`).
+    #[arg(long, conflicts_with_all = ["print_url", "from_manifest"])]
+    pub verify_tag: bool,
+
+    /// Public key the release tag must be signed with, for `--verify-tag`
+    ///
+    /// An ASCII-armored or binary OpenPGP key, e.g. a maintainer's exported key. Checking the
+    /// signature requires `gpg`. Ignored without `--verify-tag`.
+    #[arg(long, value_name = "FILE", env = "WASMEDGEUP_TAG_KEY")]
+    pub tag_key: Option<PathBuf>,
+
+    /// Check the release's checksum file against its detached signature before trusting it
+    ///
+    /// The signature is fetched from `SHA256SUM.asc` next to `SHA256SUM` and must verify
+    /// against `--signature-key`; a missing or bad signature fails the install. Checking it
+    /// requires `gpg`. The download cache is bypassed.
+    #[arg(
+        long,
+        requires = "signature_key",
+        conflicts_with_all = ["expected_checksum", "no_verify", "url", "from_manifest"]
+    )]
+    pub verify_signature: bool,
+
+    /// Public key the checksum file must be signed with, for `--verify-signature`
+    ///
+    /// An ASCII-armored or binary OpenPGP key. Ignored without `--verify-signature`.
+    #[arg(long, value_name = "FILE", env = "WASMEDGEUP_SIGNATURE_KEY")]
+    pub signature_key: Option<PathBuf>,
+
+    /// Print the result of a successful install as a JSON object
+    ///
+    /// It holds the version, install location, linkage, installed size in bytes and the
+    /// duration in milliseconds, and is printed even with `--quiet`.
+    #[arg(
+        long,
+        conflicts_with_all = ["multi", "ephemeral_run", "print_url", "manifest_only"]
+    )]
+    pub json: bool,
+
+    /// Print newline-delimited JSON progress events on stdout as the install proceeds
+    ///
+    /// Each line is an object whose `event` is `resolve`, `download` (every percent, with
+    /// `pct`), `verify`, `extract`, then `done` with the `--json` fields or `error` with a
+    /// `message`. Progress bars and the summary line are not shown; logs go to stderr.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "multi", "interactive", "ephemeral_run", "print_url", "manifest_only"]
+    )]
+    pub json_lines: bool,
+
+    /// Verify the archive against this SHA-256 checksum instead of the release's checksum file
+    ///
+    /// For a hash known from another source, e.g. when the checksum file is unavailable; the
+    /// checksum file is not fetched at all.
+    #[arg(
+        long,
+        visible_alias = "sha",
+        value_name = "HEX",
+        value_parser = parse_checksum,
+        conflicts_with = "from_manifest"
+    )]
+    pub expected_checksum: Option<String>,
+
+    /// Install without verifying the archive's checksum
+    ///
+    /// For old releases that predate published checksum files. The checksum file is not
+    /// fetched and the download cache is bypassed; the archive's checksum is only computed,
+    /// for the install manifest.
+    #[arg(
+        long,
+        conflicts_with_all = ["expected_checksum", "from_manifest", "manifest_only"]
+    )]
+    pub no_verify: bool,
+
+    /// Also write the diagnostics printed when the install fails to this file
+    ///
+    /// They list the resolved version, target, every request sent with its HTTP status, the
+    /// number of retries and the final error, for attaching to bug reports.
+    #[arg(long, value_name = "FILE", conflicts_with = "multi")]
+    pub diagnostics: Option<PathBuf>,
+
+    /// Download and install the archive at this exact URL, e.g. a CI-built artifact
+    ///
+    /// The version is not resolved but read from the URL's file name (or taken from `--label`),
+    /// and no release checksum file is fetched: the archive is only verified when
+    /// `--expected-checksum` is given.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "version", "multi", "from_manifest", "manifest_only", "prefer_static",
+            "allow_rosetta", "verify_tag"
+        ]
+    )]
+    pub url: Option<Url>,
+
+    /// Version to install the `--url` archive as, e.g. `0.15.0-ci.1234`
+    ///
+    /// Needed when the URL's file name contains no version.
+    #[arg(
+        long,
+        value_name = "VERSION",
+        requires = "url",
+        conflicts_with = "version",
+        value_parser = parse_label
+    )]
+    pub label: Option<Version>,
+
+    /// Pick the version to install from a menu of releases, newest first
+    ///
+    /// Pre-releases can be shown or hidden from the menu. Fails instead of prompting when not
+    /// run in a terminal.
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["version", "multi", "from_manifest", "url"]
+    )]
+    pub interactive: bool,
+
+    /// Never prompt for input, for scripts that must not block on it
+    ///
+    /// With `--interactive` the install fails instead of showing the menu.
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Take the options not given as flags from `WASMEDGEUP_*` environment variables
+    ///
+    /// Meant for container images configured with `ENV`. A flag always wins over its variable,
+    /// which wins over the default. Read are `WASMEDGEUP_VERSION` (default `latest`),
+    /// `WASMEDGEUP_PATH`, `WASMEDGEUP_TMPDIR`, `WASMEDGEUP_OS`, `WASMEDGEUP_ARCH`,
+    /// `WASMEDGEUP_PREFER_STATIC`, `WASMEDGEUP_SETUP_PATH`, `WASMEDGEUP_PREPEND_PATH`,
+    /// `WASMEDGEUP_CHECKSUM_CACHE_TTL` and `WASMEDGEUP_MIRROR`, a base URL to download release
+    /// assets and checksums from instead of GitHub.
+    #[arg(long)]
+    pub from_env: bool,
+}
+
+/// What `install --read-only` makes read-only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReadOnlyScope {
+    /// The installed files only
+    Files,
+    /// The files and the directories holding them
+    All,
 }
 
 impl CommandExecutor for InstallArgs {
@@ -65,63 +555,740 @@ impl CommandExecutor for InstallArgs {
     ///
     /// Returns an error if any step fails, such as download failure, extraction issues,
     /// or copying issues.
+    ///
+    /// On Ctrl-C the staging directory is removed and [`Error::Interrupted`] returned.
+    ///
+    /// With `--multi`, every version given is installed in one batch; see `install_batch`.
+    /// With `--keep-versions`, older versions are pruned once the install succeeded.
     #[tracing::instrument(name = "install", skip_all, fields(version = self.version))]
-    async fn execute(mut self, ctx: CommandContext) -> Result<()> {
-        let version = ctx.client.resolve_version(&self.version).inspect_err(
-            |e| tracing::error!(error = %e.to_string(), "Failed to resolve version"),
-        )?;
+    async fn execute(mut self, mut ctx: CommandContext) -> Result<()> {
+        if self.from_env {
+            let env = Environment::current();
+            ctx.client = self.apply_env(ctx.client, |name| env.var(name))?;
+        }
+        if let Some(key) = self
+            .signature_key
+            .as_ref()
+            .filter(|_| self.verify_signature)
+        {
+            ctx.client = ctx.client.with_checksums_key(key);
+        }
+        let prune = match self.keep_versions {
+            Some(keep) => Some((self.path.clone().map_or_else(default_path, Ok)?, keep)),
+            None => None,
+        };
+        let result = self.install_and_report(ctx).await;
+        if let (Ok(()), Some((target_dir, keep))) = (&result, prune) {
+            let pinned = super::prune::pinned_versions(&target_dir)?;
+            let retain = |rank, version: &Version| rank < keep.into() || pinned.contains(version);
+            let (pruned, reclaimed) =
+                super::prune::prune_versions(&target_dir, &retain, false).await?;
+            if pruned > 0 {
+                println!(
+                    "Pruned {pruned} version{}, keeping the {keep} newest; {} reclaimed",
+                    if pruned == 1 { "" } else { "s" },
+                    ByteSize(reclaimed)
+                );
+            }
+        }
+        result
+    }
+}
+
+impl InstallArgs {
+    /// The install proper, or the batch of `--multi`, with the diagnostics of a failure.
+    async fn install_and_report(self, mut ctx: CommandContext) -> Result<()> {
+        if self.multi {
+            return self.install_batch(ctx).await;
+        }
+        let json_lines = self.json_lines;
+        if json_lines {
+            ctx.no_progress = true;
+            ctx.client = ctx.client.with_progress_events(true);
+        }
+        let diagnostics = self.diagnostics.clone();
+        let mut report = FailureReport::new(&self.version);
+        let client = ctx.client.clone();
+        let result = self.run(ctx, true, &mut report).await;
+        let Err(e) = &result else {
+            return result;
+        };
+        // Quiet and scripted runs get the diagnostics too, as that is where bug reports come from.
+        let text =
+            (!matches!(e, Error::Interrupted)).then(|| report.render(client.request_log(), e));
+        match (&text, json_lines) {
+            (Some(text), true) => events::emit(&ProgressEvent::Diagnostics { report: text }),
+            (Some(text), false) if !super::quiet_errors() => eprint!("{text}"),
+            _ => {}
+        }
+        if json_lines {
+            events::emit(&ProgressEvent::Error {
+                message: e.to_string(),
+            });
+        }
+        if let (Some(text), Some(path)) = (&text, diagnostics) {
+            match std::fs::write(&path, text) {
+                Ok(()) => eprintln!("Diagnostics written to {}", path.display()),
+                Err(e) => {
+                    tracing::warn!(error = %e, path = %path.display(), "Failed to write diagnostics")
+                }
+            }
+        }
+        result
+    }
+
+    /// Installs this version, stopping it if Ctrl-C interrupts it.
+    ///
+    /// Unless `activate` is set, the version symlinks, PATH setup and the closing message are
+    /// left to the caller, as for each version of a `--multi` batch.
+    ///
+    /// What the install resolved is noted in `report`, for the diagnostics of a failure.
+    async fn run(
+        self,
+        ctx: CommandContext,
+        activate: bool,
+        report: &mut FailureReport,
+    ) -> Result<()> {
+        tokio::select! {
+            // Polled first so an install that completes together with a Ctrl-C still succeeds.
+            biased;
+            result = self.install(ctx, activate, report) => result,
+            // Dropping the install future drops its `TempDirGuard`s, which remove what it staged.
+            _ = tokio::signal::ctrl_c() => Err(Error::Interrupted),
+        }
+    }
+
+    /// Installs every version of a `--multi` batch, at most `--jobs` at a time.
+    ///
+    /// Versions are resolved up front, so that e.g. `latest 0.14.1` installs 0.14.1 only once.
+    /// A failure stops nothing unless `--fail-fast` is given; Ctrl-C always stops the versions
+    /// that have not started yet. Once all are done, the last successfully installed version in
+    /// argument order is activated and PATH set up, and each version's result is printed.
+    async fn install_batch(mut self, ctx: CommandContext) -> Result<()> {
+        let mut requested = vec![std::mem::take(&mut self.version)];
+        requested.append(&mut self.versions);
+        let target_dir = match self.path.take() {
+            Some(p) => p,
+            None => default_path()?,
+        };
+        self.path = Some(target_dir.clone());
+        self.multi = false;
+        let jobs = self.jobs.map_or(DEFAULT_JOBS, usize::from);
+        let fail_fast = self.fail_fast;
+        tracing::debug!(versions = ?requested, jobs, "Installing versions in a batch");
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut outcomes = Vec::new();
+        let mut versions = Vec::new();
+        for requested in requested {
+            match ctx.client.resolve_version(&requested).await {
+                Ok(version) if versions.contains(&version) => {
+                    tracing::debug!(%requested, %version, "Skipping version listed twice");
+                }
+                Ok(version) => {
+                    versions.push(version.clone());
+                    outcomes.push((version.to_string(), BatchOutcome::Skipped));
+                }
+                Err(e) => {
+                    stop.fetch_or(fail_fast, Ordering::SeqCst);
+                    outcomes.push((requested, BatchOutcome::Failed(e)));
+                }
+            }
+        }
+
+        let progress = MultiProgress::new();
+        let ctx = CommandContext {
+            client: ctx.client.with_progress(progress.clone()),
+            ..ctx
+        };
+        let permits = Arc::new(Semaphore::new(jobs));
+        let mut tasks = JoinSet::new();
+        for version in versions {
+            let args = InstallArgs {
+                version: version.to_string(),
+                ..self.clone()
+            };
+            let (ctx, permits, stop) = (ctx.clone(), permits.clone(), stop.clone());
+            tasks.spawn(async move {
+                let _permit = permits
+                    .acquire_owned()
+                    .await
+                    .expect("semaphore is never closed");
+                if stop.load(Ordering::SeqCst) {
+                    return (version, None);
+                }
+                let result = args.run(ctx, false, &mut FailureReport::default()).await;
+                match &result {
+                    Err(Error::Interrupted) => stop.store(true, Ordering::SeqCst),
+                    Err(_) => {
+                        stop.fetch_or(fail_fast, Ordering::SeqCst);
+                    }
+                    Ok(()) => {}
+                }
+                (version, Some(result))
+            });
+        }
+
+        let mut interrupted = false;
+        while let Some(joined) = tasks.join_next().await {
+            let (version, result) =
+                joined.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+            let outcome = match result {
+                None => BatchOutcome::Skipped,
+                Some(Ok(())) => BatchOutcome::Installed,
+                Some(Err(e)) => {
+                    interrupted |= matches!(e, Error::Interrupted);
+                    BatchOutcome::Failed(e)
+                }
+            };
+            let version = version.to_string();
+            if let Some(entry) = outcomes.iter_mut().find(|(v, _)| *v == version) {
+                entry.1 = outcome;
+            }
+        }
+        let _ = progress.clear();
+
+        for (version, outcome) in &outcomes {
+            match outcome {
+                BatchOutcome::Installed => println!("WasmEdge {version}: installed"),
+                BatchOutcome::Failed(e) => println!("WasmEdge {version}: failed: {e}"),
+                BatchOutcome::Skipped => println!("WasmEdge {version}: skipped"),
+            }
+        }
+        if interrupted {
+            return Err(Error::Interrupted);
+        }
+
+        let active = outcomes
+            .iter()
+            .rev()
+            .find(|(_, outcome)| matches!(outcome, BatchOutcome::Installed));
+        if let Some((version, _)) = active {
+            crate::fs::create_version_symlinks(&target_dir, version).await?;
+            if self.should_setup_path() {
+                self.add_to_path(&target_dir)?;
+            }
+            if let Some(path) = &self.write_env_file {
+                let bin_dir = target_dir.join("versions").join(version).join("bin");
+                write_env(path, self.shell, version, &bin_dir)?;
+            }
+            println!(
+                "Active version: {version}\nInstall root: {}",
+                target_dir.display()
+            );
+        }
+
+        let failed = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, BatchOutcome::Failed(_)))
+            .count();
+        if failed > 0 {
+            return Err(Error::BatchInstallFailed {
+                failed,
+                total: outcomes.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether PATH setup runs, resolving `--setup-path[=BOOL]` against `--no-setup-path`.
+    pub fn should_setup_path(&self) -> bool {
+        !self.no_setup_path && self.setup_path.unwrap_or(true)
+    }
+
+    /// Adds `target_dir` to PATH, first with `--prepend-path`, and warns when another
+    /// `wasmedge` comes before it on the current PATH, as it would keep running instead.
+    fn add_to_path(&self, target_dir: &Path) -> Result<()> {
+        #[cfg(windows)]
+        shell_utils::setup_path_for(target_dir, self.scope, self.prepend_path)?;
+        #[cfg(not(windows))]
+        shell_utils::setup_path_with(target_dir, self.prepend_path)?;
+
+        if let Some(other) = shell_utils::shadowing_wasmedge(target_dir) {
+            let bin_dir = target_dir.join("bin");
+            if self.prepend_path {
+                tracing::info!(shadowed = %other.display(), bin = %bin_dir.display(), "New shells will find this install's wasmedge first on PATH");
+            } else {
+                tracing::warn!(
+                    other = %other.display(),
+                    bin = %bin_dir.display(),
+                    "Another wasmedge comes first on PATH and will keep shadowing this install; rerun with --prepend-path to put its bin directory first, or remove the conflicting package"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills in the options `--from-env` takes from the environment, as read by `var`, leaving
+    /// those given as flags alone. Empty variables count as unset.
+    ///
+    /// `WASMEDGEUP_MIRROR` replaces where `client` downloads from, unless `--repo` already did.
+    pub fn apply_env(
+        &mut self,
+        client: WasmEdgeApiClient,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<WasmEdgeApiClient> {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let invalid = |name: &str, value: &str, reason: &str| Error::InvalidEnv {
+            name: name.to_string(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        };
+        let flag = |name: &str| -> Result<Option<bool>> {
+            let Some(value) = var(name) else {
+                return Ok(None);
+            };
+            match value.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Ok(Some(true)),
+                "0" | "false" | "no" | "off" => Ok(Some(false)),
+                _ => Err(invalid(name, &value, "expected true or false")),
+            }
+        };
+
+        let versionless =
+            self.multi || self.url.is_some() || self.from_manifest.is_some() || self.interactive;
+        if self.version.is_empty() && !versionless {
+            self.version = var("WASMEDGEUP_VERSION").unwrap_or_else(|| "latest".to_string());
+        }
+        if self.path.is_none() && self.prefix.is_none() && !self.ephemeral && !self.ephemeral_run {
+            self.path = var("WASMEDGEUP_PATH").map(PathBuf::from);
+        }
+        if self.tmpdir.is_none() && !self.temp_on_target {
+            self.tmpdir = var("WASMEDGEUP_TMPDIR").map(PathBuf::from);
+        }
+        if let (None, Some(value)) = (self.os, var("WASMEDGEUP_OS")) {
+            let os = TargetOS::from_str(&value, true)
+                .map_err(|_| invalid("WASMEDGEUP_OS", &value, "unknown operating system"))?;
+            self.os = Some(os);
+        }
+        if let (None, Some(value)) = (self.arch, var("WASMEDGEUP_ARCH")) {
+            let arch = if value.eq_ignore_ascii_case("native") {
+                TargetArch::default()
+            } else {
+                TargetArch::from_str(&value, true)
+                    .map_err(|_| invalid("WASMEDGEUP_ARCH", &value, "unknown architecture"))?
+            };
+            self.arch = Some(arch);
+        }
+        if !self.prefer_static {
+            self.prefer_static = flag("WASMEDGEUP_PREFER_STATIC")?.unwrap_or(false);
+        }
+        if self.setup_path.is_none() && !self.no_setup_path {
+            self.setup_path = flag("WASMEDGEUP_SETUP_PATH")?;
+        }
+        if let (None, Some(value)) = (
+            self.checksum_cache_ttl,
+            var("WASMEDGEUP_CHECKSUM_CACHE_TTL"),
+        ) {
+            let ttl = value
+                .parse()
+                .map_err(|e: String| invalid("WASMEDGEUP_CHECKSUM_CACHE_TTL", &value, &e))?;
+            self.checksum_cache_ttl = Some(ttl);
+        }
+        if !self.prepend_path {
+            self.prepend_path = flag("WASMEDGEUP_PREPEND_PATH")?.unwrap_or(false);
+        }
+
+        let Some(mirror) = var("WASMEDGEUP_MIRROR") else {
+            return Ok(client);
+        };
+        if client.release_base_url != RepoSlug::default().release_base_url() {
+            tracing::debug!(%mirror, "Ignoring WASMEDGEUP_MIRROR as --repo was given");
+            return Ok(client);
+        }
+        Url::parse(&mirror).map_err(|e| invalid("WASMEDGEUP_MIRROR", &mirror, &e.to_string()))?;
+        tracing::debug!(%mirror, "Downloading release files from mirror");
+        Ok(client.with_release_base_url(mirror.trim_end_matches('/')))
+    }
+
+    /// Rejects a cross-OS install that leaves the architecture to host detection, since it is
+    /// unclear whether the host architecture was meant, and warns about deliberate ones.
+    fn check_cross_install(&self) -> Result<()> {
+        let Some(os) = self.os.filter(|os| !os.matches_host()) else {
+            return Ok(());
+        };
+        let host = std::env::consts::OS;
+        let Some(arch) = self.arch else {
+            return Err(Error::CrossInstallArchRequired {
+                os: format!("{os:?}"),
+                host: host.to_string(),
+            });
+        };
+        tracing::warn!(
+            ?os,
+            ?arch,
+            host,
+            "Cross-installing for another OS; the installed runtime will not run on this machine"
+        );
+        Ok(())
+    }
+
+    /// Rejects leaving the architecture to detection when wasmedgeup seems to run under
+    /// emulation, e.g. QEMU user mode in a multi-arch image build, where the architecture it
+    /// was built for may not be the one the image is for.
+    fn check_emulated_arch(&self) -> Result<()> {
+        if self.arch.is_some() || self.url.is_some() {
+            return Ok(());
+        }
+        let Some(emulation) = crate::system::emulation::detect_emulation() else {
+            return Ok(());
+        };
+        tracing::debug!(?emulation, "Emulation detected");
+        Err(Error::EmulatedArchRequired {
+            marker: emulation.marker,
+            build: format!("{:?}", TargetArch::default()),
+        })
+    }
+
+    /// The install itself; the directories it stages are removed if it stops before finishing.
+    async fn install(
+        mut self,
+        ctx: CommandContext,
+        activate: bool,
+        report: &mut FailureReport,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let pin = self
+            .from_manifest
+            .as_deref()
+            .map(InstallLock::load)
+            .transpose()?;
+        if let Some(lock) = &pin {
+            tracing::debug!(version = %lock.version, asset = %lock.asset, "Installing from lockfile");
+            self.version = lock.version.clone();
+            self.os = Some(lock.os);
+            self.arch = Some(lock.arch);
+        }
+        self.check_cross_install()?;
+        self.check_emulated_arch()?;
+
+        let mut timings = PhaseTimings::default();
+        let version = match &self.url {
+            Some(url) => match self.label.clone().or_else(|| version_in_url(url)) {
+                Some(version) => version,
+                None => {
+                    return Err(Error::UrlVersionUnknown {
+                        url: url.to_string(),
+                    })
+                }
+            },
+            None if self.interactive => pick_version(&ctx.client, self.yes).await?,
+            None => timings
+                .time("resolve", ctx.client.resolve_version(&self.version))
+                .await
+                .inspect_err(
+                    |e| tracing::error!(error = %e.to_string(), "Failed to resolve version"),
+                )?,
+        };
+        // Channels and partial versions are resolved from the listing already.
+        let exact = Version::parse(&self.version).is_ok();
+        if exact && self.url.is_none() && pin.is_none() && !self.print_url {
+            timings
+                .time("resolve", ctx.client.ensure_published(&version))
+                .await?;
+        }
         tracing::debug!(%version, "Resolved version for installation");
+        report.version = Some(version.to_string());
+        if self.json_lines {
+            events::emit(&ProgressEvent::Resolve {
+                version: version.to_string(),
+            });
+        }
+
+        let setup_path = self.should_setup_path();
 
         let os = self.os.get_or_insert_default();
         let arch = self.arch.get_or_insert_default();
         tracing::debug!(?os, ?arch, "Host OS and architecture detected");
+        report.target = Some((*os, *arch));
+
+        let asset = if let Some(url) = &self.url {
+            Asset {
+                archive_name: url
+                    .path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or("archive")
+                    .to_string(),
+                ..Asset::new(&version, os, arch)
+            }
+        } else if let Some(lock) = &pin {
+            Asset {
+                archive_name: lock.asset.clone(),
+                linkage: lock.linkage,
+                ..Asset::new(&version, os, arch)
+            }
+        } else if self.prefer_static {
+            Asset::new_static(&version, os, arch).unwrap_or_else(|| {
+                tracing::warn!(
+                    ?os,
+                    ?arch,
+                    "No statically-linked build for this target; using the dynamic build"
+                );
+                Asset::new(&version, os, arch)
+            })
+        } else {
+            Asset::new(&version, os, arch)
+        };
+
+        if let (true, Some(url)) = (self.print_url, &self.url) {
+            println!("asset: {url}");
+            return Ok(());
+        }
+        if self.print_url {
+            println!("asset: {}", ctx.client.asset_url(&asset)?);
+            println!("checksums: {}", ctx.client.checksum_url(&version)?);
+            return Ok(());
+        }
+
+        if self.verify_tag {
+            timings
+                .time(
+                    "verify-tag",
+                    ctx.client
+                        .verify_release_tag(&version, self.tag_key.as_deref()),
+                )
+                .await
+                .inspect_err(
+                    |e| tracing::error!(error = %e.to_string(), "Release tag verification failed"),
+                )?;
+        }
 
-        let asset = Asset::new(&version, os, arch);
+        if let Some(path) = &self.manifest_only {
+            let source = ctx
+                .client
+                .checksum_source(self.expected_checksum.as_deref());
+            let checksum = timings
+                .time("checksum", source.checksum(&ctx.client, &version, &asset))
+                .await;
+            let (asset, checksum) = match checksum {
+                Err(Error::ChecksumNotFound { .. }) if asset.linkage == Linkage::Static => {
+                    tracing::warn!(%version, archive = %asset.archive_name, "Release has no statically-linked build; using the dynamic build");
+                    let dynamic = Asset::new(&version, os, arch);
+                    let checksum = ctx.client.get_release_checksum(&version, &dynamic).await?;
+                    (dynamic, checksum)
+                }
+                Err(Error::ChecksumNotFound { .. })
+                    if self.allow_rosetta && needs_rosetta(os, arch) =>
+                {
+                    warn_rosetta_fallback(&version, &asset);
+                    report.target = Some((*os, TargetArch::X86_64));
+                    *arch = TargetArch::X86_64;
+                    let x86_64 = Asset::new(&version, os, arch);
+                    let checksum = ctx.client.get_release_checksum(&version, &x86_64).await?;
+                    (x86_64, checksum)
+                }
+                checksum => (asset, checksum?),
+            };
+            let lock = InstallLock {
+                version: version.to_string(),
+                os: *os,
+                arch: *arch,
+                linkage: asset.linkage,
+                url: Some(ctx.client.asset_url(&asset)?.to_string()),
+                asset: asset.archive_name.clone(),
+                checksum: SbomChecksum {
+                    algorithm: "sha256".to_string(),
+                    value: checksum,
+                },
+            };
+            lock.write(path)?;
+            println!(
+                "Pinned WasmEdge {version} ({}) in {}",
+                asset.archive_name,
+                path.display()
+            );
+            return Ok(());
+        }
 
         // Create a dedicated temporary workspace for this installation. This provides isolation
         // between concurrent installations and ensures consistent handling of different archive
         // structures. The source path for copying will be either:
         //   - /tmp/WasmEdge-version-os/ (for archives with root-level files)
-        //   - /tmp/WasmEdge-version-os/WasmEdge-version-os/ (for nested archives)
-        let tmpdir = self
-            .tmpdir
-            .unwrap_or_else(default_tmpdir)
-            .join(&asset.install_name);
+        //   - /tmp/WasmEdge-version-os/WasmEdge-version-os/ (for nested archives, or deeper
+        //     with more wrapping directories; see `--strip-components`)
+        let staging_root = match self.tmpdir.take() {
+            Some(dir) => dir,
+            None if self.temp_on_target => self
+                .path
+                .clone()
+                .or_else(|| self.prefix.clone())
+                .map_or_else(default_path, Ok)?
+                .join(TARGET_STAGING_DIR),
+            None => default_tmpdir(),
+        };
+        let tmpdir = staging_root.join(&asset.install_name);
+        crate::fs::warn_if_symlink(&tmpdir, "Temporary directory");
+        if self.force_fresh_tmp && fs::symlink_metadata(&tmpdir).await.is_ok() {
+            tracing::debug!(tmpdir = %tmpdir.display(), "Removing existing temporary directory");
+            crate::fs::remove_dir_all_safe(&tmpdir)
+                .await
+                .context(IoSnafu {
+                    action: "remove existing temporary directory".to_string(),
+                    path: tmpdir.display().to_string(),
+                })?;
+        } else if std::fs::read_dir(&tmpdir).is_ok_and(|mut entries| entries.next().is_some()) {
+            tracing::warn!(tmpdir = %tmpdir.display(), "Temporary directory already has files, e.g. from an interrupted install; pass --force-fresh-tmp to remove them first");
+        }
         fs::create_dir_all(&tmpdir).await.inspect_err(
             |e| tracing::error!(error = %e.to_string(), "Failed to create temporary directory"),
         )?;
         tracing::debug!(tmpdir = %tmpdir.display(), "Created temporary directory");
+        let tmpdir_guard = TempDirGuard::staging(&tmpdir).context(IoSnafu {
+            action: "mark temporary directory".to_string(),
+            path: tmpdir.display().to_string(),
+        })?;
 
-        let expected_checksum = ctx
-            .client
-            .get_release_checksum(&version, &asset)
-            .await
-            .inspect_err(|e| tracing::error!(error = %e.to_string(), "Failed to get checksum"))?;
-        tracing::debug!(%expected_checksum, "Got release checksum");
-
-        let mut file = ctx
-            .client
-            .download_asset(&asset, &tmpdir, ctx.no_progress)
-            .await
-            .inspect_err(|e| tracing::error!(error = %e.to_string(), "Failed to download asset"))?
-            .into_file();
-        WasmEdgeApiClient::verify_file_checksum(&mut file, &expected_checksum)
-            .await
-            .inspect_err(
-                |e| tracing::error!(error = %e.to_string(), "Checksum verification failed"),
-            )?;
+        let cache = if self.no_cache || ctx.client.refresh {
+            None
+        } else {
+            DownloadCache::default_location()
+                .map(|cache| cache.with_ttl(self.checksum_cache_ttl.and_then(|ttl| ttl.0)))
+        };
+        let mut rosetta = false;
+        let pinned = match &pin {
+            Some(lock) => Pinned {
+                checksum: Some(lock.checksum.value.to_ascii_lowercase()),
+                url: lock.url.clone(),
+            },
+            None => Pinned {
+                checksum: self.expected_checksum.clone(),
+                url: None,
+            },
+        };
+        let fetched = match &self.url {
+            Some(url) => {
+                if self.expected_checksum.is_none() {
+                    tracing::warn!(%url, "Installing from a URL without --expected-checksum; the archive is not verified");
+                }
+                fetch_url_archive(
+                    &ctx,
+                    url,
+                    self.expected_checksum.as_deref(),
+                    &tmpdir,
+                    &mut timings,
+                )
+                .await
+            }
+            None if self.no_verify => {
+                let url = ctx.client.asset_url(&asset)?;
+                tracing::warn!(%version, %url, "Installing with --no-verify; the archive is not verified");
+                fetch_url_archive(&ctx, &url, None, &tmpdir, &mut timings).await
+            }
+            None => {
+                fetch_verified_archive(
+                    &ctx,
+                    cache.as_ref(),
+                    &version,
+                    &asset,
+                    &pinned,
+                    &tmpdir,
+                    &mut timings,
+                )
+                .await
+            }
+        };
+        let (asset, (mut file, checksum)) = match fetched {
+            // Not every release publishes a static build; its absence from the checksum file
+            // is the sign, so fall back rather than fail.
+            Err(Error::ChecksumNotFound { .. }) if asset.linkage == Linkage::Static => {
+                tracing::warn!(%version, archive = %asset.archive_name, "Release has no statically-linked build; using the dynamic build");
+                let dynamic = Asset::new(&version, os, arch);
+                let fetched = fetch_verified_archive(
+                    &ctx,
+                    cache.as_ref(),
+                    &version,
+                    &dynamic,
+                    &Pinned::default(),
+                    &tmpdir,
+                    &mut timings,
+                )
+                .await?;
+                (dynamic, fetched)
+            }
+            // Same for native Apple Silicon builds, which older releases lack.
+            Err(Error::ChecksumNotFound { .. })
+                if self.allow_rosetta && needs_rosetta(os, arch) =>
+            {
+                warn_rosetta_fallback(&version, &asset);
+                report.target = Some((*os, TargetArch::X86_64));
+                rosetta = true;
+                *arch = TargetArch::X86_64;
+                let x86_64 = Asset::new(&version, os, arch);
+                let fetched = fetch_verified_archive(
+                    &ctx,
+                    cache.as_ref(),
+                    &version,
+                    &x86_64,
+                    &Pinned::default(),
+                    &tmpdir,
+                    &mut timings,
+                )
+                .await?;
+                (x86_64, fetched)
+            }
+            fetched => (asset, fetched?),
+        };
         tracing::debug!("Checksum verified successfully");
+        if self.json_lines {
+            events::emit(&ProgressEvent::Verify {
+                checksum: &checksum,
+            });
+        }
 
+        let filter = CopyFilter {
+            include: std::mem::take(&mut self.include),
+            exclude: std::mem::take(&mut self.exclude),
+            // In prefix mode the staging directory is fresh; see `install_into_prefix`.
+            preserve_existing: self.preserve_existing && self.prefix.is_none(),
+        };
         tracing::debug!(dest = %tmpdir.display(), "Starting extraction of asset");
-        crate::fs::extract_archive(&mut file, &tmpdir)
+        // With --include/--exclude the files they leave out are not even extracted.
+        let skipped = AtomicUsize::new(0);
+        let entry_filter = |path: &Path| {
+            let selected = filter.matches_entry(path, self.strip_components);
+            if !selected {
+                skipped.fetch_add(1, Ordering::Relaxed);
+            }
+            selected
+        };
+        let extraction = async {
+            if filter.is_empty() {
+                return crate::fs::extract_archive(&mut file, &tmpdir).await;
+            }
+            match crate::fs::extract_archive_matching(&mut file, &tmpdir, Some(&entry_filter))
+                .await?
+            {
+                0 => Err(Error::NoFilesSelected),
+                _ => Ok(()),
+            }
+        };
+        timings
+            .time("extract", extraction)
             .await
             .inspect_err(|e| tracing::error!(error = %e.to_string(), "Failed to extract asset"))?;
         tracing::debug!(dest = %tmpdir.display(), "Extraction completed successfully");
+        if self.json_lines {
+            events::emit(&ProgressEvent::Extract);
+        }
 
-        let target_dir = match self.path {
+        let ephemeral = self.ephemeral || self.ephemeral_run;
+        let prefix_mode = self.prefix.is_some();
+        let mode = self.mode.unwrap_or(crate::fs::DEFAULT_DIR_MODE);
+        let mut ephemeral_guard = None;
+        let target_dir = match self.path.as_ref().or(self.prefix.as_ref()).cloned() {
             Some(p) => p,
+            None if ephemeral => {
+                let dir = tempfile::Builder::new()
+                    .prefix("wasmedgeup-ephemeral-")
+                    .tempdir()?
+                    .keep();
+                ephemeral_guard = Some(TempDirGuard::new(&dir));
+                dir
+            }
             None => default_path()?,
         };
+        crate::fs::warn_if_symlink(&target_dir, "Install location");
 
         if target_dir.exists() {
             if crate::fs::can_write_to_directory(&target_dir) {
@@ -136,6 +1303,10 @@ impl CommandExecutor for InstallArgs {
         } else {
             match fs::create_dir_all(&target_dir).await {
                 Ok(_) => {
+                    crate::fs::set_dir_mode(&target_dir, mode).context(IoSnafu {
+                        action: "set the mode of the install location".to_string(),
+                        path: target_dir.display().to_string(),
+                    })?;
                     if !crate::fs::can_write_to_directory(&target_dir) {
                         tracing::debug!(path = %target_dir.display(), "Created directory but cannot write to it");
                         return Err(crate::commands::insufficient_permissions(
@@ -157,50 +1328,979 @@ impl CommandExecutor for InstallArgs {
             }
         }
 
-        let version_dir = target_dir.join("versions").join(version.to_string());
+        let mut prefix_staging_guard = None;
+        let version_dir = if prefix_mode {
+            // Staged next to the extracted archive and copied into the prefix once recorded, so
+            // the manifest lists exactly the files this install adds to it.
+            let dir = tmpdir.with_file_name(format!("{}-prefix", asset.install_name));
+            if dir.exists() {
+                crate::fs::remove_dir_all_safe(&dir)
+                    .await
+                    .context(IoSnafu {
+                        action: "remove stale prefix staging directory".to_string(),
+                        path: dir.display().to_string(),
+                    })?;
+            }
+            prefix_staging_guard = Some(TempDirGuard::staging(&dir).context(IoSnafu {
+                action: "mark prefix staging directory".to_string(),
+                path: dir.display().to_string(),
+            })?);
+            dir
+        } else {
+            target_dir.join("versions").join(version.to_string())
+        };
+        if self.force && !prefix_mode && version_dir.exists() {
+            crate::fs::remove_dir_all_safe(&version_dir)
+                .await
+                .context(IoSnafu {
+                    action: "remove existing version directory".to_string(),
+                    path: version_dir.display().to_string(),
+                })?;
+            tracing::debug!(version_dir = %version_dir.display(), "Removed existing version directory");
+        } else if !prefix_mode && version_dir.exists() {
+            // An earlier `--read-only` install would refuse to be overwritten.
+            crate::fs::make_writable_blocking(&version_dir).context(IoSnafu {
+                action: "restore write permission on existing version directory".to_string(),
+                path: version_dir.display().to_string(),
+            })?;
+        }
+        let versions_dir = version_dir
+            .parent()
+            .filter(|dir| !prefix_mode && !dir.exists());
         fs::create_dir_all(&version_dir).await.inspect_err(
             |e| tracing::error!(error = %e.to_string(), "Failed to create version directory"),
         )?;
+        if let Some(dir) = versions_dir {
+            crate::fs::set_dir_mode(dir, mode).context(IoSnafu {
+                action: "set the mode of the versions directory".to_string(),
+                path: dir.display().to_string(),
+            })?;
+        }
         tracing::debug!(version_dir = %version_dir.display(), "Created version directory");
 
-        let mut read_dir = fs::read_dir(&tmpdir).await?;
-        let mut source_dir = tmpdir.clone();
-
+        let strip = match self.strip_components {
+            Some(count) => count,
+            None => crate::fs::wrapper_depth(&tmpdir).await?,
+        };
+        let source_dir = crate::fs::strip_components(&tmpdir, strip).await?;
+        let mut read_dir = fs::read_dir(&source_dir).await?;
         if let Some(entry) = read_dir.next_entry().await? {
             let file_name = entry.file_name().into_string().unwrap_or_default();
-            if file_name.starts_with("WasmEdge-") && entry.file_type().await?.is_dir() {
-                source_dir = entry.path();
-            } else if !matches!(file_name.as_str(), "bin" | "lib64" | "include" | "lib") {
-                tracing::debug!(found_file = %file_name, "Unexpected file found in archive");
+            if !crate::fs::LAYOUT_DIRS.contains(&file_name.as_str()) {
+                tracing::debug!(found_file = %file_name, strip, "Unexpected file found in archive");
                 return Err(Error::InvalidArchiveStructure {
                     found_file: file_name,
                 });
             }
         } else {
-            tracing::debug!(dir = %tmpdir.display(), "Archive directory is empty");
+            tracing::debug!(dir = %source_dir.display(), "Archive directory is empty");
             return Err(Error::InvalidArchiveStructure {
                 found_file: "<empty directory>".to_string(),
             });
         }
+        let runtime = if matches!(os, TargetOS::Windows) {
+            "bin/wasmedge.exe"
+        } else {
+            "bin/wasmedge"
+        };
+        // Only an unfiltered install is sure to include the runtime.
+        if self.include.is_empty() && self.exclude.is_empty() && !source_dir.join(runtime).is_file()
+        {
+            tracing::debug!(dir = %source_dir.display(), runtime, "Archive has no runtime binary");
+            return Err(Error::NoInstallableFiles {
+                reason: format!("it has no {runtime}"),
+            });
+        }
 
         tracing::debug!(source_dir = %source_dir.display(), "Start copying files to version directory");
-        crate::fs::copy_tree(&source_dir, &version_dir).await?;
+        let moved = self.temp_on_target
+            && filter.is_empty()
+            && timings
+                .time("copy", crate::fs::move_tree(&source_dir, &version_dir))
+                .await;
+        let stats = if moved {
+            tracing::debug!("Moved staged files into place");
+            CopyStats::default()
+        } else {
+            timings
+                .time(
+                    "copy",
+                    crate::fs::copy_tree_matching(&source_dir, &version_dir, &filter),
+                )
+                .await?
+        };
+        let skipped = skipped.into_inner() + stats.filtered;
+        if skipped > 0 {
+            tracing::info!(skipped, "Skipped files not selected by --include/--exclude");
+        }
+        if filter.preserve_existing {
+            report_preserved(&stats);
+        }
         tracing::debug!(version_dir = %version_dir.display(), "Copying files to version directory completed");
 
-        fs::remove_dir_all(&tmpdir).await.inspect_err(
+        #[cfg(target_os = "macos")]
+        if matches!(os, TargetOS::Darwin) {
+            for dir in ["bin", LIB_DIR] {
+                crate::fs::strip_quarantine(&version_dir.join(dir));
+            }
+        }
+
+        crate::fs::apply_install_mode(&version_dir, mode)
+            .await
+            .context(IoSnafu {
+                action: "set the mode of installed files".to_string(),
+                path: version_dir.display().to_string(),
+            })?;
+
+        let mut manifest = timings
+            .time("manifest", async {
+                InstallManifest::record(&version_dir, &asset, *os, *arch, &checksum)
+            })
+            .await?;
+        manifest.rosetta = rosetta;
+        if prefix_mode {
+            install_into_prefix(&version_dir, &target_dir, &manifest, self.preserve_existing)
+                .await?;
+        } else {
+            manifest.write(&version_dir).inspect_err(
+                |e| tracing::error!(error = %e.to_string(), "Failed to write install manifest"),
+            )?;
+            tracing::debug!(version_dir = %version_dir.display(), "Wrote install manifest");
+            if let Some(scope) = self.read_only {
+                crate::fs::make_read_only(&version_dir, scope == ReadOnlyScope::All)
+                    .await
+                    .context(IoSnafu {
+                        action: "make installed files read-only".to_string(),
+                        path: version_dir.display().to_string(),
+                    })?;
+                tracing::debug!(version_dir = %version_dir.display(), ?scope, "Made installed files read-only");
+            }
+        }
+
+        drop(prefix_staging_guard);
+        tmpdir_guard.close().await.inspect_err(
             |e| tracing::error!(error = %e.to_string(), "Failed to clean up temporary directory"),
         )?;
         tracing::debug!(tmpdir = %tmpdir.display(), "Cleaned up temporary directory");
+        if self.temp_on_target {
+            // Only once no other install is staging there.
+            let _ = std::fs::remove_dir(&staging_root);
+        }
 
-        tracing::debug!("Creating version symlinks");
-        crate::fs::create_version_symlinks(&target_dir, &version.to_string()).await?;
-        shell_utils::setup_path(&target_dir)?;
+        if prefix_mode {
+            tracing::debug!(prefix = %target_dir.display(), "Skipping version symlinks and PATH setup for prefix install");
+        } else if !activate {
+            tracing::debug!(%version, "Leaving version symlinks and PATH setup to the batch");
+        } else {
+            tracing::debug!("Creating version symlinks");
+            crate::fs::create_version_symlinks(&target_dir, &version.to_string()).await?;
+            if ephemeral {
+                tracing::debug!(target_dir = %target_dir.display(), "Skipping PATH setup for ephemeral install");
+            } else if !setup_path {
+                tracing::debug!(target_dir = %target_dir.display(), "Skipping PATH setup as requested");
+            } else {
+                self.add_to_path(&target_dir)?;
+            }
+        }
 
-        println!(
-            "Installed WasmEdge {version}\nInstall root: {}",
-            target_dir.display()
+        if let Some(path) = &self.sbom {
+            let url = match pin.as_ref().and_then(|lock| lock.url.clone()) {
+                Some(url) => url,
+                None => ctx.client.asset_url(&asset)?.to_string(),
+            };
+            let signature = if self.verify_signature {
+                SignatureStatus::ChecksumFileVerified
+            } else {
+                SignatureStatus::NotChecked
+            };
+            manifest.sbom(&url, signature).write(path)?;
+            tracing::debug!(path = %path.display(), "Wrote SBOM");
+        }
+
+        if let Some(path) = self.write_env_file.as_ref().filter(|_| activate) {
+            let bin_dir = if prefix_mode {
+                target_dir.join("bin")
+            } else {
+                version_dir.join("bin")
+            };
+            write_env(path, self.shell, &version.to_string(), &bin_dir)?;
+        }
+
+        tracing::info!(%timings, "Install phase timings");
+
+        // The install is complete; an ephemeral one is kept for the caller from here on.
+        if let Some(guard) = ephemeral_guard {
+            guard.disarm();
+        }
+
+        if let Some(hook) = &self.after_install {
+            match run_after_install(hook, &target_dir, &version.to_string()) {
+                Err(e) if self.ignore_hook_errors => {
+                    tracing::warn!(error = %e.to_string(), "after-install hook failed; ignoring as requested");
+                }
+                result => result?,
+            }
+        }
+
+        if self.ephemeral_run {
+            let result = run_ephemeral(&target_dir, &self.run_args);
+            if let Err(e) = crate::fs::remove_dir_all_safe(&target_dir).await {
+                tracing::warn!(error = %e, path = %target_dir.display(), "Failed to remove ephemeral install");
+            }
+            return result;
+        }
+
+        if !activate {
+            return Ok(());
+        }
+
+        let files_root = if prefix_mode {
+            &target_dir
+        } else {
+            &version_dir
+        };
+        let size = manifest
+            .files
+            .keys()
+            .filter_map(|name| std::fs::metadata(files_root.join(name)).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        let summary = InstallSummary {
+            version: version.to_string(),
+            path: std::path::absolute(&target_dir).unwrap_or(target_dir),
+            linkage: asset.linkage,
+            rosetta,
+            size,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        if self.json {
+            let json = serde_json::to_string_pretty(&summary).map_err(|_| Error::Unknown)?;
+            println!("{json}");
+        } else if self.json_lines {
+            events::emit(&ProgressEvent::Done(&summary));
+        } else if !ctx.no_progress {
+            println!("{summary}");
+        }
+
+        Ok(())
+    }
+}
+
+/// What a successful install printed as its final line, or as JSON with `--json`.
+#[derive(Debug, Serialize)]
+pub(crate) struct InstallSummary {
+    version: String,
+    /// The install location, or the prefix
+    path: PathBuf,
+    linkage: Linkage,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    rosetta: bool,
+    /// Bytes of the files installed
+    size: u64,
+    duration_ms: u64,
+}
+
+impl std::fmt::Display for InstallSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variant = match self.linkage {
+            Linkage::Dynamic if self.rosetta => " (x86_64, runs under Rosetta)",
+            Linkage::Dynamic => "",
+            Linkage::Static => " (statically linked)",
+        };
+        write!(
+            f,
+            "\u{2713} WasmEdge {}{variant} installed to {} ({}, {})",
+            self.version,
+            self.path.display(),
+            ByteSize(self.size),
+            Seconds(Duration::from_millis(self.duration_ms))
+        )
+    }
+}
+
+/// How one version of a `--multi` batch went.
+#[derive(Debug)]
+enum BatchOutcome {
+    Installed,
+    Failed(Error),
+    /// Not started, because of `--fail-fast` or Ctrl-C
+    Skipped,
+}
+
+/// What an install got to before failing, printed as diagnostics for bug reports.
+#[derive(Debug, Default)]
+struct FailureReport {
+    requested: String,
+    version: Option<String>,
+    target: Option<(TargetOS, TargetArch)>,
+}
+
+impl FailureReport {
+    fn new(requested: &str) -> Self {
+        Self {
+            requested: requested.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The diagnostics for `error`, with the requests `log` recorded.
+    fn render(&self, log: &RequestLog, error: &Error) -> String {
+        let mut text = String::from("Install failed. Diagnostics for bug reports:\n");
+        let mut line = |name: &str, value: &dyn std::fmt::Display| {
+            text.push_str(&format!("  {name}: {value}\n"));
+        };
+        line("wasmedgeup", &env!("CARGO_PKG_VERSION"));
+        line("requested", &self.requested);
+        line(
+            "version",
+            &self.version.as_deref().unwrap_or("(unresolved)"),
         );
+        match self.target {
+            Some((os, arch)) => line("target", &format!("{os:?} {arch:?}")),
+            None => line("target", &"(undetermined)"),
+        }
+        line("retries", &log.retries());
+        line("error", &error);
+        let requests = log.records();
+        if requests.is_empty() {
+            text.push_str("  requests: none\n");
+        } else {
+            text.push_str("  requests:\n");
+            for request in requests {
+                text.push_str(&format!(
+                    "    {} {} -> {}\n",
+                    request.method, request.url, request.outcome
+                ));
+            }
+        }
+        text
+    }
+}
+
+/// Wall-clock time spent in each phase of an install, in the order the phases first ran.
+#[derive(Debug, Default)]
+pub(crate) struct PhaseTimings(Vec<(&'static str, Duration)>);
+
+impl PhaseTimings {
+    /// Runs `phase` in a `phase` span and adds its elapsed time to `name`'s total.
+    async fn time<T>(&mut self, name: &'static str, phase: impl Future<Output = T>) -> T {
+        let span = tracing::info_span!("phase", name);
+        let start = Instant::now();
+        let output = phase.instrument(span.clone()).await;
+        let elapsed = start.elapsed();
+        span.in_scope(|| {
+            tracing::debug!(elapsed_ms = elapsed.as_millis() as u64, "Phase finished")
+        });
 
+        match self.0.iter_mut().find(|(phase, _)| *phase == name) {
+            Some((_, total)) => *total += elapsed,
+            None => self.0.push((name, elapsed)),
+        }
+        output
+    }
+}
+
+impl std::fmt::Display for PhaseTimings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (name, elapsed)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{name} {elapsed:.2?}")?;
+        }
         Ok(())
     }
 }
+
+/// What an archive must hash to and where to fetch it from, when given by a lockfile or
+/// `--expected-checksum` rather than looked up in the release.
+#[derive(Debug, Default)]
+struct Pinned {
+    /// Lowercase hex SHA-256
+    checksum: Option<String>,
+    url: Option<String>,
+}
+
+/// Returns the verified archive for `asset` and its checksum, served from `cache` when possible.
+/// Only archives cached from the same release base URL (or pinned URL) are used.
+///
+/// A cache hit is re-verified against the checksum stored with it; only a miss (or a hit that
+/// fails verification) fetches the checksum file and downloads the archive. A hit older than
+/// the cache's TTL is first compared with the published checksum, and downloaded again when
+/// that changed. A `pinned` checksum
+/// (and URL) is used instead where given, and the checksum file is then not fetched.
+async fn fetch_verified_archive(
+    ctx: &CommandContext,
+    cache: Option<&DownloadCache>,
+    version: &Version,
+    asset: &Asset,
+    pinned: &Pinned,
+    tmpdir: &Path,
+    timings: &mut PhaseTimings,
+) -> Result<(std::fs::File, String)> {
+    let source = ctx.client.checksum_source(pinned.checksum.as_deref());
+    let mut published = None;
+    let url = match pinned.url.as_deref() {
+        Some(url) => Url::parse(url).context(UrlSnafu)?,
+        None => ctx.client.asset_url(asset)?,
+    };
+    // Another release source, e.g. a fork, may publish a different archive under the same name.
+    let cache = cache.map(|cache| {
+        cache.clone().with_source(
+            pinned
+                .url
+                .as_deref()
+                .unwrap_or(&ctx.client.release_base_url),
+        )
+    });
+    let cache = cache.as_ref();
+
+    // A cached archive was verified against a checksum file that may not have been signed.
+    if let Some(cache) = cache.filter(|_| ctx.client.checksums_key.is_none()) {
+        let mut hit = cache.lookup(asset).await.filter(|hit| {
+            pinned
+                .checksum
+                .as_ref()
+                .is_none_or(|pinned| hit.checksum.eq_ignore_ascii_case(pinned))
+        });
+        if let Some(expired) = hit.take_if(|hit| hit.expired) {
+            let checksum = timings
+                .time("checksum", source.checksum(&ctx.client, version, asset))
+                .await
+                .inspect_err(
+                    |e| tracing::error!(error = %e.to_string(), "Failed to get checksum"),
+                )?;
+            if checksum.eq_ignore_ascii_case(&expired.checksum) {
+                tracing::debug!(path = %expired.path.display(), "Cached archive expired but still matches the release");
+                cache.renew(asset, &checksum).await;
+                hit = Some(expired);
+            } else {
+                tracing::info!(cached = %expired.checksum, %checksum, "Release checksum changed since the archive was cached; downloading again");
+                cache.evict(asset).await;
+            }
+            published = Some(checksum);
+        }
+        if let Some(hit) = hit {
+            tracing::debug!(path = %hit.path.display(), "Found archive in download cache");
+            let mut file = std::fs::File::open(&hit.path)?;
+            let verified = timings
+                .time(
+                    "checksum",
+                    WasmEdgeApiClient::verify_file_checksum(&mut file, &hit.checksum),
+                )
+                .await;
+            match verified {
+                Ok(()) => return Ok((file, hit.checksum)),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Cached archive failed verification; downloading again");
+                    cache.evict(asset).await;
+                }
+            }
+        }
+    }
+
+    let expected_checksum = match published {
+        Some(checksum) => checksum,
+        None => timings
+            .time("checksum", source.checksum(&ctx.client, version, asset))
+            .await
+            .inspect_err(|e| tracing::error!(error = %e.to_string(), "Failed to get checksum"))?,
+    };
+    tracing::debug!(%expected_checksum, source = source.name(), "Got release checksum");
+
+    let (downloaded, file) =
+        download_verified(ctx, &url, &expected_checksum, tmpdir, timings).await?;
+
+    if let Some(cache) = cache {
+        if let Err(e) = cache
+            .store(asset, downloaded.path(), &expected_checksum)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to store archive in download cache");
+        }
+    }
+
+    Ok((file, expected_checksum))
+}
+
+/// Downloads the archive at `url` for `install --url` or `--no-verify`, bypassing the cache, and
+/// returns it with its checksum.
+///
+/// The archive is verified against `expected` when given; otherwise its checksum is only
+/// computed, for the install manifest.
+async fn fetch_url_archive(
+    ctx: &CommandContext,
+    url: &Url,
+    expected: Option<&str>,
+    tmpdir: &Path,
+    timings: &mut PhaseTimings,
+) -> Result<(std::fs::File, String)> {
+    if let Some(expected) = expected {
+        let (_, file) = download_verified(ctx, url, expected, tmpdir, timings).await?;
+        return Ok((file, expected.to_string()));
+    }
+    let downloaded = timings
+        .time(
+            "download",
+            ctx.client.download_from(url, tmpdir, ctx.no_progress),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(error = %e.to_string(), "Failed to download asset"))?;
+    let mut file = downloaded.reopen()?;
+    let checksum = crate::api::sha256_hex_file(&mut file)?;
+    Ok((file, checksum))
+}
+
+/// How often an archive that downloads without error but fails its checksum is downloaded,
+/// counting the first download, before [`Error::ChecksumMismatch`] is returned.
+const CORRUPT_DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/// Downloads the archive at `url` and verifies it against `expected`, returning the downloaded
+/// file and the open, verified archive.
+///
+/// A mismatch is most likely a transfer corrupted on a lossy link, which the transport's own
+/// retries never see, so the archive is downloaded again from scratch, resolving `url` anew,
+/// up to [`CORRUPT_DOWNLOAD_ATTEMPTS`] times. A local `file://` archive is not, as copying it
+/// again cannot change it. Plugin archives are downloaded the same way.
+pub(crate) async fn download_verified(
+    ctx: &CommandContext,
+    url: &Url,
+    expected: &str,
+    tmpdir: &Path,
+    timings: &mut PhaseTimings,
+) -> Result<(tempfile::NamedTempFile, std::fs::File)> {
+    let mut attempt = 1;
+    loop {
+        let downloaded = timings
+            .time(
+                "download",
+                ctx.client.download_from(url, tmpdir, ctx.no_progress),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(error = %e.to_string(), "Failed to download asset"))?;
+        let mut file = downloaded.reopen()?;
+        let verified = timings
+            .time(
+                "checksum",
+                WasmEdgeApiClient::verify_file_checksum(&mut file, expected),
+            )
+            .await;
+        match verified {
+            Ok(()) => return Ok((downloaded, file)),
+            Err(e @ Error::ChecksumMismatch { .. })
+                if attempt < CORRUPT_DOWNLOAD_ATTEMPTS && url.scheme() != "file" =>
+            {
+                tracing::warn!(error = %e, attempt, "Downloaded archive is corrupt; downloading it again");
+                attempt += 1;
+            }
+            Err(e) => {
+                tracing::error!(error = %e.to_string(), "Checksum verification failed");
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Releases the `--interactive` menu lists at most
+const MENU_SIZE: usize = 15;
+
+/// An answer to the `--interactive` menu.
+#[derive(Debug, PartialEq, Eq)]
+enum MenuChoice {
+    /// Index into the listed releases
+    Release(usize),
+    TogglePrereleases,
+    /// Input ended without a choice
+    Closed,
+}
+
+/// Asks for a release from a numbered menu of the newest [`MENU_SIZE`] releases, showing
+/// pre-releases only once toggled on, and installs the chosen one.
+///
+/// Pre-releases are shown from the start when no stable release is published.
+async fn pick_version(client: &WasmEdgeApiClient, yes: bool) -> Result<Version> {
+    use std::io::IsTerminal;
+
+    if yes {
+        return Err(Error::InteractiveUnavailable {
+            reason: "--interactive cannot prompt with --yes",
+        });
+    }
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err(Error::InteractiveUnavailable {
+            reason: "--interactive needs a terminal to prompt on",
+        });
+    }
+    let releases = client.releases(ReleasesFilter::All, usize::MAX).await?;
+    if releases.is_empty() {
+        return Err(Error::VersionNotFound {
+            version: "latest".to_string(),
+        });
+    }
+    let mut prereleases = releases.iter().all(|v| !v.pre.is_empty());
+    loop {
+        let listed = releases
+            .iter()
+            .filter(|v| prereleases || v.pre.is_empty())
+            .take(MENU_SIZE)
+            .collect::<Vec<_>>();
+        let choice = ask_menu(
+            &listed,
+            prereleases,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stdout(),
+        )?;
+        match choice {
+            MenuChoice::Release(index) => return Ok(listed[index].clone()),
+            MenuChoice::TogglePrereleases => prereleases = !prereleases,
+            MenuChoice::Closed => {
+                return Err(Error::InteractiveUnavailable {
+                    reason: "--interactive got no answer",
+                })
+            }
+        }
+    }
+}
+
+/// Prints the menu of `listed` to `output` and reads a choice from `input`, asking again until
+/// the answer is valid. An empty answer picks the first, i.e. newest, release.
+///
+/// With nothing listed, i.e. only pre-releases published and hidden, only toggling them is
+/// accepted.
+fn ask_menu(
+    listed: &[&Version],
+    prereleases: bool,
+    input: &mut impl std::io::BufRead,
+    output: &mut impl std::io::Write,
+) -> Result<MenuChoice> {
+    for (i, version) in listed.iter().enumerate() {
+        writeln!(output, "{:>3}) {version}", i + 1)?;
+    }
+    let toggle = if prereleases { "hide" } else { "show" };
+    if listed.is_empty() {
+        writeln!(
+            output,
+            "No releases to list; only pre-releases are published"
+        )?;
+    }
+    loop {
+        if listed.is_empty() {
+            write!(output, "`p` to {toggle} pre-releases: ")?;
+        } else {
+            write!(
+                output,
+                "Version to install [1], or `p` to {toggle} pre-releases: "
+            )?;
+        }
+        output.flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(MenuChoice::Closed);
+        }
+        match answer.trim() {
+            "p" | "P" => return Ok(MenuChoice::TogglePrereleases),
+            "" if !listed.is_empty() => return Ok(MenuChoice::Release(0)),
+            _ if listed.is_empty() => writeln!(output, "Enter `p` to {toggle} pre-releases")?,
+            answer => match answer.parse::<usize>() {
+                Ok(n) if (1..=listed.len()).contains(&n) => return Ok(MenuChoice::Release(n - 1)),
+                _ => writeln!(output, "Enter a number from 1 to {}", listed.len())?,
+            },
+        }
+    }
+}
+
+/// Finds the version in the file name of an archive URL, such as `0.14.1` in
+/// `.../WasmEdge-0.14.1-Linux.tar.gz` or `0.15.0-rc.1` in `WasmEdge-0.15.0-rc.1-darwin_arm64.tar.gz`.
+///
+/// Only `alpha`, `beta` and `rc` pre-release labels are recognized, since anything else after the
+/// version is usually the target.
+fn version_in_url(url: &Url) -> Option<Version> {
+    let name = url.path_segments()?.next_back()?;
+    let name = [".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tar", ".zip"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(name);
+    name.char_indices()
+        .filter(|&(i, c)| {
+            c.is_ascii_digit()
+                && name[..i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|prev| !prev.is_ascii_alphanumeric() && prev != '.')
+        })
+        .find_map(|(i, _)| {
+            let rest = &name[i..];
+            let end = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len());
+            let core = rest[..end].trim_end_matches('.');
+            let version = Version::parse(core).ok()?;
+            let pre = rest[core.len()..]
+                .strip_prefix('-')
+                .filter(|pre| ["alpha", "beta", "rc"].iter().any(|l| pre.starts_with(l)))
+                .map(|pre| {
+                    let end = pre
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                        .unwrap_or(pre.len());
+                    pre[..end].trim_end_matches('.')
+                });
+            match pre {
+                Some(pre) => Version::parse(&format!("{version}-{pre}")).ok(),
+                None => Some(version),
+            }
+        })
+}
+
+/// Copies the install staged in `staging` into `prefix` and writes its manifest there.
+///
+/// Files and links listed by the manifest of an earlier prefix install that this one does not
+/// ship are removed first; nothing the manifests do not list is ever removed from the prefix.
+async fn install_into_prefix(
+    staging: &Path,
+    prefix: &Path,
+    manifest: &InstallManifest,
+    preserve_existing: bool,
+) -> Result<()> {
+    let manifest_dir = InstallManifest::prefix_dir(prefix);
+    match InstallManifest::load(&manifest_dir, &manifest.version) {
+        Ok(mut previous) => {
+            let shipped =
+                |name: &String| manifest.files.contains_key(name) || manifest.links.contains(name);
+            previous.files.retain(|name, _| !shipped(name));
+            previous.links.retain(|name| !shipped(name));
+            let removed = previous.remove_files(prefix)?;
+            tracing::debug!(previous = %previous.version, removed, "Removed files of the previous prefix install");
+        }
+        Err(Error::ManifestNotFound { .. }) => {}
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not read the manifest of the previous prefix install; its files are left in place");
+        }
+    }
+
+    let filter = CopyFilter {
+        preserve_existing,
+        ..Default::default()
+    };
+    let stats = crate::fs::copy_tree_matching(staging, prefix, &filter).await?;
+    if preserve_existing {
+        report_preserved(&stats);
+    }
+    fs::create_dir_all(&manifest_dir).await.context(IoSnafu {
+        action: "create prefix manifest directory".to_string(),
+        path: manifest_dir.display().to_string(),
+    })?;
+    manifest.write(&manifest_dir).inspect_err(
+        |e| tracing::error!(error = %e.to_string(), "Failed to write install manifest"),
+    )?;
+    tracing::debug!(prefix = %prefix.display(), "Copied staged files into prefix");
+
+    crate::fs::remove_dir_all_safe(staging)
+        .await
+        .context(IoSnafu {
+            action: "remove prefix staging directory".to_string(),
+            path: staging.display().to_string(),
+        })
+}
+
+/// Logs how many files `--preserve-existing` wrote and how many it left untouched.
+fn report_preserved(stats: &CopyStats) {
+    tracing::info!(
+        written = stats.copied,
+        unchanged = stats.unchanged,
+        "Only wrote files that differ from the installed ones"
+    );
+}
+
+/// Whether `--allow-rosetta` applies to the target: macOS on Apple Silicon.
+fn needs_rosetta(os: &TargetOS, arch: &TargetArch) -> bool {
+    matches!(os, TargetOS::Darwin) && matches!(arch, TargetArch::Aarch64)
+}
+
+fn warn_rosetta_fallback(version: &Version, asset: &Asset) {
+    tracing::warn!(%version, archive = %asset.archive_name, "Release has no native Apple Silicon build; using the x86_64 build, which runs under Rosetta 2");
+}
+
+/// Writes the `--write-env-file` file for `version`, whose executables are in `bin_dir`.
+fn write_env(
+    path: &Path,
+    shell: Option<shell_utils::EnvFileShell>,
+    version: &str,
+    bin_dir: &Path,
+) -> Result<()> {
+    let bin_dir = std::path::absolute(bin_dir).context(IoSnafu {
+        action: "resolve bin directory".to_string(),
+        path: bin_dir.display().to_string(),
+    })?;
+    shell_utils::write_env_file(path, shell.unwrap_or_default(), version, &bin_dir)?;
+    tracing::debug!(path = %path.display(), "Wrote env file");
+    Ok(())
+}
+
+/// Runs the `--after-install` command `hook` through the platform shell, telling it what was
+/// installed through `WASMEDGE_INSTALL_DIR` and `WASMEDGE_VERSION`.
+fn run_after_install(hook: &str, install_dir: &Path, version: &str) -> Result<()> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    tracing::debug!(%hook, install_dir = %install_dir.display(), version, "Running after-install hook");
+    let status = std::process::Command::new(shell)
+        .arg(flag)
+        .arg(hook)
+        .env("WASMEDGE_INSTALL_DIR", install_dir)
+        .env("WASMEDGE_VERSION", version)
+        .status()
+        .context(IoSnafu {
+            action: "run after-install hook".to_string(),
+            path: shell.to_string(),
+        })?;
+
+    tracing::info!(code = ?status.code(), "after-install hook finished");
+    if !status.success() {
+        return Err(Error::ChildProcessFailed {
+            program: hook.to_string(),
+            code: status.code(),
+        });
+    }
+    Ok(())
+}
+
+/// Runs the `wasmedge` binary of an ephemeral install, with the library and plugin
+/// directories of that install taking precedence over anything already in the environment.
+fn run_ephemeral(install_dir: &Path, args: &[String]) -> Result<()> {
+    let bin_dir = install_dir.join("bin");
+    let program = if cfg!(windows) {
+        bin_dir.join("wasmedge.exe")
+    } else {
+        bin_dir.join("wasmedge")
+    };
+
+    let prepend = |var: &str, dir: PathBuf| -> std::ffi::OsString {
+        let existing = std::env::var_os(var).unwrap_or_default();
+        let paths = std::iter::once(dir).chain(std::env::split_paths(&existing));
+        std::env::join_paths(paths).unwrap_or(existing)
+    };
+
+    let mut command = std::process::Command::new(&program);
+    command
+        .args(args)
+        .env("PATH", prepend("PATH", bin_dir.clone()))
+        .env("WASMEDGE_PLUGIN_PATH", install_dir.join("plugin"));
+    if cfg!(target_os = "macos") {
+        command.env(
+            "DYLD_LIBRARY_PATH",
+            prepend("DYLD_LIBRARY_PATH", install_dir.join(LIB_DIR)),
+        );
+    } else if cfg!(unix) {
+        command.env(
+            "LD_LIBRARY_PATH",
+            prepend("LD_LIBRARY_PATH", install_dir.join(LIB_DIR)),
+        );
+    }
+
+    tracing::debug!(program = %program.display(), ?args, "Running ephemeral WasmEdge");
+    let status = command.status().context(IoSnafu {
+        action: "run wasmedge".to_string(),
+        path: program.display().to_string(),
+    })?;
+
+    if !status.success() {
+        return Err(Error::ChildProcessFailed {
+            program: program.display().to_string(),
+            code: status.code(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_line_and_json() {
+        let summary = InstallSummary {
+            version: "0.14.1".to_string(),
+            path: PathBuf::from("/home/me/.wasmedge"),
+            linkage: Linkage::Static,
+            rosetta: false,
+            size: 3 * 1024 * 1024 / 2,
+            duration_ms: 3420,
+        };
+        assert_eq!(
+            summary.to_string(),
+            "\u{2713} WasmEdge 0.14.1 (statically linked) installed to /home/me/.wasmedge (1.50 MiB, 3.4s)"
+        );
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "version": "0.14.1",
+                "path": "/home/me/.wasmedge",
+                "linkage": "static",
+                "size": 1572864,
+                "duration_ms": 3420,
+            })
+        );
+    }
+
+    #[test]
+    fn version_from_url_file_name() {
+        let version = |url: &str| version_in_url(&Url::parse(url).unwrap()).map(|v| v.to_string());
+        for (url, expected) in [
+            (
+                "https://ci.example.com/a/WasmEdge-0.14.1-Linux.tar.gz",
+                Some("0.14.1"),
+            ),
+            (
+                "https://ci.example.com/WasmEdge-0.15.0-rc.1-ubuntu20.04_x86_64.tar.gz",
+                Some("0.15.0-rc.1"),
+            ),
+            (
+                "https://ci.example.com/v0.13.5/wasmedge-linux-x86_64-0.13.5.tgz",
+                Some("0.13.5"),
+            ),
+            (
+                "https://ci.example.com/artifacts/build-1234/wasmedge.tar.gz",
+                None,
+            ),
+        ] {
+            assert_eq!(version(url).as_deref(), expected, "{url}");
+        }
+    }
+
+    #[test]
+    fn menu_reads_choice() {
+        let (v1, v2) = (Version::new(0, 15, 0), Version::new(0, 14, 1));
+        let listed = [&v1, &v2];
+        let ask = |answers: &str| {
+            let mut output = Vec::new();
+            let choice = ask_menu(&listed, false, &mut answers.as_bytes(), &mut output).unwrap();
+            (choice, String::from_utf8(output).unwrap())
+        };
+
+        let (choice, output) = ask("\n");
+        assert_eq!(choice, MenuChoice::Release(0));
+        assert!(output.starts_with("  1) 0.15.0\n  2) 0.14.1\n"), "{output}");
+        assert_eq!(ask("2\n").0, MenuChoice::Release(1));
+        assert_eq!(ask("p\n").0, MenuChoice::TogglePrereleases);
+        assert_eq!(ask("").0, MenuChoice::Closed);
+
+        // Invalid answers are asked again.
+        let (choice, output) = ask("7\nlatest\n2\n");
+        assert_eq!(choice, MenuChoice::Release(1));
+        assert_eq!(output.matches("Enter a number from 1 to 2").count(), 2);
+    }
+
+    #[test]
+    fn menu_without_stable_releases_offers_toggle() {
+        let mut output = Vec::new();
+        let choice = ask_menu(&[], false, &mut "\n1\np\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(choice, MenuChoice::TogglePrereleases);
+        assert!(output.starts_with("No releases to list"), "{output}");
+        assert_eq!(output.matches("Enter `p` to show pre-releases").count(), 2);
+        assert!(!output.contains("from 1 to 0"), "{output}");
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{api::ReleasesFilter, cli::CommandContext, prelude::*};
+use crate::{
+    api::{in_version_window, Linkage, ReleasesFilter, VersionBound},
+    cli::CommandContext,
+    manifest::InstallManifest,
+    prelude::*,
+    target::{TargetArch, TargetOS},
+};
 use clap::Parser;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use crate::{cli::CommandExecutor, commands::default_path};
@@ -11,10 +18,30 @@ pub struct ListArgs {
     #[arg(long, default_value_t = false)]
     remote: bool,
 
+    /// Show installed versions (the default)
+    #[arg(long, conflicts_with = "remote")]
+    installed: bool,
+
+    /// Print the installed versions as a JSON array, with the metadata of their install manifests
+    #[arg(long, conflicts_with = "remote")]
+    json: bool,
+
     /// Include pre-release versions (alpha, beta, rc) when listing remote versions
     #[arg(short, long, default_value_t = false)]
     all: bool,
 
+    /// Only list remote versions from this one on, e.g. `0.13.0`, or `0.13` for the whole line
+    ///
+    /// Without `--since` or `--until` only the newest 10 releases are listed; with either,
+    /// every release in the window is.
+    #[arg(long, value_name = "VERSION", requires = "remote")]
+    since: Option<VersionBound>,
+
+    /// Only list remote versions up to and including this one, e.g. `0.14.1`, or `0.14` for
+    /// the whole line
+    #[arg(long, value_name = "VERSION", requires = "remote")]
+    until: Option<VersionBound>,
+
     /// Set the install location for the WasmEdge runtime
     ///
     /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
@@ -31,10 +58,24 @@ impl CommandExecutor for ListArgs {
                 ReleasesFilter::Stable
             };
 
-            let releases = ctx.client.releases(filter, 10)?;
-            let latest_release = ctx.client.latest_release()?;
+            if let (Some(since), Some(until)) = (&self.since, &self.until) {
+                if until.compare(&since.lowest()).is_gt() {
+                    return Err(Error::EmptyVersionWindow {
+                        since: since.to_string(),
+                        until: until.to_string(),
+                    });
+                }
+            }
+            let windowed = self.since.is_some() || self.until.is_some();
+            let releases = ctx
+                .client
+                .releases(filter, if windowed { usize::MAX } else { 10 })
+                .await?
+                .into_iter()
+                .filter(|v| in_version_window(v, self.since.as_ref(), self.until.as_ref()));
+            let latest_release = ctx.client.latest_release().await?;
 
-            for gh_release in releases.into_iter() {
+            for gh_release in releases {
                 print!("{gh_release}");
                 if gh_release == latest_release {
                     println!(" <- latest");
@@ -80,6 +121,20 @@ impl CommandExecutor for ListArgs {
 
                 versions.sort_by(|a, b| b.cmp(a));
 
+                if self.json {
+                    let listing = versions
+                        .into_iter()
+                        .map(|version| {
+                            let active = current_version.as_ref() == Some(&version);
+                            InstalledVersion::read(&versions_dir, version, active)
+                        })
+                        .collect::<Vec<_>>();
+                    let json =
+                        serde_json::to_string_pretty(&listing).map_err(|_| Error::Unknown)?;
+                    println!("{json}");
+                    return Ok(());
+                }
+
                 for version in versions {
                     print!("{version}");
                     if Some(version.clone()) == current_version {
@@ -88,9 +143,49 @@ impl CommandExecutor for ListArgs {
                         println!();
                     }
                 }
+            } else if self.json {
+                println!("[]");
             }
         }
 
         Ok(())
     }
 }
+
+/// An installed version as `list --json` prints it.
+///
+/// The metadata fields are `null` for versions without a readable install manifest, e.g.
+/// those installed before manifests were recorded.
+#[derive(Debug, Serialize)]
+struct InstalledVersion {
+    version: String,
+    path: PathBuf,
+    active: bool,
+    os: Option<TargetOS>,
+    arch: Option<TargetArch>,
+    linkage: Option<Linkage>,
+    archive: Option<String>,
+    checksum: Option<String>,
+    /// Seconds since the Unix epoch
+    installed_at: Option<u64>,
+}
+
+impl InstalledVersion {
+    fn read(versions_dir: &Path, version: String, active: bool) -> Self {
+        let dir = versions_dir.join(&version);
+        let manifest = InstallManifest::load(&dir, &version)
+            .inspect_err(|e| tracing::debug!(%version, error = %e.to_string(), "No install manifest to list"))
+            .ok();
+        Self {
+            path: std::path::absolute(&dir).unwrap_or(dir),
+            active,
+            os: manifest.as_ref().map(|m| m.os),
+            arch: manifest.as_ref().map(|m| m.arch),
+            linkage: manifest.as_ref().map(|m| m.linkage),
+            archive: manifest.as_ref().map(|m| m.archive.clone()),
+            checksum: manifest.as_ref().map(|m| m.archive_checksum.clone()),
+            installed_at: manifest.map(|m| m.installed_at),
+            version,
+        }
+    }
+}
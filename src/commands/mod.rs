@@ -1,17 +1,85 @@
-use crate::prelude::*;
+use crate::{environment::Environment, prelude::*};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
+pub mod check_update;
+pub mod clean;
+pub mod files;
 pub mod install;
 pub mod list;
 pub mod plugin;
+pub mod prune;
+pub mod reinstall;
 pub mod remove;
+pub mod targets;
 pub mod use_cmd;
+pub mod verify;
+pub mod verify_file;
+pub mod version;
 
-fn default_path() -> Result<PathBuf> {
-    let home_dir = dirs::home_dir().ok_or(Error::HomeDirNotFound)?;
+static XDG_LAYOUT: AtomicBool = AtomicBool::new(false);
+
+/// Makes the default install and cache locations follow the XDG Base Directory spec for the
+/// rest of the process, as `--xdg` asks.
+pub fn use_xdg_layout() {
+    XDG_LAYOUT.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--xdg` was given.
+pub fn xdg_layout() -> bool {
+    XDG_LAYOUT.load(Ordering::Relaxed)
+}
+
+static QUIET_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Keeps everything but the final error of a failure off stderr for the rest of the process,
+/// as `--quiet-errors` asks.
+pub fn use_quiet_errors() {
+    QUIET_ERRORS.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--quiet-errors` was given.
+pub fn quiet_errors() -> bool {
+    QUIET_ERRORS.load(Ordering::Relaxed)
+}
+
+/// The install location used without `--path`, resolved in [`Environment::current`].
+pub fn default_path() -> Result<PathBuf> {
+    let env = Environment::current();
+    let home_dir = env.home_dir().ok_or(Error::HomeDirNotFound)?;
+    if xdg_layout() {
+        let data_home = env
+            .xdg_dir("XDG_DATA_HOME")
+            .unwrap_or_else(|| home_dir.join(".local/share"));
+        return Ok(data_home.join("wasmedge"));
+    }
     Ok(home_dir.join(".wasmedge"))
 }
 
+/// Asks `question` on the terminal and returns whether it was answered with yes.
+///
+/// Without a terminal to ask on, [`Error::ConfirmationRequired`] is returned for `action` so
+/// that scripts have to opt in with `--yes`.
+fn confirm(question: &str, action: &str) -> Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Err(Error::ConfirmationRequired {
+            action: action.to_string(),
+        });
+    }
+
+    print!("{question} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
 pub fn insufficient_permissions(path: &Path, action: &str, version: &str) -> Error {
     let system_dir = if cfg!(windows) {
         "C\\Program Files\\WasmEdge".to_string()
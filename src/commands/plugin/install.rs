@@ -1,24 +1,27 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 use clap::{value_parser, Args};
 use tokio::fs;
-use walkdir::WalkDir;
 
 use crate::system::plugins::plugin_platform_key;
 use crate::{
+    api::Asset,
     cli::{CommandContext, CommandExecutor},
-    commands::default_path,
+    commands::{
+        default_path,
+        install::{download_verified, PhaseTimings},
+    },
+    environment::Environment,
     error::{Error, Result},
-    fs as wfs,
-    http::HttpClientConfig,
-    system,
+    fs as wfs, system,
 };
 
-use super::utils::find_plugin_shared_objects;
+use super::utils::{find_plugin_shared_objects, is_plugin_file};
 use super::version::PluginVersion;
 
-const GH_RELEASE_DOWNLOAD_BASE: &str = "https://github.com/WasmEdge/WasmEdge/releases/download";
-
 #[derive(Debug, Args)]
 pub struct PluginInstallArgs {
     /// Space-separated names and versions of plugins to install, e.g. `plugin1 plugin2@version`
@@ -36,13 +39,20 @@ pub struct PluginInstallArgs {
     /// Set the install location for the WasmEdge runtime (defaults to $HOME/.wasmedge)
     #[arg(short, long)]
     pub path: Option<PathBuf>,
+
+    /// Install the plugins into this directory instead of `versions/<version>/plugin`
+    ///
+    /// WasmEdge only finds plugins there when `WASMEDGE_PLUGIN_PATH` points at it; the export
+    /// needed is printed after the install.
+    #[arg(long, value_name = "DIR")]
+    pub plugin_dir: Option<PathBuf>,
 }
 
 impl PluginInstallArgs {
     fn tmpdir(&self) -> PathBuf {
         self.tmpdir
             .clone()
-            .unwrap_or_else(std::env::temp_dir)
+            .unwrap_or_else(|| Environment::current().temp_dir())
             .join("wasmedgeup")
             .join("plugins")
     }
@@ -56,9 +66,9 @@ impl CommandExecutor for PluginInstallArgs {
     /// # Steps
     /// 1. Resolve the target runtime version (either a specific version or the latest installed one).
     /// 2. Detect the host system specs and compute the plugin platform key (version-aware for Linux manylinux baseline and Darwin major on macOS).
-    /// 3. For each requested plugin, construct the release asset URL and download it to a temporary workspace.
+    /// 3. For each requested plugin, look up the archive's checksum in the release's checksum file, download it to a temporary workspace and verify it.
     /// 4. Unpack the archive into the workspace.
-    /// 5. Discover plugin artifacts and copy them into `versions/<version>/plugin`.
+    /// 5. Discover plugin artifacts and copy them into `versions/<version>/plugin`, removing them again when the copy fails, and print the plugin's version and checksum.
     /// 6. If no plugin shared objects are found, emit a warning and include a listing of archive contents to aid debugging.
     ///
     /// # Arguments
@@ -86,108 +96,145 @@ impl CommandExecutor for PluginInstallArgs {
                 version: runtime_version.to_string(),
             });
         }
-        if !wfs::can_write_to_directory(&version_dir) {
-            return Err(crate::commands::insufficient_permissions(
-                &version_dir,
-                "write to target version directory",
-                &runtime_version.to_string(),
-            ));
-        }
+        let dest_plugin = match &self.plugin_dir {
+            Some(dir) => {
+                if let Err(e) = fs::create_dir_all(dir).await {
+                    tracing::debug!(error = %e, path = %dir.display(), "Failed to create plugin directory");
+                }
+                if !wfs::can_write_to_directory(dir) {
+                    return Err(crate::commands::insufficient_permissions(
+                        dir,
+                        "write to plugin directory",
+                        &runtime_version.to_string(),
+                    ));
+                }
+                dir.clone()
+            }
+            None => {
+                if !wfs::can_write_to_directory(&version_dir) {
+                    return Err(crate::commands::insufficient_permissions(
+                        &version_dir,
+                        "write to target version directory",
+                        &runtime_version.to_string(),
+                    ));
+                }
+                let dir = version_dir.join("plugin");
+                fs::create_dir_all(&dir).await?;
+                dir
+            }
+        };
 
         let specs = system::detect();
         let os_key = plugin_platform_key(&specs.os, &runtime_version)?;
         tracing::debug!(platform_key = %os_key, "Resolved plugin platform key for plugins");
 
-        let dest_plugin = version_dir.join("plugin");
-        fs::create_dir_all(&dest_plugin).await?;
-
         let tmp_root = self.tmpdir();
         for plugin in &self.plugins {
             let (name, pver) = match plugin {
-                PluginVersion::Name(n) => (n.as_str(), runtime_version.to_string()),
-                PluginVersion::NameAndVersion(n, v) => (n.as_str(), v.to_string()),
+                PluginVersion::Name(n) => (n.as_str(), runtime_version.clone()),
+                PluginVersion::NameAndVersion(n, v) => (n.as_str(), v.clone()),
             };
 
-            let is_windows = matches!(specs.os.os_type, crate::target::TargetOS::Windows);
-            let ext = if is_windows { "zip" } else { "tar.gz" };
-            let url = format!(
-                "{base}/{ver}/WasmEdge-plugin-{name}-{ver}-{os_key}.{ext}",
-                base = GH_RELEASE_DOWNLOAD_BASE,
-                name = name,
-                ver = pver,
-                os_key = os_key,
-                ext = ext,
-            );
+            let asset = Asset::plugin(&pver, name, &os_key, &specs.os.os_type);
+            let url = ctx.client.asset_url(&asset)?;
             tracing::debug!(%name, %pver, %url, "Downloading plugin");
 
-            let workspace = tmp_root.join(format!("{name}-{pver}"));
+            let workspace = tmp_root.join(&asset.install_name);
             fs::create_dir_all(&workspace).await?;
-            let archive_path = if is_windows {
-                workspace.join("plugin.zip")
-            } else {
-                workspace.join("plugin.tar.gz")
-            };
-
-            download_with_progress(&ctx, &url, &archive_path).await?;
-
-            let mut file = std::fs::OpenOptions::new()
-                .read(true)
-                .open(&archive_path)
-                .map_err(|source| Error::Io {
-                    action: "open archive".to_string(),
-                    path: archive_path.display().to_string(),
+            let workspace_guard =
+                wfs::TempDirGuard::staging(&workspace).map_err(|source| Error::Io {
+                    action: "mark plugin workspace".to_string(),
+                    path: workspace.display().to_string(),
                     source,
                 })?;
-            wfs::extract_archive(&mut file, &workspace).await?;
 
-            let paths = find_plugin_shared_objects(&workspace);
-            let found_any = if !paths.is_empty() {
-                for src in paths {
-                    let file_name = src.file_name().unwrap_or_default();
-                    let dest = dest_plugin.join(file_name);
-                    if let Some(parent) = dest.parent() {
-                        if let Err(e) = fs::create_dir_all(parent).await {
-                            tracing::warn!(error = %e, path = %parent.display(), "Failed to create parent directory for plugin");
-                        }
-                    }
-                    if let Err(e) = fs::copy(&src, &dest).await {
-                        tracing::warn!(error = %e, from = %src.display(), to = %dest.display(), "Failed to copy plugin shared object");
-                    } else {
-                        tracing::debug!(from = %src.display(), to = %dest.display(), "Copied plugin shared object");
-                    }
+            let checksum = ctx
+                .client
+                .get_release_checksum(&pver, &asset)
+                .await
+                .inspect_err(|e| tracing::error!(error = %e.to_string(), plugin = %name, "Failed to get plugin checksum"))?;
+            let (_downloaded, mut file) = download_verified(
+                &ctx,
+                &url,
+                &checksum,
+                &workspace,
+                &mut PhaseTimings::default(),
+            )
+            .await?;
+
+            // Only the shared objects are installed, so nothing else is extracted; what is
+            // left out is kept for the warning when there is none.
+            let extracted = workspace.join("extracted");
+            let skipped = Mutex::new(Vec::new());
+            let filter = |path: &Path| {
+                let selected = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(is_plugin_file);
+                if !selected {
+                    skipped.lock().unwrap().push(path.display().to_string());
                 }
-                true
-            } else {
-                false
+                selected
             };
+            wfs::extract_archive_matching(&mut file, &extracted, Some(&filter)).await?;
 
-            if !found_any {
-                let mut entries: Vec<String> = Vec::new();
-                for e in WalkDir::new(&workspace).into_iter().filter_map(|e| e.ok()) {
-                    let p = e.path();
-                    if p.is_file() {
-                        let rel = p.strip_prefix(&workspace).unwrap_or(p);
-                        entries.push(rel.display().to_string());
-                    }
-                }
+            let paths = find_plugin_shared_objects(&extracted);
+            if paths.is_empty() {
+                let entries = skipped.into_inner().unwrap();
                 tracing::warn!(
-                    root = %workspace.display(),
+                    root = %extracted.display(),
                     entries = ?entries,
                     "No plugin shared object found in archive; nothing was installed"
                 );
+            } else {
+                // Gathered flat so the shared objects land directly in the plugin directory.
+                let stage = workspace.join("plugin");
+                fs::create_dir_all(&stage).await?;
+                for src in &paths {
+                    let file_name = src.file_name().unwrap_or_default();
+                    fs::rename(src, stage.join(file_name)).await?;
+                }
+                let files = copy_plugin_files(&stage, &dest_plugin).await?;
+                println!(
+                    "{}",
+                    PluginSummary {
+                        name: name.to_string(),
+                        version: pver.to_string(),
+                        path: dest_plugin.clone(),
+                        files,
+                        checksum,
+                    }
+                );
+                tracing::info!(plugin = %name, version = %pver, "Installed plugin successfully");
             }
 
-            if let Err(e) = fs::remove_dir_all(&workspace).await {
+            if let Err(e) = workspace_guard.close().await {
                 tracing::debug!(error = %e, path = %workspace.display(), "Failed to cleanup workspace");
             }
+        }
 
-            tracing::info!(plugin = %name, version = %pver, "Installed plugin successfully");
+        if let Some(dir) = &self.plugin_dir {
+            println!(
+                "Plugins installed into {}\nFor WasmEdge to load them from there, set:\n  {}",
+                dir.display(),
+                plugin_path_export(dir)
+            );
         }
 
         Ok(())
     }
 }
 
+/// The shell command that points `WASMEDGE_PLUGIN_PATH` at `dir`, for the user to run.
+fn plugin_path_export(dir: &Path) -> String {
+    let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+    if cfg!(windows) {
+        format!("$env:WASMEDGE_PLUGIN_PATH = \"{}\"", dir.display())
+    } else {
+        format!("export WASMEDGE_PLUGIN_PATH=\"{}\"", dir.display())
+    }
+}
+
 pub(super) fn select_runtime_version(
     versions_dir: &Path,
     requested: Option<&str>,
@@ -203,39 +250,51 @@ pub(super) fn select_runtime_version(
     }
 }
 
-async fn download_with_progress(ctx: &CommandContext, url: &str, to: &Path) -> Result<()> {
-    use tokio::io::AsyncWriteExt as _;
-
-    let client = HttpClientConfig::new()
-        .with_connect_timeout(ctx.client.connect_timeout)
-        .with_request_timeout(ctx.client.request_timeout)
-        .build()?;
-
-    let resp = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|source| Error::Request {
-            source,
-            resource: "plugin download",
-        })?;
-
-    let resp = resp.error_for_status().map_err(|source| Error::Request {
-        source,
-        resource: "plugin download",
-    })?;
-
-    let bytes = resp.bytes().await.map_err(|source| Error::Request {
-        source,
-        resource: "plugin download body",
-    })?;
-
-    let mut file = tokio::fs::OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(to)
-        .await?;
-    file.write_all(&bytes).await?;
-    Ok(())
+/// Copies the shared objects staged in `stage` into the plugin directory `dest` with
+/// [`wfs::copy_tree_matching`], returning how many were copied.
+///
+/// When one fails to copy, the plugin's files already in `dest` are removed again, including
+/// an earlier install they replaced, so no half-copied plugin is left to be loaded.
+async fn copy_plugin_files(stage: &Path, dest: &Path) -> Result<usize> {
+    match wfs::copy_tree_matching(stage, dest, &wfs::CopyFilter::default()).await {
+        Ok(stats) => Ok(stats.copied),
+        Err(e) => {
+            for entry in std::fs::read_dir(stage).into_iter().flatten().flatten() {
+                let path = dest.join(entry.file_name());
+                if std::fs::symlink_metadata(&path).is_ok_and(|meta| !meta.is_dir()) {
+                    if let Err(e) = std::fs::remove_file(&path) {
+                        tracing::warn!(error = %e, path = %path.display(), "Failed to remove partially installed plugin file");
+                    }
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+/// The line printed for each installed plugin.
+#[derive(Debug)]
+struct PluginSummary {
+    name: String,
+    version: String,
+    /// The plugin directory the files were copied into
+    path: PathBuf,
+    files: usize,
+    /// SHA-256 of the verified plugin archive
+    checksum: String,
+}
+
+impl std::fmt::Display for PluginSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\u{2713} Plugin {} {} installed to {} ({} file{}, sha256 {})",
+            self.name,
+            self.version,
+            self.path.display(),
+            self.files,
+            if self.files == 1 { "" } else { "s" },
+            self.checksum
+        )
+    }
 }
@@ -1,16 +1,17 @@
-use crate::api::runtime_ge_015;
+use crate::api::{releases::api_get, runtime_ge_015, RequestLog};
 use crate::cli::{CommandContext, CommandExecutor};
+use crate::http::Secret;
 use crate::prelude::*;
 use crate::system;
 use crate::system::plugins::plugin_platform_key;
+use crate::system::spec::LibcKind;
+use crate::target::{TargetArch, TargetArchParser, TargetOS};
 use clap::Args;
+use serde::Serialize;
 use serde_json::Value;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 
-const UA: &str = "wasmedgeup";
-const GH_RELEASE_TAG_API: &str = "https://api.github.com/repos/WasmEdge/WasmEdge/releases/tags";
-const GH_RELEASE_DOWNLOAD_BASE: &str = "https://github.com/WasmEdge/WasmEdge/releases/download";
 const ASSET_PREFIX: &str = "WasmEdge-plugin-";
 const TAR_GZ: &str = ".tar.gz";
 const ZIP: &str = ".zip";
@@ -22,6 +23,10 @@ const MANYLINUX_2_28_PREFIX: &str = "manylinux_2_28_";
 
 #[derive(Debug, Args)]
 pub struct PluginListArgs {
+    /// WasmEdge runtime version to list plugins for (e.g., 0.15.0); same as `--runtime`
+    #[arg(conflicts_with = "runtime")]
+    version: Option<String>,
+
     /// Show all (including assets that are not found for this runtime/platform)
     #[arg(long)]
     all: bool,
@@ -33,13 +38,37 @@ pub struct PluginListArgs {
     /// Filter by a single plugin name
     #[arg(long)]
     name: Option<String>,
+
+    /// List plugins built for this OS instead of the host's
+    #[arg(short, long, ignore_case = true)]
+    os: Option<TargetOS>,
+
+    /// List plugins built for this architecture instead of the host's
+    #[arg(short, long, ignore_case = true, value_parser = TargetArchParser)]
+    arch: Option<TargetArch>,
+
+    /// Print the result as JSON
+    #[arg(long)]
+    json: bool,
 }
 
 impl CommandExecutor for PluginListArgs {
-    async fn execute(self, _ctx: CommandContext) -> Result<()> {
-        let spec = system::detect();
+    async fn execute(self, ctx: CommandContext) -> Result<()> {
+        let mut spec = system::detect();
+        if let Some(os) = self.os {
+            // The host's distro, OS version and libc say nothing about another OS.
+            if !os.matches_host() {
+                spec.os.distro = None;
+                spec.os.version = None;
+                spec.os.libc.kind = LibcKind::Glibc;
+            }
+            spec.os.os_type = os;
+        }
+        if let Some(arch) = self.arch {
+            spec.os.arch = arch;
+        }
 
-        let runtime = if let Some(r) = self.runtime {
+        let runtime = if let Some(r) = self.version.or(self.runtime) {
             r
         } else {
             match system::toolchain::get_installed_wasmedge_version() {
@@ -72,7 +101,18 @@ impl CommandExecutor for PluginListArgs {
                 .features
                 .contains(&crate::system::spec::CpuFeature::AVX);
 
-        let assets = match fetch_release_assets(&runtime).await {
+        ctx.client.require_network("plugin listing")?;
+        let http = ctx.client.http_client()?;
+        let log = ctx.client.request_log();
+        let assets = match fetch_release_assets(
+            &http,
+            log,
+            &ctx.client.releases_api_url,
+            &runtime,
+            ctx.client.github_token.as_ref(),
+        )
+        .await
+        {
             Ok(v) => v,
             Err(_) => {
                 eprintln!("failed to fetch release assets for tag {runtime}");
@@ -132,13 +172,14 @@ impl CommandExecutor for PluginListArgs {
                 };
                 for probe in probes {
                     for plat in &platform_candidates {
+                        let base = &ctx.client.release_base_url;
                         let url_targz = format!(
-                            "{GH_RELEASE_DOWNLOAD_BASE}/{runtime}/{ASSET_PREFIX}{probe}-{runtime}-{plat}{TAR_GZ}"
-                        );
-                        let url_zip = format!(
-                            "{GH_RELEASE_DOWNLOAD_BASE}/{runtime}/{ASSET_PREFIX}{probe}-{runtime}-{plat}{ZIP}"
+                            "{base}/{runtime}/{ASSET_PREFIX}{probe}-{runtime}-{plat}{TAR_GZ}"
                         );
-                        let available = head_ok(&url_targz).await || head_ok(&url_zip).await;
+                        let url_zip =
+                            format!("{base}/{runtime}/{ASSET_PREFIX}{probe}-{runtime}-{plat}{ZIP}");
+                        let available = head_ok(&http, log, &url_targz).await
+                            || head_ok(&http, log, &url_zip).await;
                         rows.push(Row {
                             name: probe.to_string(),
                             version: runtime.clone(),
@@ -158,6 +199,17 @@ impl CommandExecutor for PluginListArgs {
             other => other,
         });
 
+        if self.json {
+            let listing = Listing {
+                runtime: &runtime,
+                platform: &platform,
+                plugins: &rows,
+            };
+            let json = serde_json::to_string_pretty(&listing).map_err(|_| Error::Unknown)?;
+            println!("{json}");
+            return Ok(());
+        }
+
         println!("Runtime: {runtime}\nPlatform: {platform}");
         if rows.is_empty() {
             println!(
@@ -200,7 +252,14 @@ impl CommandExecutor for PluginListArgs {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+struct Listing<'a> {
+    runtime: &'a str,
+    platform: &'a str,
+    plugins: &'a [Row],
+}
+
+#[derive(Debug, Serialize)]
 struct Row {
     name: String,
     version: String,
@@ -230,14 +289,13 @@ fn order_plugins(a: &str, b: &str, cuda: bool, noavx: bool) -> Ordering {
     rank(a).cmp(&rank(b)).then(a.cmp(b))
 }
 
-async fn head_ok(url: &str) -> bool {
-    let client = reqwest::Client::new();
-    if let Ok(resp) = client.head(url).send().await {
+async fn head_ok(client: &reqwest::Client, log: &RequestLog, url: &str) -> bool {
+    if let Ok(resp) = log.send(client.head(url)).await {
         if resp.status().is_success() {
             return true;
         }
     }
-    if let Ok(resp) = client.get(url).send().await {
+    if let Ok(resp) = log.send(client.get(url)).await {
         return resp.status().is_success();
     }
     false
@@ -250,13 +308,16 @@ struct AssetInfo {
     platform: String,
 }
 
-async fn fetch_release_assets(tag: &str) -> Result<Vec<AssetInfo>, ()> {
-    let url = format!("{GH_RELEASE_TAG_API}/{tag}");
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
-        .header("User-Agent", UA)
-        .send()
+async fn fetch_release_assets(
+    client: &reqwest::Client,
+    log: &RequestLog,
+    releases_api: &str,
+    tag: &str,
+    token: Option<&Secret>,
+) -> Result<Vec<AssetInfo>, ()> {
+    let url = format!("{releases_api}/tags/{tag}");
+    let resp = log
+        .send(api_get(client, &url, token))
         .await
         .map_err(|_| ())?;
     if !resp.status().is_success() {
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use semver::Version;
+use snafu::ResultExt;
+
+use crate::{
+    api::installed_versions,
+    cli::{CommandContext, CommandExecutor},
+    commands::default_path,
+    manifest::InstallManifest,
+    prelude::*,
+    units::ByteSize,
+};
+
+/// Versions listed here, one per line, are never pruned.
+pub const PIN_FILE_NAME: &str = ".wasmedgeup-pins";
+
+#[derive(Debug, Default, Parser)]
+pub struct PruneArgs {
+    /// Keep these versions too, besides the active one and those pinned (repeatable)
+    #[arg(long, value_name = "VERSION", num_args = 1..)]
+    pub keep: Vec<Version>,
+
+    /// List the versions that would be removed and the space they hold, without removing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Set the install location for the WasmEdge runtime
+    ///
+    /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
+
+impl CommandExecutor for PruneArgs {
+    /// Removes every installed version that is neither active, pinned in the install
+    /// location's [`PIN_FILE_NAME`] nor given with `--keep`.
+    #[tracing::instrument(name = "prune", skip_all)]
+    async fn execute(self, _ctx: CommandContext) -> Result<()> {
+        let target_dir = self.path.map_or_else(default_path, Ok)?;
+        let mut kept = pinned_versions(&target_dir)?;
+        kept.extend(self.keep);
+
+        let (pruned, reclaimed) = prune_versions(
+            &target_dir,
+            &|_, version| kept.contains(version),
+            self.dry_run,
+        )
+        .await?;
+        match pruned {
+            0 => println!("Nothing to prune"),
+            count => println!(
+                "{} {count} version{}, {} reclaimed",
+                if self.dry_run {
+                    "Would prune"
+                } else {
+                    "Pruned"
+                },
+                if count == 1 { "" } else { "s" },
+                ByteSize(reclaimed)
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// The versions pinned in `target_dir`'s [`PIN_FILE_NAME`]; none when it does not exist.
+///
+/// Blank lines and `#` comments are skipped. A line that is not a version fails, rather than
+/// leaving the version it was meant to pin unprotected.
+pub fn pinned_versions(target_dir: &Path) -> Result<Vec<Version>> {
+    let path = target_dir.join(PIN_FILE_NAME);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(Error::Io {
+                action: "read pin list".to_string(),
+                path: path.display().to_string(),
+                source,
+            })
+        }
+    };
+    let mut pinned = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let version = Version::parse(line.strip_prefix('v').unwrap_or(line)).map_err(|e| {
+            Error::InvalidPath {
+                path: path.display().to_string(),
+                reason: format!("line {}: '{line}' is not a version: {e}", number + 1),
+            }
+        })?;
+        pinned.push(version);
+    }
+    Ok(pinned)
+}
+
+/// Removes the installed versions under `target_dir` that `retain` does not keep, printing
+/// each with the space it took, and returns how many were removed and the bytes reclaimed.
+/// With `dry_run` they are only printed.
+///
+/// `retain` is given each version's rank, newest first by semantic version, with the version.
+/// The active version is always kept. Only versions whose install manifest records them are
+/// removed: a directory without one, e.g. from before manifests were written or put there by
+/// hand, may not be wasmedgeup's and is kept with a note.
+pub(crate) async fn prune_versions(
+    target_dir: &Path,
+    retain: &(dyn Fn(usize, &Version) -> bool + Sync),
+    dry_run: bool,
+) -> Result<(usize, u64)> {
+    let versions_dir = target_dir.join("versions");
+    let active = crate::fs::active_version(target_dir).await;
+    let mut versions = installed_versions(&versions_dir)?;
+    versions.sort_by(|a, b| b.cmp(a));
+
+    let mut pruned = 0;
+    let mut reclaimed = 0;
+    for (rank, version) in versions.iter().enumerate() {
+        let name = version.to_string();
+        if retain(rank, version) {
+            continue;
+        }
+        if active.as_deref() == Some(name.as_str()) {
+            tracing::debug!(%version, "Keeping the active version");
+            continue;
+        }
+        let version_dir = versions_dir.join(&name);
+        match InstallManifest::load(&version_dir, &name) {
+            Ok(manifest) if manifest.version == name => {}
+            _ => {
+                println!("Kept WasmEdge {version}: it has no install manifest");
+                continue;
+            }
+        }
+        let size = walkdir::WalkDir::new(&version_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|meta| meta.is_file())
+            .map(|meta| meta.len())
+            .sum::<u64>();
+        if dry_run {
+            println!("Would prune WasmEdge {version} ({})", ByteSize(size));
+        } else {
+            crate::fs::remove_dir_all_safe(&version_dir)
+                .await
+                .context(IoSnafu {
+                    action: "remove pruned version".to_string(),
+                    path: version_dir.display().to_string(),
+                })?;
+            println!("Pruned WasmEdge {version} ({})", ByteSize(size));
+        }
+        pruned += 1;
+        reclaimed += size;
+    }
+    Ok((pruned, reclaimed))
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    api::Linkage,
+    cli::{CommandContext, CommandExecutor},
+    commands::{default_path, install::InstallArgs},
+    manifest::InstallManifest,
+    prelude::*,
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct ReinstallArgs {
+    /// WasmEdge version to reinstall, e.g. `0.14.1`
+    ///
+    /// Defaults to the currently active version.
+    pub version: Option<String>,
+
+    /// Set the install location for the WasmEdge runtime
+    ///
+    /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// Set the temporary directory for staging downloaded assets
+    ///
+    /// Defaults to the system temporary directory, this differs between operating systems.
+    #[arg(short, long)]
+    pub tmpdir: Option<PathBuf>,
+
+    /// Do not read from or write to the download cache
+    #[arg(long)]
+    pub no_cache: bool,
+}
+
+impl CommandExecutor for ReinstallArgs {
+    /// Downloads the version again and replaces its files, as `install --force` would.
+    ///
+    /// The target OS, architecture and linkage are taken from the version's install manifest
+    /// when it has a readable one, so the same build is fetched again. PATH is left as it is.
+    #[tracing::instrument(name = "reinstall", skip_all, fields(version = self.version))]
+    async fn execute(self, ctx: CommandContext) -> Result<()> {
+        let target_dir = match self.path {
+            Some(p) => p,
+            None => default_path()?,
+        };
+
+        let version = match self.version {
+            Some(version) => version,
+            None => crate::fs::active_version(&target_dir)
+                .await
+                .ok_or_else(|| Error::InvalidPath {
+                    path: target_dir.display().to_string(),
+                    reason: "no active version found; provide a version to reinstall".to_string(),
+                })?,
+        };
+        tracing::debug!(%version, "Reinstalling version");
+
+        let version_dir = target_dir
+            .join("versions")
+            .join(version.trim_start_matches('v'));
+        let manifest = if version_dir.is_dir() {
+            InstallManifest::load(&version_dir, &version)
+                .inspect_err(
+                    |e| tracing::warn!(error = %e.to_string(), "Could not read the install manifest; reinstalling for the host target"),
+                )
+                .ok()
+        } else {
+            None
+        };
+
+        let args = InstallArgs {
+            version,
+            path: Some(target_dir),
+            tmpdir: self.tmpdir,
+            os: manifest.as_ref().map(|m| m.os),
+            arch: manifest.as_ref().map(|m| m.arch),
+            no_setup_path: true,
+            force: true,
+            no_cache: self.no_cache,
+            prefer_static: manifest.is_some_and(|m| m.linkage == Linkage::Static),
+            ..Default::default()
+        };
+        args.execute(ctx).await
+    }
+}
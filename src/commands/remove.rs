@@ -1,37 +1,173 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
+use snafu::ResultExt;
 use tokio::fs;
 
 use crate::{
     api::latest_installed_version,
     cli::{CommandContext, CommandExecutor},
     commands::{default_path, use_cmd::UseArgs},
+    manifest::InstallManifest,
     prelude::*,
-    shell_utils::uninstall_path,
+    shell_utils,
 };
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Default, Parser)]
 pub struct RemoveArgs {
     /// WasmEdge version to remove, e.g. `0.13.0`, `0.15.0`, etc.
     #[arg(default_value = "")]
     pub version: String,
 
-    /// Remove all installed versions
+    /// Remove all installed versions, the version symlinks and the PATH entry
+    ///
+    /// The install root itself is removed too when nothing else is left in it.
     #[arg(long)]
     pub all: bool,
 
+    /// Do not ask for confirmation before `--all`
+    #[arg(short, long, requires = "all")]
+    pub yes: bool,
+
     /// Set the install location for the WasmEdge runtime
     ///
     /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
     #[arg(short, long)]
     pub path: Option<PathBuf>,
+
+    /// Remove the install made with `install --prefix` from this prefix
+    ///
+    /// Only the files listed in its manifest are deleted, along with nested directories left
+    /// empty by that; the version may be omitted.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["path", "all"])]
+    pub prefix: Option<PathBuf>,
+
+    /// Which PATH the install was added to; must match the `--scope` used to install
+    #[cfg(windows)]
+    #[arg(long, value_enum, default_value_t)]
+    pub scope: crate::shell_utils::PathScope,
+}
+
+impl RemoveArgs {
+    fn uninstall_path(&self, target_dir: &Path) -> Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(windows)] {
+                shell_utils::uninstall_path_for(target_dir, self.scope)
+            } else {
+                shell_utils::uninstall_path(target_dir)
+            }
+        }
+    }
+
+    /// Tears down the whole installation under `target_dir` after confirmation, printing each
+    /// step. Files that `install` did not create are kept, and with them the install root.
+    async fn remove_all(&self, target_dir: &Path) -> Result<()> {
+        let versions_dir = target_dir.join("versions");
+        let mut versions = Vec::new();
+        let mut dir_stream = fs::read_dir(&versions_dir).await?;
+        while let Some(entry) = dir_stream.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                versions.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        versions.sort();
+
+        if !self.yes {
+            let question = format!(
+                "Remove {} installed version(s) ({}) from {} and its PATH entry?",
+                versions.len(),
+                versions.join(", "),
+                target_dir.display()
+            );
+            if !super::confirm(&question, "remove all installed versions")? {
+                println!("Nothing removed");
+                return Ok(());
+            }
+        }
+
+        tracing::debug!("Removing all installed versions");
+        for version in &versions {
+            crate::fs::remove_dir_all_safe(&versions_dir.join(version)).await?;
+            println!("Removed WasmEdge {version}");
+        }
+        let _ = fs::remove_dir(&versions_dir).await;
+
+        for link in ["bin", "include", "lib", "plugin"] {
+            let path = target_dir.join(link);
+            let is_link = fs::symlink_metadata(&path)
+                .await
+                .is_ok_and(|m| m.file_type().is_symlink());
+            // Windows directory symlinks are removed like directories.
+            if is_link
+                && (fs::remove_file(&path).await.is_ok() || fs::remove_dir(&path).await.is_ok())
+            {
+                println!("Removed symlink {}", path.display());
+            }
+        }
+
+        match self.uninstall_path(target_dir) {
+            Ok(()) => println!("Removed PATH entry for {}", target_dir.display()),
+            Err(e) => {
+                tracing::warn!(error = %e.to_string(), "Failed to update shell rc files during --all removal")
+            }
+        }
+
+        if fs::remove_dir(target_dir).await.is_ok() {
+            println!("Removed install root {}", target_dir.display());
+        } else {
+            println!(
+                "Kept install root {}: it still contains other files",
+                target_dir.display()
+            );
+        }
+        tracing::info!("All versions and configuration removed successfully");
+        Ok(())
+    }
+
+    /// Removes a prefix install using its manifest; the prefix itself is never removed.
+    async fn remove_from_prefix(&self, ctx: &CommandContext, prefix: &Path) -> Result<()> {
+        let manifest_dir = InstallManifest::prefix_dir(prefix);
+        let manifest = match InstallManifest::load(&manifest_dir, &self.version) {
+            Err(Error::ManifestNotFound { .. }) => {
+                return Err(Error::InvalidPath {
+                    path: prefix.display().to_string(),
+                    reason: "no WasmEdge prefix install found".to_string(),
+                });
+            }
+            manifest => manifest?,
+        };
+
+        if !self.version.is_empty() {
+            let version = ctx.client.resolve_version(&self.version).await?;
+            if version.to_string() != manifest.version {
+                return Err(Error::VersionNotFound {
+                    version: version.to_string(),
+                });
+            }
+        }
+
+        let removed = manifest.remove_files(prefix)?;
+        let manifest_path = InstallManifest::path(&manifest_dir);
+        fs::remove_file(&manifest_path).await.context(IoSnafu {
+            action: "remove install manifest".to_string(),
+            path: manifest_path.display().to_string(),
+        })?;
+        // Only succeeds if nothing else was put there.
+        let _ = fs::remove_dir(&manifest_dir).await;
+
+        tracing::info!(version = %manifest.version, removed, prefix = %prefix.display(), "Prefix install removed successfully");
+        Ok(())
+    }
 }
 
 impl CommandExecutor for RemoveArgs {
     async fn execute(self, ctx: CommandContext) -> Result<()> {
-        let target_dir = match self.path {
-            Some(p) => p,
+        if let Some(prefix) = &self.prefix {
+            return self.remove_from_prefix(&ctx, prefix).await;
+        }
+
+        let target_dir = match &self.path {
+            Some(p) => p.clone(),
             None => default_path()?,
         };
         let versions_dir = target_dir.join("versions");
@@ -94,23 +230,21 @@ impl CommandExecutor for RemoveArgs {
         };
 
         if self.all {
-            tracing::debug!("Removing all installed versions");
-            if let Err(e) = uninstall_path(&target_dir) {
-                tracing::warn!(error = %e.to_string(), "Failed to update shell rc files during --all removal");
-            }
-            fs::remove_dir_all(&target_dir).await?;
-            tracing::info!("All versions and configuration removed successfully");
-            return Ok(());
+            return self.remove_all(&target_dir).await;
         }
 
-        let version = ctx.client.resolve_version(&self.version).inspect_err(
-            |e| tracing::error!(error = %e.to_string(), "Failed to resolve version"),
-        )?;
+        let version = ctx
+            .client
+            .resolve_version(&self.version)
+            .await
+            .inspect_err(
+                |e| tracing::error!(error = %e.to_string(), "Failed to resolve version"),
+            )?;
         tracing::debug!(%version, "Resolved version for use");
 
         let version_dir = versions_dir.join(version.to_string());
         if version_dir.exists() {
-            fs::remove_dir_all(&version_dir).await?;
+            crate::fs::remove_dir_all_safe(&version_dir).await?;
             tracing::info!(version = %version, "Version removed successfully");
         }
 
@@ -126,10 +260,10 @@ impl CommandExecutor for RemoveArgs {
 
         if remaining_versions == 0 {
             tracing::debug!("No versions remaining, cleaning up configuration");
-            if let Err(e) = uninstall_path(&target_dir) {
+            if let Err(e) = self.uninstall_path(&target_dir) {
                 tracing::warn!(error = %e.to_string(), "Failed to update shell rc files when cleaning up last version");
             }
-            fs::remove_dir_all(&target_dir).await?;
+            crate::fs::remove_dir_all_safe(&target_dir).await?;
             tracing::info!("All versions and configuration removed successfully");
             return Ok(());
         }
@@ -0,0 +1,76 @@
+use clap::{Parser, ValueEnum};
+
+use crate::{
+    api::{Asset, Linkage, SumsFile},
+    cli::{CommandContext, CommandExecutor},
+    prelude::*,
+    target::{SupportedTarget, SUPPORTED_TARGETS},
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct TargetsArgs {
+    /// Also show which builds this release publishes, e.g. `latest`, `0.14.1`
+    ///
+    /// Read from the release's checksum file; without it, only the supported combinations are
+    /// listed and nothing is fetched.
+    #[arg(long)]
+    pub version: Option<String>,
+}
+
+impl CommandExecutor for TargetsArgs {
+    /// Prints every `--os`/`--arch`/linkage combination from [`SUPPORTED_TARGETS`], one per
+    /// line, with the archive name and whether it is published when a version is given.
+    #[tracing::instrument(name = "targets", skip_all, fields(version = self.version))]
+    async fn execute(self, ctx: CommandContext) -> Result<()> {
+        let Some(version) = &self.version else {
+            println!("{:<8} {:<8} {:<10} LINKAGE", "OS", "ARCH", "LIBC");
+            for target in SUPPORTED_TARGETS {
+                println!("{}", row(target).trim_end());
+            }
+            return Ok(());
+        };
+
+        let version = ctx.client.resolve_version(version).await?;
+        let listed = SumsFile.listed(&ctx.client, &version).await?;
+        if listed.is_none() {
+            tracing::warn!(%version, "Release has no checksum file; cannot tell which builds it publishes");
+        }
+
+        println!("Release: {version}");
+        println!(
+            "{:<8} {:<8} {:<10} {:<8} {:<48} PUBLISHED",
+            "OS", "ARCH", "LIBC", "LINKAGE", "ASSET"
+        );
+        for target in SUPPORTED_TARGETS {
+            let asset = match target.linkage {
+                Linkage::Dynamic => Some(Asset::new(&version, &target.os, &target.arch)),
+                Linkage::Static => Asset::new_static(&version, &target.os, &target.arch),
+            };
+            let Some(asset) = asset else { continue };
+            let published = match &listed {
+                Some(listed) if listed.contains(&asset.archive_name) => "yes",
+                Some(_) => "no",
+                None => "unknown",
+            };
+            println!("{} {:<48} {published}", row(target), asset.archive_name);
+        }
+        Ok(())
+    }
+}
+
+/// The OS, arch, libc and linkage columns, with the names `--os` and `--arch` accept.
+fn row(target: &SupportedTarget) -> String {
+    let name = |value: Option<clap::builder::PossibleValue>| {
+        value.map_or_else(String::new, |v| v.get_name().to_string())
+    };
+    let linkage = match target.linkage {
+        Linkage::Dynamic => "dynamic",
+        Linkage::Static => "static",
+    };
+    format!(
+        "{:<8} {:<8} {:<10} {linkage:<8}",
+        name(target.os.to_possible_value()),
+        name(target.arch.to_possible_value()),
+        target.libc
+    )
+}
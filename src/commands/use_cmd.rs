@@ -2,6 +2,7 @@ use clap::Parser;
 use std::path::PathBuf;
 
 use crate::{
+    api::{installed_versions, newest_matching, partial_version_req},
     cli::{CommandContext, CommandExecutor},
     commands::default_path,
     fs,
@@ -11,6 +12,8 @@ use crate::{
 #[derive(Debug, Parser)]
 pub struct UseArgs {
     /// WasmEdge version to use, e.g. `latest`, `0.14.1`, `0.15.0`, etc.
+    ///
+    /// A partial version such as `0.14` selects the newest installed `0.14.x`.
     pub version: String,
 
     /// Set the install location for the WasmEdge runtime
@@ -23,16 +26,29 @@ pub struct UseArgs {
 impl CommandExecutor for UseArgs {
     #[tracing::instrument(name = "use", skip_all, fields(version = self.version))]
     async fn execute(self, ctx: CommandContext) -> Result<()> {
-        let version = ctx.client.resolve_version(&self.version).inspect_err(
-            |e| tracing::error!(error = %e.to_string(), "Failed to resolve version"),
-        )?;
-        tracing::debug!(%version, "Resolved version for use");
-
         let target_dir = match self.path {
             Some(p) => p,
             None => default_path()?,
         };
 
+        // A partial version picks among the installed versions rather than the releases.
+        let version = match partial_version_req(&self.version) {
+            Some(req) => {
+                let installed = installed_versions(&target_dir.join("versions"))?;
+                newest_matching(&installed, &req).ok_or_else(|| Error::VersionNotFound {
+                    version: self.version.clone(),
+                })?
+            }
+            None => ctx
+                .client
+                .resolve_version(&self.version)
+                .await
+                .inspect_err(
+                    |e| tracing::error!(error = %e.to_string(), "Failed to resolve version"),
+                )?,
+        };
+        tracing::debug!(%version, "Resolved version for use");
+
         let version_dir = target_dir.join("versions").join(version.to_string());
         if !version_dir.exists() {
             return Err(Error::VersionNotFound {
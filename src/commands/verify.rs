@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+
+use crate::{
+    cli::{CommandContext, CommandExecutor},
+    commands::default_path,
+    manifest::InstallManifest,
+    prelude::*,
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct VerifyArgs {
+    /// Installed WasmEdge version to verify, e.g. `0.14.1`
+    ///
+    /// Defaults to every installed version.
+    pub version: Option<String>,
+
+    /// Set the install location for the WasmEdge runtime
+    ///
+    /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
+
+impl CommandExecutor for VerifyArgs {
+    async fn execute(self, _ctx: CommandContext) -> Result<()> {
+        let target_dir = match self.path {
+            Some(p) => p,
+            None => default_path()?,
+        };
+        let versions_dir = target_dir.join("versions");
+
+        let explicit = self.version.is_some();
+        let versions = match self.version {
+            Some(version) => {
+                let version = version.trim_start_matches('v').to_string();
+                if !versions_dir.join(&version).is_dir() {
+                    return Err(Error::VersionNotFound { version });
+                }
+                vec![version]
+            }
+            None => installed_versions(&versions_dir)?,
+        };
+
+        let mut failures = 0;
+        for version in versions {
+            let version_dir = versions_dir.join(&version);
+            let manifest = match InstallManifest::load(&version_dir, &version) {
+                Ok(manifest) => manifest,
+                Err(e @ Error::ManifestNotFound { .. }) if !explicit => {
+                    println!("{version}: skipped ({e})");
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let problems = manifest.verify(&version_dir)?;
+            if problems.is_empty() {
+                println!("{version}: OK ({} files)", manifest.files.len());
+            } else {
+                println!("{version}: FAILED");
+                for problem in &problems {
+                    println!("  {problem}");
+                }
+                failures += problems.len();
+            }
+        }
+
+        if failures > 0 {
+            return Err(Error::VerificationFailed { count: failures });
+        }
+        Ok(())
+    }
+}
+
+fn installed_versions(versions_dir: &Path) -> Result<Vec<String>> {
+    let entries = std::fs::read_dir(versions_dir).map_err(|_| Error::InvalidPath {
+        path: versions_dir.display().to_string(),
+        reason: "no WasmEdge installation found".to_string(),
+    })?;
+
+    let mut versions = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    versions.sort_by(|a, b| b.cmp(a));
+    Ok(versions)
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use snafu::ResultExt;
+
+use crate::{
+    api::{Asset, WasmEdgeApiClient},
+    cli::{CommandContext, CommandExecutor},
+    prelude::*,
+    target::{TargetArch, TargetArchParser, TargetOS},
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct VerifyFileArgs {
+    /// Path to a downloaded WasmEdge release archive
+    pub file: PathBuf,
+
+    /// WasmEdge version the archive belongs to, e.g. `latest`, `0.14.1`, `0.14.1-rc.1`, etc.
+    #[arg(long)]
+    pub version: String,
+
+    /// Target OS the archive was built for
+    ///
+    /// `wasmedgeup` will detect the OS of your host system by default.
+    #[arg(short, long, ignore_case = true)]
+    pub os: Option<TargetOS>,
+
+    /// Target architecture the archive was built for
+    ///
+    /// `wasmedgeup` will detect the architecture of your host system by default.
+    #[arg(short, long, ignore_case = true, value_parser = TargetArchParser)]
+    pub arch: Option<TargetArch>,
+}
+
+impl CommandExecutor for VerifyFileArgs {
+    /// Checks a local archive against the checksum published for its release.
+    ///
+    /// Prints `PASS` or `FAIL`; a mismatch is returned as [`Error::ChecksumMismatch`]. Nothing
+    /// is extracted or installed.
+    #[tracing::instrument(name = "verify-file", skip_all, fields(file = %self.file.display()))]
+    async fn execute(self, ctx: CommandContext) -> Result<()> {
+        let version = ctx.client.resolve_version(&self.version).await?;
+        let os = self.os.unwrap_or_default();
+        let arch = self.arch.unwrap_or_default();
+        let asset = Asset::new(&version, &os, &arch);
+        tracing::debug!(%version, asset = %asset.archive_name, "Verifying local file");
+
+        let expected = ctx.client.get_release_checksum(&version, &asset).await?;
+
+        let mut file = std::fs::File::open(&self.file).context(IoSnafu {
+            action: "open file to verify".to_string(),
+            path: self.file.display().to_string(),
+        })?;
+        match WasmEdgeApiClient::verify_file_checksum(&mut file, &expected).await {
+            Ok(()) => {
+                println!(
+                    "PASS: {} matches {} ({version})",
+                    self.file.display(),
+                    asset.archive_name
+                );
+                Ok(())
+            }
+            Err(e) => {
+                println!(
+                    "FAIL: {} does not match {} ({version})",
+                    self.file.display(),
+                    asset.archive_name
+                );
+                Err(e)
+            }
+        }
+    }
+}
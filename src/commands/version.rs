@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{
+    cli::{CommandContext, CommandExecutor},
+    commands::default_path,
+    manifest::InstallManifest,
+    prelude::*,
+    target::{TargetArch, TargetOS},
+};
+
+#[derive(Debug, Default, Parser)]
+pub struct VersionArgs {
+    /// Print the versions as a JSON object
+    #[arg(long)]
+    pub json: bool,
+
+    /// Set the install location for the WasmEdge runtime
+    ///
+    /// Defaults to `$HOME/.wasmedge` on Unix-like systems and `%HOME%\.wasmedge` on Windows.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
+
+/// What `version --json` prints.
+#[derive(Debug, Serialize)]
+struct Versions {
+    wasmedgeup: &'static str,
+    /// `None` when no version is active
+    wasmedge: Option<Runtime>,
+}
+
+/// The active runtime; the target is `null` without a readable install manifest.
+#[derive(Debug, Serialize)]
+struct Runtime {
+    version: String,
+    path: PathBuf,
+    os: Option<TargetOS>,
+    arch: Option<TargetArch>,
+}
+
+impl CommandExecutor for VersionArgs {
+    /// Prints the version of wasmedgeup and of the active WasmEdge runtime with its path and,
+    /// from its install manifest, its target, e.g. for bug reports.
+    #[tracing::instrument(name = "version", skip_all)]
+    async fn execute(self, _ctx: CommandContext) -> Result<()> {
+        let target_dir = self.path.map_or_else(default_path, Ok)?;
+        let runtime = crate::fs::active_version(&target_dir)
+            .await
+            .map(|version| {
+                let dir = target_dir.join("versions").join(&version);
+                let manifest = InstallManifest::load(&dir, &version)
+                    .inspect_err(|e| tracing::debug!(%version, error = %e.to_string(), "No install manifest for the active version"))
+                    .ok();
+                Runtime {
+                    path: std::path::absolute(&dir).unwrap_or(dir),
+                    os: manifest.as_ref().map(|m| m.os),
+                    arch: manifest.map(|m| m.arch),
+                    version,
+                }
+            });
+        let versions = Versions {
+            wasmedgeup: env!("CARGO_PKG_VERSION"),
+            wasmedge: runtime,
+        };
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&versions).map_err(|_| Error::Unknown)?;
+            println!("{json}");
+            return Ok(());
+        }
+        println!("wasmedgeup: {}", versions.wasmedgeup);
+        match versions.wasmedge {
+            Some(runtime) => {
+                println!("wasmedge: {}", runtime.version);
+                println!("path: {}", runtime.path.display());
+                if let (Some(os), Some(arch)) = (runtime.os, runtime.arch) {
+                    println!("target: {os:?} {arch:?}");
+                }
+            }
+            None => println!(
+                "wasmedge: none (no active version in {})",
+                target_dir.display()
+            ),
+        }
+        Ok(())
+    }
+}
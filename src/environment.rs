@@ -0,0 +1,218 @@
+//! The home, config, cache and temp directories and the environment variables that path
+//! resolution depends on, read through one [`Environment`] that tests and sandboxes can
+//! override.
+
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{PoisonError, RwLock},
+};
+
+use snafu::ResultExt;
+
+use crate::prelude::*;
+
+/// The variables an env file may set: proxies, the GitHub token and the download mirror.
+pub const ENV_FILE_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "http_proxy",
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "NO_PROXY",
+    "no_proxy",
+    "GITHUB_TOKEN",
+    "WASMEDGEUP_MIRROR",
+];
+
+/// The environment set with [`Environment::activate`], if any.
+static ACTIVE: RwLock<Option<Environment>> = RwLock::new(None);
+
+/// Where the default install location, the download cache, shell rc files and temporary files
+/// are looked up.
+///
+/// Each getter returns the override set with the matching `with_*` method, or else what the
+/// process and the OS report. [`Environment::current`] is what the rest of the crate reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Environment {
+    home: Option<PathBuf>,
+    temp: Option<PathBuf>,
+    /// Variables to report instead of the process's; `None` reports them as unset
+    vars: BTreeMap<String, Option<OsString>>,
+}
+
+impl Environment {
+    /// The process environment, without overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The environment path lookups use: the one last [activated](Self::activate), or else
+    /// the process environment.
+    pub fn current() -> Self {
+        ACTIVE
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Makes [`Environment::current`] return this environment until the returned guard is
+    /// dropped, which restores the previous one.
+    ///
+    /// This affects the whole process, so tests that activate one must not run concurrently
+    /// with others that resolve paths.
+    pub fn activate(self) -> ActiveEnvironment {
+        let mut active = ACTIVE.write().unwrap_or_else(PoisonError::into_inner);
+        ActiveEnvironment {
+            previous: active.replace(self),
+        }
+    }
+
+    /// Uses `home` as the home directory.
+    pub fn with_home(mut self, home: impl Into<PathBuf>) -> Self {
+        self.home = Some(home.into());
+        self
+    }
+
+    /// Uses `dir` for temporary files.
+    pub fn with_temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp = Some(dir.into());
+        self
+    }
+
+    /// Reports `value` for the environment variable `name`.
+    pub fn with_var(mut self, name: impl Into<String>, value: impl Into<OsString>) -> Self {
+        self.vars.insert(name.into(), Some(value.into()));
+        self
+    }
+
+    /// Reports the environment variable `name` as unset.
+    pub fn without_var(mut self, name: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), None);
+        self
+    }
+
+    /// Sets the [`ENV_FILE_VARS`] assigned in the dotenv file at `path` that are not set
+    /// already, so the process environment takes precedence over the file.
+    ///
+    /// Lines are `NAME=value`, optionally prefixed with `export` and with the value in single or
+    /// double quotes; blank lines and `#` comments are skipped. Other variables are ignored
+    /// with a warning. Only names are logged, never values.
+    pub fn with_env_file(mut self, path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).context(IoSnafu {
+            action: "read env file".to_string(),
+            path: path.display().to_string(),
+        })?;
+        for (number, line) in content.lines().enumerate() {
+            let invalid = |reason: &str| Error::InvalidEnvFile {
+                path: path.display().to_string(),
+                line: number + 1,
+                reason: reason.to_string(),
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("expected NAME=value"))?;
+            let name = name.trim();
+            let value = unquote(value.trim()).ok_or_else(|| invalid("unterminated quote"))?;
+            if !ENV_FILE_VARS.contains(&name) {
+                tracing::warn!(name, path = %path.display(), "Ignoring variable the env file may not set");
+                continue;
+            }
+            if self.var_os(name).is_some() {
+                tracing::debug!(name, "Keeping the environment's value over the env file's");
+                continue;
+            }
+            tracing::debug!(name, path = %path.display(), "Loaded from env file");
+            self = self.with_var(name, value);
+        }
+        Ok(self)
+    }
+
+    /// Whether `name` is overridden rather than read from the process environment.
+    pub fn has_override(&self, name: &str) -> bool {
+        self.vars.contains_key(name)
+    }
+
+    pub fn var_os(&self, name: &str) -> Option<OsString> {
+        match self.vars.get(name) {
+            Some(value) => value.clone(),
+            None => std::env::var_os(name),
+        }
+    }
+
+    /// The variable `name`, when it is set to valid Unicode.
+    pub fn var(&self, name: &str) -> Option<String> {
+        self.var_os(name)?.into_string().ok()
+    }
+
+    pub fn home_dir(&self) -> Option<PathBuf> {
+        self.home.clone().or_else(dirs::home_dir)
+    }
+
+    /// An XDG base directory variable; relative values are invalid per the spec and ignored.
+    pub fn xdg_dir(&self, var: &str) -> Option<PathBuf> {
+        self.var_os(var)
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+    }
+
+    /// `$XDG_CONFIG_HOME`, or `~/.config` when it is unset, where shells such as fish keep
+    /// their configuration on every Unix.
+    pub fn config_dir(&self) -> Option<PathBuf> {
+        self.xdg_dir("XDG_CONFIG_HOME")
+            .or_else(|| Some(self.home_dir()?.join(".config")))
+    }
+
+    /// The per-user cache directory of the platform, e.g. `~/.cache` on Linux.
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        let home = self.home_dir();
+        if cfg!(windows) {
+            // The known folder cannot be derived from an overridden home.
+            return match &self.home {
+                Some(home) => Some(home.join("AppData").join("Local")),
+                None => dirs::cache_dir(),
+            };
+        }
+        if cfg!(target_os = "macos") {
+            return Some(home?.join("Library").join("Caches"));
+        }
+        self.xdg_dir("XDG_CACHE_HOME")
+            .or_else(|| Some(home?.join(".cache")))
+    }
+
+    pub fn temp_dir(&self) -> PathBuf {
+        self.temp.clone().unwrap_or_else(std::env::temp_dir)
+    }
+}
+
+/// A dotenv value without its quotes, or `None` when a quote is not closed. Unquoted values
+/// end at a ` #` comment.
+fn unquote(value: &str) -> Option<&str> {
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote) {
+            return rest.find(quote).map(|end| &rest[..end]);
+        }
+    }
+    Some(value.split(" #").next().unwrap_or_default().trim_end())
+}
+
+/// Keeps an [activated](Environment::activate) environment in effect until dropped.
+#[derive(Debug)]
+#[must_use = "the environment is only active until the guard is dropped"]
+pub struct ActiveEnvironment {
+    previous: Option<Environment>,
+}
+
+impl Drop for ActiveEnvironment {
+    fn drop(&mut self) {
+        *ACTIVE.write().unwrap_or_else(PoisonError::into_inner) = self.previous.take();
+    }
+}
@@ -6,12 +6,27 @@ pub enum Error {
     #[snafu(display("Version {version} not found in wasmedge installation"))]
     VersionNotFound { version: String },
 
-    #[snafu(display("Unable to fetch resource '{}' for git", resource))]
+    #[snafu(display("Unable to fetch resource '{}' for git: {}", resource, source.message()))]
     Git {
         source: git2::Error,
         resource: &'static str,
     },
 
+    #[snafu(display(
+        "Version {version} not found among the published releases{}",
+        did_you_mean(close)
+    ))]
+    VersionNotPublished { version: String, close: Vec<String> },
+
+    #[snafu(display("Unknown release channel '{name}'; valid channels are: {valid}"))]
+    UnknownChannel { name: String, valid: String },
+
+    #[snafu(display("--since {since} is newer than --until {until}, so no version can match"))]
+    EmptyVersionWindow { since: String, until: String },
+
+    #[snafu(display("Invalid repository '{slug}'; expected the form 'owner/name'"))]
+    InvalidRepo { slug: String },
+
     #[snafu(display("Invalid semantic version specifier"))]
     SemVer { source: semver::Error },
 
@@ -33,6 +48,9 @@ pub enum Error {
         source: zip::result::ZipError,
     },
 
+    #[snafu(display("Cannot extract a {format} archive: this build of wasmedgeup only supports {supported}.\n\nDownload a release archive in a supported format, or install a wasmedgeup build with {format} support from https://github.com/WasmEdge/wasmedgeup/releases"))]
+    UnsupportedArchiveFormat { format: String, supported: String },
+
     #[snafu(transparent)]
     IO { source: std::io::Error },
 
@@ -52,12 +70,85 @@ pub enum Error {
     #[snafu(display("Parent directory not found for rc path: {}", path))]
     RcDirNotFound { path: String },
 
-    #[snafu(display("Checksum not found for version {} asset {}", version, asset))]
-    ChecksumNotFound { version: String, asset: String },
+    #[snafu(display(
+        "Checksum not found for version {} asset {}{}",
+        version,
+        asset,
+        listed_hint(listed)
+    ))]
+    ChecksumNotFound {
+        version: String,
+        asset: String,
+        /// Files the checksum file lists instead; empty when the file itself is missing
+        listed: Vec<String>,
+    },
+
+    #[snafu(display("WasmEdge {version} predates published checksums: the release has {asset} but no checksum file to verify it against.\n\nPass --expected-checksum <HEX> with a checksum from a source you trust, or --no-verify to install it unverified"))]
+    ChecksumsUnpublished { version: String, asset: String },
+
+    #[snafu(display("Signature check of {file} failed: {reason}"))]
+    SignatureInvalid { file: String, reason: String },
 
     #[snafu(display("Checksum mismatch. Expected: {}, got: {}", expected, actual))]
     ChecksumMismatch { expected: String, actual: String },
 
+    #[snafu(display("Timed out after {secs}s during {operation}"))]
+    Timeout { operation: &'static str, secs: u64 },
+
+    #[snafu(display(
+        "Could not resolve the latest version: {source}\n\nPass an explicit version instead, e.g. `wasmedgeup install 0.14.1`"
+    ))]
+    LatestVersionUnresolved { source: Box<Error> },
+
+    #[snafu(display("Offline mode: {operation} requires network access.\n\nInstall from the download cache, from a lockfile with --from-manifest, or from a local archive with --url file://<path>; or run without --offline"))]
+    Offline { operation: &'static str },
+
+    #[snafu(display("Attempt timed out after {secs}s during {operation}"))]
+    AttemptTimeout { operation: &'static str, secs: u64 },
+
+    #[snafu(display("No build available at {url}: the server has no such file (404 Not Found).\n\nThe release may not publish an archive for this OS and architecture; check --os and --arch, or pick another version"))]
+    AssetNotFound { url: String },
+
+    #[snafu(display("Incomplete download (expected {expected} bytes, got {actual})"))]
+    IncompleteDownload { expected: u64, actual: u64 },
+
+    #[snafu(display(
+        "No install manifest for version {version}; reinstall it to enable verification"
+    ))]
+    ManifestNotFound { version: String },
+
+    #[snafu(display("Unable to parse the GitHub release listing: {source}"))]
+    ReleaseListing { source: serde_json::Error },
+
+    #[snafu(display("Invalid install lockfile at {path}: {reason}"))]
+    InvalidLockfile { path: String, reason: String },
+
+    #[snafu(display("Invalid install manifest at {path}: {source}"))]
+    Manifest {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Verification failed: {count} file(s) missing or modified"))]
+    VerificationFailed { count: usize },
+
+    #[snafu(display("{failed} of {total} version(s) failed to install"))]
+    BatchInstallFailed { failed: usize, total: usize },
+
+    #[snafu(display("Invalid env file {path}, line {line}: {reason}"))]
+    InvalidEnvFile {
+        path: String,
+        line: usize,
+        reason: String,
+    },
+
+    #[snafu(display("Invalid {name}={value}: {reason}"))]
+    InvalidEnv {
+        name: String,
+        value: String,
+        reason: String,
+    },
+
     #[snafu(display("Invalid path {path}: {reason}"))]
     InvalidPath { path: String, reason: String },
 
@@ -68,6 +159,15 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[snafu(display("'{program}' exited with {}", code.map_or_else(|| "a signal".to_string(), |c| format!("status {c}"))))]
+    ChildProcessFailed { program: String, code: Option<i32> },
+
+    #[snafu(display("Installing for {os} on a {host} host requires an explicit --arch; pass `--arch native` to use this machine's architecture"))]
+    CrossInstallArchRequired { os: String, host: String },
+
+    #[snafu(display("{marker}, so wasmedgeup may be running emulated and cannot tell which architecture to install; pass --arch, or `--arch native` for the {build} build"))]
+    EmulatedArchRequired { marker: String, build: String },
+
     #[snafu(display("Unsupported platform: os={os} arch={arch}"))]
     UnsupportedPlatform { os: String, arch: String },
 
@@ -76,6 +176,23 @@ pub enum Error {
     ))]
     RuntimeNotFound,
 
+    #[snafu(display("Interrupted"))]
+    Interrupted,
+
+    #[snafu(display(
+        "{reason}; pass the version to install instead, e.g. `wasmedgeup install latest`"
+    ))]
+    InteractiveUnavailable { reason: &'static str },
+
+    #[snafu(display("Could not tell the version of {url} from its file name; pass it with --label, e.g. --label 0.15.0-ci.1"))]
+    UrlVersionUnknown { url: String },
+
+    #[snafu(display("WasmEdge {latest} is available (active: {current})"))]
+    UpdateAvailable { current: String, latest: String },
+
+    #[snafu(display("Refusing to {action} without confirmation; pass --yes to proceed"))]
+    ConfirmationRequired { action: String },
+
     #[default]
     #[snafu(display("Unknown error occurred"))]
     Unknown,
@@ -87,6 +204,12 @@ pub enum Error {
     #[snafu(display("Error: Cannot create symbolic links.\n\nTo enable symlink creation on Windows:\n  1. Run as Administrator, or\n  2. Enable Developer Mode:\n     - Open Windows Settings\n     - Update & Security > For developers\n     - Enable 'Developer Mode'\n"))]
     WindowsSymlinkError { version: String },
 
+    #[snafu(display("The archive contained no installable files: {reason}.\n\nThe download verified, so the archive itself is likely packaged wrongly, e.g. by a mirror; try another source or version"))]
+    NoInstallableFiles { reason: String },
+
+    #[snafu(display("None of the archive's files matches --include/--exclude, so nothing was installed. Patterns match paths such as bin/wasmedge or lib64/libwasmedge.so"))]
+    NoFilesSelected,
+
     #[snafu(display("Invalid archive structure: found '{found_file}' but expected either a WasmEdge directory or standard directories (bin, lib64, include, lib).\n\nThis might indicate:\n  1. A corrupted download\n  2. An unsupported archive format\n  3. A change in the WasmEdge release structure"))]
     InvalidArchiveStructure { found_file: String },
 
@@ -97,6 +220,50 @@ pub enum Error {
 
     #[snafu(display("Failed to build HTTP client: {reason}"))]
     HttpClientBuild { reason: String },
+
+    #[snafu(display("Could not load CA certificate from {path}: {reason}"))]
+    InvalidCaCert { path: String, reason: String },
+}
+
+fn did_you_mean(close: &[String]) -> String {
+    match close {
+        [] => String::new(),
+        [only] => format!("; did you mean {only}?"),
+        [rest @ .., last] => format!("; did you mean {} or {last}?", rest.join(", ")),
+    }
+}
+
+fn listed_hint(listed: &[String]) -> String {
+    match listed.len() {
+        0 => String::new(),
+        n => format!(" ({n} other file(s) listed; run with -v to see them)"),
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Process exit code for this error: 130 (128 + SIGINT) when interrupted, 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Interrupted => 130,
+            _ => 1,
+        }
+    }
+
+    /// Whether the failure is likely transient and the operation is worth retrying.
+    ///
+    /// Transport errors, stalled attempts and truncated transfers qualify; integrity failures
+    /// such as [`Error::ChecksumMismatch`] do not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            // Client errors such as a 404 will not go away by asking again.
+            Error::Request { source, .. } => source
+                .status()
+                .is_none_or(|s| s.is_server_error() || s.as_u16() == 429),
+            Error::IncompleteDownload { .. } | Error::AttemptTimeout { .. } => true,
+            Error::Git { source, .. } => source.class() == git2::ErrorClass::Net,
+            _ => false,
+        }
+    }
+}
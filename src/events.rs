@@ -0,0 +1,38 @@
+//! The newline-delimited JSON events `install --json-lines` prints on stdout.
+
+use serde::Serialize;
+
+use crate::commands::install::InstallSummary;
+
+/// One line of `install --json-lines` output, a JSON object whose `event` field names it.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub(crate) enum ProgressEvent<'a> {
+    /// The version to install is known
+    Resolve { version: String },
+    /// Another percent of the archive was downloaded
+    Download {
+        asset: &'a str,
+        pct: u64,
+        downloaded: u64,
+        total: u64,
+    },
+    /// The archive matched its checksum
+    Verify { checksum: &'a str },
+    /// The archive was unpacked into the staging directory
+    Extract,
+    /// The install finished, with the fields `--json` prints
+    Done(&'a InstallSummary),
+    /// The install failed, with the diagnostics for bug reports; followed by [`Self::Error`]
+    Diagnostics { report: &'a str },
+    /// The install failed
+    Error { message: String },
+}
+
+/// Prints `event` as one line of JSON on stdout.
+pub(crate) fn emit(event: &ProgressEvent<'_>) {
+    match serde_json::to_string(event) {
+        Ok(line) => println!("{line}"),
+        Err(e) => tracing::warn!(error = %e, "Failed to serialize progress event"),
+    }
+}
@@ -6,11 +6,15 @@ use std::io::Seek;
 #[cfg(unix)]
 use std::os::unix::fs::symlink as symlink_unix;
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 #[cfg(windows)]
 use std::os::windows::fs::{symlink_dir, symlink_file};
 
+use glob::{MatchOptions, Pattern};
 use std::fs::OpenOptions;
 use tokio::fs;
 use walkdir::WalkDir;
@@ -31,7 +35,188 @@ pub fn can_write_to_directory(path: &Path) -> bool {
     can_write
 }
 
+/// Selects the files [`copy_tree_matching`] copies by glob patterns over their path relative to
+/// the source directory, using `/` separators and the names as extracted (i.e. `lib64`).
+///
+/// Without include patterns every file is a candidate; exclude patterns take precedence over
+/// include patterns. `*` does not match `/`, `**` matches across directories.
+#[derive(Debug, Clone, Default)]
+pub struct CopyFilter {
+    pub include: Vec<Pattern>,
+    pub exclude: Vec<Pattern>,
+    /// Leave destination files with the same size and SHA-256 (and symlinks with the same
+    /// target) as they are, so their mtimes do not change
+    pub preserve_existing: bool,
+}
+
+/// What [`copy_tree_matching`] did with the files and symlinks it found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyStats {
+    /// Written to the destination
+    pub copied: usize,
+    /// Not selected by the filter's patterns
+    pub filtered: usize,
+    /// Already identical at the destination, with `preserve_existing`
+    pub unchanged: usize,
+}
+
+impl CopyFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether the file at `relative` (e.g. `lib64/libwasmedge.so`) is copied.
+    pub fn matches(&self, relative: &str) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        let matches = |pattern: &Pattern| pattern.matches_with(relative, options);
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Whether the archive entry at `path` (e.g. `WasmEdge-0.14.1-Linux/lib64/libwasmedge.so`)
+    /// is extracted, so that files the filter does not select are never written at all.
+    ///
+    /// The patterns are matched below the first `strip` components, or without `strip` below
+    /// the wrapping directories, i.e. from the first of the [`LAYOUT_DIRS`] on. Entries above
+    /// that depth, such as the wrapping directories themselves, are always extracted, so that
+    /// the layout can still be told from what was extracted.
+    pub fn matches_entry(&self, path: &Path, strip: Option<usize>) -> bool {
+        let components = path
+            .components()
+            .filter_map(|c| match c {
+                std::path::Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let start = match strip {
+            Some(count) => count,
+            None => match components
+                .iter()
+                .position(|name| LAYOUT_DIRS.contains(&name.as_ref()))
+            {
+                Some(depth) => depth,
+                None => return true,
+            },
+        };
+        components.len() <= start + 1 || self.matches(&components[start..].join("/"))
+    }
+}
+
 pub async fn copy_tree(from_dir: &Path, to_dir: &Path) -> Result<()> {
+    copy_tree_matching(from_dir, to_dir, &CopyFilter::default())
+        .await
+        .map(|_| ())
+}
+
+/// Moves the extracted tree `from_dir` to the empty directory `to_dir` by renaming it, with
+/// `lib64` renamed to `lib` as [`copy_tree`] does.
+///
+/// Renames only work within one filesystem; returns `false` when the tree could not be moved,
+/// in which case it is left for [`copy_tree`] to copy.
+pub async fn move_tree(from_dir: &Path, to_dir: &Path) -> bool {
+    let (lib64, lib) = (from_dir.join("lib64"), from_dir.join("lib"));
+    if lib64.exists() {
+        if lib.exists() {
+            // Both would have to be merged, which renaming cannot do.
+            return false;
+        }
+        if let Err(e) = fs::rename(&lib64, &lib).await {
+            tracing::debug!(error = %e, "Could not rename lib64 to lib; copying instead");
+            return false;
+        }
+    }
+
+    if fs::remove_dir(to_dir).await.is_err() {
+        tracing::debug!(to_dir = %to_dir.display(), "Target directory not empty; copying instead");
+        return false;
+    }
+    match fs::rename(from_dir, to_dir).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::debug!(error = %e, "Could not move staged files; copying instead");
+            let _ = fs::create_dir_all(to_dir).await;
+            false
+        }
+    }
+}
+
+/// Top-level directories of a release archive once any wrapping directory is stripped.
+pub const LAYOUT_DIRS: [&str; 4] = ["bin", "lib64", "include", "lib"];
+
+/// Lists the names of the entries of `dir`, sorted, and whether each is a directory.
+async fn dir_entries(dir: &Path) -> Result<Vec<(String, bool)>> {
+    let mut entries = Vec::new();
+    let mut read_dir = fs::read_dir(dir).await?;
+    while let Some(entry) = read_dir.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        entries.push((name, entry.file_type().await?.is_dir()));
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+/// Counts the directories wrapping the release layout extracted to `dir`, such as
+/// `WasmEdge-0.14.1-Linux/`: the levels that hold a single directory which is not itself one
+/// of the [`LAYOUT_DIRS`].
+pub async fn wrapper_depth(dir: &Path) -> Result<usize> {
+    let mut depth = 0;
+    let mut dir = dir.to_path_buf();
+    loop {
+        match dir_entries(&dir).await?.as_slice() {
+            [(name, true)] if !LAYOUT_DIRS.contains(&name.as_str()) => {
+                dir.push(name);
+                depth += 1;
+            }
+            _ => return Ok(depth),
+        }
+    }
+}
+
+/// Returns the directory `count` levels below `dir` to install from, dropping the leading path
+/// components of the extracted entries as `tar --strip-components` does.
+///
+/// Files above that depth are skipped, as tar skips them. Each stripped level must hold a
+/// single directory, since the contents of several could not be told apart.
+pub async fn strip_components(dir: &Path, count: usize) -> Result<PathBuf> {
+    let mut dir = dir.to_path_buf();
+    for _ in 0..count {
+        let entries = dir_entries(&dir).await?;
+        let mut dirs = entries.iter().filter(|(_, is_dir)| *is_dir);
+        match (dirs.next(), dirs.next()) {
+            (Some((name, _)), None) => {
+                for (file, _) in entries.iter().filter(|(_, is_dir)| !is_dir) {
+                    tracing::debug!(%file, "Skipping file above --strip-components depth");
+                }
+                dir.push(name);
+            }
+            (None, _) => {
+                return Err(Error::InvalidArchiveStructure {
+                    found_file: "<no directory to strip>".to_string(),
+                })
+            }
+            (Some(_), Some((second, _))) => {
+                return Err(Error::InvalidArchiveStructure {
+                    found_file: second.clone(),
+                })
+            }
+        }
+    }
+    Ok(dir)
+}
+
+/// Like [`copy_tree`], but only copies the files `filter` matches, and with
+/// [`CopyFilter::preserve_existing`] only those that differ from the destination's.
+///
+/// Fails on the first file that cannot be written, leaving the files copied before it in place.
+pub async fn copy_tree_matching(
+    from_dir: &Path,
+    to_dir: &Path,
+    filter: &CopyFilter,
+) -> Result<CopyStats> {
+    let mut stats = CopyStats::default();
     for entry in WalkDir::new(from_dir).into_iter().filter_map(|e| e.ok()) {
         tracing::trace!(entry = %entry.path().display(), "Copying entry");
         let Ok(metadata) = entry.metadata() else {
@@ -41,6 +226,20 @@ pub async fn copy_tree(from_dir: &Path, to_dir: &Path) -> Result<()> {
             continue;
         }
 
+        let relative = entry.path().strip_prefix(from_dir).unwrap_or(entry.path());
+        if !filter.is_empty() {
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if !filter.matches(&relative) {
+                tracing::trace!(entry = %relative, "Skipping entry excluded by filter");
+                stats.filtered += 1;
+                continue;
+            }
+        }
+
         // Calculate the target location by stripping the source directory prefix
         // from the entry path and appending it to the destination directory.
         // During this process, any 'lib64' directory is renamed to 'lib' for consistency.
@@ -53,25 +252,26 @@ pub async fn copy_tree(from_dir: &Path, to_dir: &Path) -> Result<()> {
         // 1. Strip prefix: 'foo/lib64/something.so'
         // 2. Replace lib64: 'foo/lib/something.so'
         // 3. Join with to_dir: '/to/path/foo/lib/something.so'
-        let target_loc = to_dir.join(
-            entry
-                .path()
-                .strip_prefix(from_dir)
-                .unwrap_or(entry.path())
-                .to_string_lossy()
-                .replace("lib64", LIB_DIR),
-        );
+        let target_loc = to_dir.join(relative.to_string_lossy().replace("lib64", LIB_DIR));
 
         let Some(parent) = target_loc.parent() else {
             tracing::warn!(location = %target_loc.display(), "Missing parent for target location");
             continue;
         };
-        if let Err(e) = fs::create_dir_all(parent).await {
-            tracing::warn!(error = %e, directories = %parent.display(), "Failed to create directories");
-            continue;
-        };
+        fs::create_dir_all(parent).await.context(IoSnafu {
+            action: "create directories".to_string(),
+            path: parent.display().to_string(),
+        })?;
         if metadata.is_symlink() {
             if let Ok(target) = std::fs::read_link(entry.path()) {
+                if filter.preserve_existing
+                    && std::fs::read_link(&target_loc).is_ok_and(|existing| existing == target)
+                {
+                    tracing::trace!(entry = %target_loc.display(), "Keeping identical symlink");
+                    stats.unchanged += 1;
+                    continue;
+                }
+                stats.copied += 1;
                 if target_loc.exists() {
                     match fs::remove_file(&target_loc).await {
                         Ok(_) => {}
@@ -155,19 +355,341 @@ pub async fn copy_tree(from_dir: &Path, to_dir: &Path) -> Result<()> {
                     }
                 }
             }
-        } else if let Err(e) = fs::copy(entry.path(), &target_loc).await {
-            tracing::warn!(
-                error = %e,
-                entry = %entry.path().display(),
-                target_loc = %target_loc.display(),
-                "Failed to copy file to target location",
-            );
+        } else if filter.preserve_existing && same_contents(entry.path(), &target_loc) {
+            tracing::trace!(entry = %target_loc.display(), "Keeping identical file");
+            stats.unchanged += 1;
+        } else {
+            fs::copy(entry.path(), &target_loc).await.context(IoSnafu {
+                action: "copy file to target location".to_string(),
+                path: target_loc.display().to_string(),
+            })?;
+            stats.copied += 1;
+            if let Ok(mtime) = metadata.modified() {
+                set_mtime(&target_loc, mtime);
+            }
+        }
+    }
+    Ok(stats)
+}
+
+/// Whether `existing` is a regular file with the same size and SHA-256 as `file`.
+fn same_contents(file: &Path, existing: &Path) -> bool {
+    let (Ok(ours), Ok(theirs)) = (std::fs::metadata(file), std::fs::symlink_metadata(existing))
+    else {
+        return false;
+    };
+    if !theirs.is_file() || ours.len() != theirs.len() {
+        return false;
+    }
+    let hash = |path: &Path| {
+        std::fs::File::open(path)
+            .map_err(Error::from)
+            .and_then(|mut file| crate::api::sha256_hex_file(&mut file))
+            .ok()
+    };
+    hash(file).is_some_and(|ours| hash(existing) == Some(ours))
+}
+
+/// Removes the directory at `path` and its contents, unless `path` is a symlink.
+///
+/// A symlinked directory (e.g. `~/.wasmedge` pointing at another volume) may hold files that
+/// are not ours, so only the link itself is removed and a warning explains what was left.
+pub async fn remove_dir_all_safe(path: &Path) -> std::io::Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || remove_dir_all_safe_blocking(&path))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Blocking variant of [`remove_dir_all_safe`], for where no runtime can be awaited.
+pub fn remove_dir_all_safe_blocking(path: &Path) -> std::io::Result<()> {
+    if !std::fs::symlink_metadata(path)?.is_symlink() {
+        return match std::fs::remove_dir_all(path) {
+            // A `--read-only` install refuses deletion until write permission is restored.
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                make_writable_blocking(path)?;
+                std::fs::remove_dir_all(path)
+            }
+            result => result,
         };
     }
+
+    let target = std::fs::read_link(path).unwrap_or_default();
+    tracing::warn!(
+        path = %path.display(),
+        target = %target.display(),
+        "Not deleting through a symlink; removing the link only and leaving the directory it points to in place"
+    );
+    // Directory symlinks are removed with `remove_dir` on Windows and `remove_file` elsewhere.
+    #[cfg(windows)]
+    return std::fs::remove_dir(path);
+    #[cfg(not(windows))]
+    std::fs::remove_file(path)
+}
+
+/// Removes write permission from every file under `dir`, and with `dirs` from `dir` and the
+/// directories below it too. Symlinks are left alone.
+pub async fn make_read_only(dir: &Path, dirs: bool) -> std::io::Result<()> {
+    let dir = dir.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        walk_permissions(&dir, &mut |is_dir, permissions| {
+            (dirs || !is_dir) && set_write(permissions, false)
+        })
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+/// Gives the owner write permission on `dir` and everything below it again, undoing
+/// [`make_read_only`].
+pub fn make_writable_blocking(dir: &Path) -> std::io::Result<()> {
+    walk_permissions(dir, &mut |_, permissions| set_write(permissions, true))
+}
+
+/// Applies `update` to the permissions of `dir` and every entry below it, writing back those
+/// it changed. Directories are visited before their contents.
+fn walk_permissions(
+    dir: &Path,
+    update: &mut dyn FnMut(bool, &mut std::fs::Permissions) -> bool,
+) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if metadata.is_symlink() {
+        return Ok(());
+    }
+    let mut permissions = metadata.permissions();
+    // Read-only directories are listed before their permissions are removed, as a missing
+    // write bit does not stop listing.
+    let entries = if metadata.is_dir() {
+        std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?
+    } else {
+        Vec::new()
+    };
+    if update(metadata.is_dir(), &mut permissions) {
+        std::fs::set_permissions(dir, permissions)?;
+    }
+    for entry in entries {
+        walk_permissions(&entry.path(), update)?;
+    }
     Ok(())
 }
 
-/// Extracts the contents of a compressed archive (`.tar.gz` for Unix-like systems, `.zip` for Windows) to a specified directory.
+/// Grants or removes write permission, the owner's only when granting; returns whether
+/// anything changed.
+#[cfg(unix)]
+fn set_write(permissions: &mut std::fs::Permissions, writable: bool) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = permissions.mode();
+    let new = if writable {
+        mode | 0o200
+    } else {
+        mode & !0o222
+    };
+    permissions.set_mode(new);
+    new != mode
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn set_write(permissions: &mut std::fs::Permissions, writable: bool) -> bool {
+    let changed = permissions.readonly() == writable;
+    permissions.set_readonly(!writable);
+    changed
+}
+
+/// Mode of the directories an install creates unless `install --mode` says otherwise.
+pub const DEFAULT_DIR_MODE: u32 = 0o755;
+
+/// Gives `dir` and the directories below it `mode`, and the files below it `mode` without its
+/// execute bits unless they are executable, e.g. `0755` and `0644` for `mode` `0755`, so an
+/// install tree does not end up as permissive as the process umask or the archive allow.
+/// Symlinks are left alone; on Windows nothing is changed.
+pub async fn apply_install_mode(dir: &Path, mode: u32) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            walk_permissions(&dir, &mut |is_dir, permissions| {
+                let old = permissions.mode() & 0o7777;
+                let new = if is_dir || old & 0o111 != 0 {
+                    mode
+                } else {
+                    mode & !0o111
+                };
+                permissions.set_mode(new);
+                new != old
+            })
+        })
+        .await
+        .map_err(std::io::Error::other)?
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (dir, mode);
+        Ok(())
+    }
+}
+
+/// Gives the directory `dir` itself `mode`, as [`apply_install_mode`] does for a whole tree.
+pub fn set_dir_mode(dir: &Path, mode: u32) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(mode))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (dir, mode);
+        Ok(())
+    }
+}
+
+/// Name prefix of every staging directory, e.g. `WasmEdge-0.14.1-Linux` or
+/// `WasmEdge-plugin-wasi_nn-0.14.1`.
+pub const STAGING_DIR_PREFIX: &str = "WasmEdge-";
+
+/// Suffix of the file that marks a staging directory as wasmedgeup's, kept next to it rather
+/// than inside so it is not mistaken for part of the archive. It holds the staging process's
+/// ID, so that `clean` leaves the directories of running installs alone.
+pub const STAGING_MARKER_SUFFIX: &str = ".wasmedgeup-staging";
+
+/// The marker file of the staging directory `dir`, e.g. `WasmEdge-0.14.1-Linux.wasmedgeup-staging`.
+pub fn staging_marker(dir: &Path) -> PathBuf {
+    let mut name = dir.file_name().unwrap_or_default().to_os_string();
+    name.push(STAGING_MARKER_SUFFIX);
+    dir.with_file_name(name)
+}
+
+/// Owns a temporary directory and removes it when dropped, unless it was
+/// [disarmed](Self::disarm) to keep it.
+///
+/// This covers every way an install can stop early in one place: an error returned with `?`,
+/// a panic, or the install future being dropped on Ctrl-C.
+#[derive(Debug)]
+pub struct TempDirGuard {
+    path: Option<PathBuf>,
+    /// The [`staging_marker`] written for the directory, if any
+    marker: Option<PathBuf>,
+}
+
+impl TempDirGuard {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            marker: None,
+        }
+    }
+
+    /// Guards the staging directory `path` and writes its [`staging_marker`], which is removed
+    /// together with it. Should the process die before that, `clean` recognizes the directory.
+    pub fn staging(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let marker = staging_marker(&path);
+        std::fs::write(&marker, format!("{}\n", std::process::id()))?;
+        Ok(Self {
+            path: Some(path),
+            marker: Some(marker),
+        })
+    }
+
+    /// Removes the directory now, returning the error a drop could only log. A directory that
+    /// is already gone, e.g. moved elsewhere, is not an error.
+    pub async fn close(mut self) -> std::io::Result<()> {
+        self.remove_marker();
+        let Some(path) = self.path.take() else {
+            return Ok(());
+        };
+        match remove_dir_all_safe(&path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Keeps the directory and returns its path. It is no longer marked as staging, so `clean`
+    /// leaves it alone.
+    pub fn disarm(mut self) -> PathBuf {
+        self.remove_marker();
+        self.path.take().unwrap_or_default()
+    }
+
+    fn remove_marker(&mut self) {
+        if let Some(marker) = self.marker.take() {
+            let _ = std::fs::remove_file(marker);
+        }
+    }
+}
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        self.remove_marker();
+        let Some(path) = self.path.take() else {
+            return;
+        };
+        match remove_dir_all_safe_blocking(&path) {
+            Ok(()) => tracing::debug!(path = %path.display(), "Removed temporary directory"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                tracing::warn!(error = %e, path = %path.display(), "Failed to remove temporary directory")
+            }
+        }
+    }
+}
+
+/// Warns when `path` is a symlink that installing will write through, e.g. an install root
+/// kept on another volume.
+pub fn warn_if_symlink(path: &Path, what: &str) {
+    if let Ok(target) = std::fs::read_link(path) {
+        tracing::warn!(
+            path = %path.display(),
+            target = %target.display(),
+            "{what} is a symlink; files are written to the directory it points to"
+        );
+    }
+}
+
+/// Sets the modification time of the file at `path`, so that copies keep the timestamps of the
+/// release and do not look newer to make-style build tools. Failures are only logged.
+fn set_mtime(path: &Path, mtime: SystemTime) {
+    let mtime = filetime::FileTime::from_system_time(mtime);
+    if let Err(e) = filetime::set_file_mtime(path, mtime) {
+        tracing::debug!(error = %e, path = %path.display(), "Failed to preserve modification time");
+    }
+}
+
+/// Removes the `com.apple.quarantine` extended attribute from everything under `path`.
+///
+/// Gatekeeper refuses to run downloaded binaries carrying this attribute ("cannot be opened
+/// because the developer cannot be verified"). This is the equivalent of
+/// `xattr -dr com.apple.quarantine <path>` and only warns if the attribute cannot be removed.
+#[cfg(target_os = "macos")]
+pub fn strip_quarantine(path: &Path) {
+    const QUARANTINE_ATTR: &str = "com.apple.quarantine";
+
+    match std::process::Command::new("xattr")
+        .args(["-d", "-r", QUARANTINE_ATTR])
+        .arg(path)
+        .output()
+    {
+        Ok(out) if out.status.success() => {
+            tracing::debug!(path = %path.display(), "Removed quarantine attribute");
+        }
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            // Files that never had the attribute are reported as errors too; only a refusal
+            // to modify the attributes is worth surfacing.
+            if stderr.contains("not permitted") || stderr.contains("Permission denied") {
+                tracing::warn!(path = %path.display(), error = %stderr.trim(), "Failed to remove quarantine attribute");
+            } else {
+                tracing::debug!(path = %path.display(), output = %stderr.trim(), "xattr reported errors");
+            }
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, path = %path.display(), "Failed to run xattr to remove quarantine attribute");
+        }
+    }
+}
+
+/// Extracts the contents of a compressed archive (`.tar.gz`, `.tar.bz2` or plain `.tar` for Unix-like systems, `.zip` for Windows) to a specified directory.
 ///
 /// # Arguments
 ///
@@ -179,42 +701,308 @@ pub async fn copy_tree(from_dir: &Path, to_dir: &Path) -> Result<()> {
 /// Returns an error if the extraction fails. This could happen if the archive format is unsupported or
 /// if the destination path cannot be created.
 pub async fn extract_archive(file: &mut std::fs::File, dest: &Path) -> Result<()> {
+    // An archive of nothing but directories would otherwise install as an empty success.
+    if extract_archive_matching(file, dest, None).await? == 0 {
+        return Err(Error::NoInstallableFiles {
+            reason: "it is empty or only holds directories".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Like [`extract_archive`], but only writes the entries for which `filter` returns `true`, and
+/// returns how many entries other than directories it wrote, which may be none.
+///
+/// The filter is given each entry's path relative to the archive root, e.g.
+/// `WasmEdge-0.14.1-Linux/bin/wasmedge`, and `None` extracts everything. Entries whose paths
+/// would escape `dest` are skipped either way.
+pub async fn extract_archive_matching(
+    file: &mut std::fs::File,
+    dest: &Path,
+    filter: Option<&EntryFilter<'_>>,
+) -> Result<usize> {
     fs::create_dir_all(dest).await.inspect_err(
         |e| tracing::error!(error = %e.to_string(), "Failed to create directory during extraction"),
     )?;
     file.rewind()?;
 
-    #[cfg(unix)]
-    {
-        use flate2::read::GzDecoder;
-        let decompressed = GzDecoder::new(file);
-        extract_tar(decompressed, dest)?;
+    let mut header = Vec::with_capacity(512);
+    std::io::Read::read_to_end(&mut std::io::Read::take(&mut *file, 512), &mut header)?;
+    file.rewind()?;
+    let format = ArchiveFormat::detect(&header);
+    tracing::debug!(?format, "Detected archive format");
+    if let Some(format) = format.filter(|f| !f.is_supported()) {
+        return Err(Error::UnsupportedArchiveFormat {
+            format: format.name().to_string(),
+            supported: ArchiveFormat::SUPPORTED.to_string(),
+        });
     }
 
+    #[cfg(unix)]
+    let files = match format {
+        Some(ArchiveFormat::Tar) => extract_tar(file, dest, filter)?,
+        Some(ArchiveFormat::Bzip2) => {
+            let decompressed = bzip2::read::MultiBzDecoder::new(file);
+            extract_tar(decompressed, dest, filter)?
+        }
+        _ => {
+            use flate2::read::GzDecoder;
+            let decompressed = GzDecoder::new(file);
+            extract_tar(decompressed, dest, filter)?
+        }
+    };
+
     #[cfg(windows)]
-    extract_zip(file, dest)?;
+    let files = extract_zip(file, dest, filter)?;
 
-    Ok(())
+    tracing::debug!(files, "Extracted files");
+    Ok(files)
 }
 
-#[cfg(unix)]
-fn extract_tar(file: impl std::io::Read, to: &Path) -> Result<()> {
-    use tar::Archive;
+/// Container or compression format of an archive, as told by its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Gzip,
+    Zip,
+    Xz,
+    Zstd,
+    Bzip2,
+    /// An uncompressed tar archive
+    Tar,
+}
 
-    let mut archive = Archive::new(file);
-    archive.unpack(to).context(ExtractSnafu {})?;
+impl ArchiveFormat {
+    /// The formats this build extracts, for error messages.
+    #[cfg(unix)]
+    pub const SUPPORTED: &str =
+        "gzip-compressed (.tar.gz), bzip2-compressed (.tar.bz2) and plain tar archives";
+    #[cfg(windows)]
+    pub const SUPPORTED: &str = "zip archives";
 
-    Ok(())
+    /// Recognizes the format from the start of an archive; at least 262 bytes are needed to
+    /// tell an uncompressed tar archive.
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        const MAGIC: [(&[u8], ArchiveFormat); 5] = [
+            (&[0x1f, 0x8b], ArchiveFormat::Gzip),
+            (b"PK\x03\x04", ArchiveFormat::Zip),
+            (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], ArchiveFormat::Xz),
+            (&[0x28, 0xb5, 0x2f, 0xfd], ArchiveFormat::Zstd),
+            (b"BZh", ArchiveFormat::Bzip2),
+        ];
+        MAGIC
+            .iter()
+            .find(|(magic, _)| header.starts_with(magic))
+            .map(|(_, format)| *format)
+            .or_else(|| (header.get(257..262) == Some(b"ustar")).then_some(Self::Tar))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zip => "zip",
+            Self::Xz => "xz",
+            Self::Zstd => "zstd",
+            Self::Bzip2 => "bzip2",
+            Self::Tar => "tar",
+        }
+    }
+
+    /// Whether this build of wasmedgeup can extract archives of this format.
+    pub fn is_supported(self) -> bool {
+        if cfg!(windows) {
+            self == Self::Zip
+        } else {
+            matches!(self, Self::Gzip | Self::Bzip2 | Self::Tar)
+        }
+    }
+}
+
+/// Predicate on archive entry paths for [`extract_archive_matching`].
+pub type EntryFilter<'a> = dyn Fn(&Path) -> bool + Send + Sync + 'a;
+
+/// The paths an extraction created in its destination, so that a failed one can remove them
+/// again instead of leaving a half-extracted tree behind.
+///
+/// Files that existed before and were overwritten cannot be restored and are left as they are.
+#[derive(Debug)]
+struct ExtractedPaths<'a> {
+    dest: &'a Path,
+    created: Vec<PathBuf>,
+}
+
+impl<'a> ExtractedPaths<'a> {
+    fn new(dest: &'a Path) -> Self {
+        Self {
+            dest,
+            created: Vec::new(),
+        }
+    }
+
+    /// Records the outermost directory or file of the entry at `relative` that does not exist
+    /// yet; call before writing the entry.
+    fn record(&mut self, relative: &Path) {
+        let mut path = self.dest.to_path_buf();
+        for component in relative.components() {
+            match component {
+                std::path::Component::Normal(name) => path.push(name),
+                std::path::Component::CurDir => continue,
+                // Paths escaping `dest` are not extracted at all.
+                _ => return,
+            }
+            if std::fs::symlink_metadata(&path).is_err() {
+                self.created.push(path);
+                return;
+            }
+        }
+    }
+
+    /// Removes what was recorded, newest first.
+    fn roll_back(self) {
+        for path in self.created.iter().rev() {
+            let removed = match std::fs::symlink_metadata(path) {
+                Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path),
+                Ok(_) => std::fs::remove_file(path),
+                Err(_) => continue,
+            };
+            if let Err(e) = removed {
+                tracing::warn!(error = %e, path = %path.display(), "Failed to remove partially extracted entry");
+            }
+        }
+        tracing::debug!(
+            dest = %self.dest.display(),
+            removed = self.created.len(),
+            "Removed partially extracted entries"
+        );
+    }
+}
+
+/// Extracts with `unpack`, removing the entries it created when it fails.
+fn extract_transactionally(
+    to: &Path,
+    unpack: impl FnOnce(&mut ExtractedPaths) -> Result<usize>,
+) -> Result<usize> {
+    let mut extracted = ExtractedPaths::new(to);
+    let result = unpack(&mut extracted);
+    if result.is_err() {
+        extracted.roll_back();
+    }
+    result
+}
+
+#[cfg(unix)]
+fn extract_tar(
+    file: impl std::io::Read,
+    to: &Path,
+    filter: Option<&EntryFilter<'_>>,
+) -> Result<usize> {
+    use tar::{Archive, EntryType};
+
+    extract_transactionally(to, |extracted| {
+        let mut archive = Archive::new(file);
+        archive.set_preserve_mtime(true);
+        // As in `Archive::unpack`, directories are unpacked last, so that writing their
+        // contents does not change the mtimes set on them.
+        let mut directories = Vec::new();
+        let mut files = 0;
+        for entry in archive.entries().context(ExtractSnafu {})? {
+            let mut entry = entry.context(ExtractSnafu {})?;
+            let path = entry.path().context(ExtractSnafu {})?.into_owned();
+            if filter.is_some_and(|filter| !filter(&path)) {
+                continue;
+            }
+            extracted.record(&path);
+            if entry.header().entry_type() == EntryType::Directory {
+                directories.push(entry);
+                continue;
+            }
+            // `unpack_in` skips entries that would escape `to`.
+            files += usize::from(entry.unpack_in(to).context(ExtractSnafu {})?);
+        }
+        for mut directory in directories {
+            directory.unpack_in(to).context(ExtractSnafu {})?;
+        }
+        Ok(files)
+    })
 }
 
 #[cfg(windows)]
-fn extract_zip(file: &mut std::fs::File, to: &Path) -> Result<()> {
+fn extract_zip(
+    file: &mut std::fs::File,
+    to: &Path,
+    filter: Option<&EntryFilter<'_>>,
+) -> Result<usize> {
     use zip::ZipArchive;
 
     let mut archive = ZipArchive::new(file).context(ExtractSnafu {})?;
-    archive.extract(to).context(ExtractSnafu {})?;
+    extract_transactionally(to, |extracted| {
+        let mut files = 0;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).context(ExtractSnafu {})?;
+            let Some(relative) = entry.enclosed_name() else {
+                continue;
+            };
+            if filter.is_some_and(|filter| !filter(&relative)) {
+                continue;
+            }
+            extracted.record(&relative);
 
-    Ok(())
+            let out = to.join(relative);
+            let written = if entry.is_dir() {
+                std::fs::create_dir_all(&out)
+            } else {
+                out.parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::File::create(&out))
+                    .and_then(|mut dest| std::io::copy(&mut entry, &mut dest).map(|_| ()))
+            };
+            written
+                .map_err(zip::result::ZipError::Io)
+                .context(ExtractSnafu {})?;
+            // `ZipArchive::extract` would only restore timestamps with zip's `chrono` feature.
+            if let (false, Some(mtime)) = (entry.is_dir(), zip_mtime(&entry)) {
+                set_mtime(&out, mtime);
+            }
+            files += usize::from(!entry.is_dir());
+        }
+        Ok(files)
+    })
+}
+
+/// Modification time of a zip entry. Zip stores a local date and time without a zone; it is
+/// taken as UTC, as zip's own `extract` does.
+#[cfg(windows)]
+fn zip_mtime<R: std::io::Read>(entry: &zip::read::ZipFile<'_, R>) -> Option<SystemTime> {
+    let time = entry.last_modified().filter(|t| t.is_valid())?;
+    // Days since 1970-01-01 of the civil date, after Howard Hinnant's `days_from_civil`.
+    let (month, day) = (i64::from(time.month()), i64::from(time.day()));
+    let year = i64::from(time.year()) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let secs = days * 86_400
+        + i64::from(time.hour()) * 3_600
+        + i64::from(time.minute()) * 60
+        + i64::from(time.second());
+    let secs = u64::try_from(secs).ok()?;
+    Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+}
+
+/// Returns the version the `bin` symlink in `base_dir` points to, if any.
+pub async fn active_version(base_dir: &Path) -> Option<String> {
+    let link_target = fs::read_link(base_dir.join("bin")).await.ok()?;
+    let resolved = if link_target.is_absolute() {
+        link_target
+    } else {
+        base_dir.join(link_target)
+    };
+    resolved
+        .strip_prefix(base_dir.join("versions"))
+        .ok()
+        .and_then(|p| p.components().next())
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
 }
 
 /// Creates or updates symlinks for a WasmEdge version installation.
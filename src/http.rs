@@ -1,6 +1,123 @@
-use crate::prelude::*;
-use reqwest::Client;
-use std::time::Duration;
+use crate::{environment::Environment, prelude::*};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CACHE_CONTROL, PRAGMA},
+    Certificate, Client, ClientBuilder, NoProxy, Proxy,
+};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// User-Agent sent with every request unless overridden with `--user-agent`.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "wasmedgeup/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/WasmEdge/wasmedgeup)"
+);
+
+/// Redirects followed per request, enough for GitHub's hop to its CDN plus a mirror's own.
+pub const MAX_REDIRECTS: usize = 10;
+
+/// Follows up to [`MAX_REDIRECTS`] redirects, refusing to downgrade from HTTPS to HTTP.
+///
+/// reqwest drops `Authorization`, `Cookie` and `Proxy-Authorization` from every hop to a
+/// different host, port or scheme, so credentials stay with the origin they were meant for.
+fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        let previous = attempt.previous();
+        if previous.len() >= MAX_REDIRECTS {
+            return attempt.error(format!("stopped after {MAX_REDIRECTS} redirects"));
+        }
+        let from = previous.last();
+        if from.is_some_and(|from| from.scheme() == "https") && attempt.url().scheme() != "https" {
+            let to = attempt.url().to_string();
+            return attempt.error(format!("refusing redirect from HTTPS to {to}"));
+        }
+        tracing::trace!(
+            from = from.map(|from| from.as_str()),
+            to = %attempt.url(),
+            status = %attempt.status(),
+            "Following redirect"
+        );
+        attempt.follow()
+    })
+}
+
+/// A credential such as a token or a proxy URL with a password, kept out of `Debug` output
+/// and therefore out of logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+/// Proxies to use instead of the ones reqwest reads from the process environment, e.g. those
+/// an env file sets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy for `http://` URLs
+    pub http: Option<Secret>,
+    /// Proxy for `https://` URLs
+    pub https: Option<Secret>,
+    /// Proxy for URLs of any scheme without a more specific proxy
+    pub all: Option<Secret>,
+    /// Comma-separated hosts and domains to connect to directly
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyConfig {
+    /// The proxies `env` sets with `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`,
+    /// the uppercase names taking precedence over the lowercase ones.
+    pub fn from_environment(env: &Environment) -> Self {
+        let var = |name: &str| {
+            env.var(name)
+                .or_else(|| env.var(&name.to_ascii_lowercase()))
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            http: var("HTTP_PROXY").map(Secret::new),
+            https: var("HTTPS_PROXY").map(Secret::new),
+            all: var("ALL_PROXY").map(Secret::new),
+            no_proxy: var("NO_PROXY"),
+        }
+    }
+
+    fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        let no_proxy = self.no_proxy.as_deref().and_then(NoProxy::from_string);
+        // Without any proxy, direct connections are used rather than the process's proxies.
+        builder = builder.no_proxy();
+        for (url, scheme) in [
+            (&self.http, "http"),
+            (&self.https, "https"),
+            (&self.all, "all"),
+        ] {
+            let Some(url) = url else { continue };
+            let proxy = match scheme {
+                "http" => Proxy::http(url.expose()),
+                "https" => Proxy::https(url.expose()),
+                _ => Proxy::all(url.expose()),
+            };
+            let proxy = proxy.map_err(|e| Error::HttpClientBuild {
+                reason: format!("invalid {scheme} proxy: {e}"),
+            })?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+        }
+        Ok(builder)
+    }
+}
 
 /// Configuration for building HTTP clients with consistent settings.
 #[derive(Debug, Clone)]
@@ -9,6 +126,16 @@ pub struct HttpClientConfig {
     pub connect_timeout: u64,
     /// Request timeout in seconds
     pub request_timeout: u64,
+    /// User-Agent header value; [`DEFAULT_USER_AGENT`] when unset
+    pub user_agent: Option<String>,
+    /// PEM file with root certificates to trust in addition to the system ones
+    pub ca_cert: Option<PathBuf>,
+    /// Accept invalid TLS certificates, including self-signed and expired ones
+    pub danger_accept_invalid_certs: bool,
+    /// Ask servers and proxies for fresh responses instead of cached ones
+    pub no_cache: bool,
+    /// Proxies to use; unset, reqwest reads them from the process environment
+    pub proxy: Option<ProxyConfig>,
 }
 
 impl Default for HttpClientConfig {
@@ -16,6 +143,11 @@ impl Default for HttpClientConfig {
         Self {
             connect_timeout: 15, // 15 seconds for connection
             request_timeout: 90, // 90 seconds for request
+            user_agent: None,
+            ca_cert: None,
+            danger_accept_invalid_certs: false,
+            no_cache: false,
+            proxy: None,
         }
     }
 }
@@ -38,18 +170,76 @@ impl HttpClientConfig {
         self
     }
 
+    /// Sets the User-Agent header value.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Trusts the root certificates in the PEM file at `path`, e.g. a TLS-inspecting proxy's CA.
+    pub fn with_ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_cert = Some(path.into());
+        self
+    }
+
+    /// Disables TLS certificate verification; only for trusted internal mirrors.
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// Sends `Cache-Control: no-cache` (and `Pragma: no-cache` for HTTP/1.0 caches) with every
+    /// request, so that caching proxies and CDNs revalidate instead of answering from cache.
+    pub fn with_no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
+    /// Uses `proxy` instead of the proxies of the process environment.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
     /// Builds a reqwest Client with the configured settings.
     pub fn build(&self) -> Result<Client> {
-        reqwest::ClientBuilder::new()
+        let mut builder = reqwest::ClientBuilder::new()
             .connect_timeout(Duration::from_secs(self.connect_timeout))
             .timeout(Duration::from_secs(self.request_timeout))
-            .user_agent(format!(
-                "wasmedgeup/{} (+https://github.com/WasmEdge/wasmedgeup)",
-                env!("CARGO_PKG_VERSION")
-            ))
-            .build()
-            .map_err(|e| Error::HttpClientBuild {
-                reason: e.to_string(),
-            })
+            .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .danger_accept_invalid_certs(self.danger_accept_invalid_certs)
+            .redirect(redirect_policy());
+        if self.no_cache {
+            let no_cache = HeaderValue::from_static("no-cache");
+            builder = builder.default_headers(HeaderMap::from_iter([
+                (CACHE_CONTROL, no_cache.clone()),
+                (PRAGMA, no_cache),
+            ]));
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = proxy.apply(builder)?;
+        }
+        if let Some(path) = &self.ca_cert {
+            for cert in load_ca_certs(path)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        builder.build().map_err(|e| Error::HttpClientBuild {
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Reads every certificate from the PEM file at `path`, failing if there is none.
+fn load_ca_certs(path: &Path) -> Result<Vec<Certificate>> {
+    let invalid = |reason: String| Error::InvalidCaCert {
+        path: path.display().to_string(),
+        reason,
+    };
+    let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+    let certs = Certificate::from_pem_bundle(&pem).map_err(|e| invalid(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid("no PEM certificates found".to_string()));
     }
+    Ok(certs)
 }
@@ -1,10 +1,15 @@
 pub mod api;
+pub mod cache;
 pub mod cli;
 pub mod commands;
+pub mod environment;
 pub mod error;
+pub(crate) mod events;
 pub mod fs;
 pub mod http;
+pub mod manifest;
 pub mod prelude;
 pub mod shell_utils; // This should now point to the directory
 pub mod system;
 pub mod target;
+pub mod units;
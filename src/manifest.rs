@@ -0,0 +1,374 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use walkdir::WalkDir;
+
+use crate::{
+    api::{sha256_hex_file, Asset, Linkage},
+    prelude::*,
+    target::{TargetArch, TargetOS},
+};
+
+pub const MANIFEST_FILE_NAME: &str = ".wasmedgeup-manifest.json";
+
+/// Record of an installed version, written to `versions/<version>/.wasmedgeup-manifest.json`.
+///
+/// Besides the archive the version was installed from, it holds the SHA-256 of every regular
+/// file copied into the version directory, keyed by its `/`-separated relative path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub version: String,
+    pub os: TargetOS,
+    pub arch: TargetArch,
+    pub archive: String,
+    pub archive_checksum: String,
+    /// Missing from manifests written before static builds could be selected
+    #[serde(default)]
+    pub linkage: Linkage,
+    /// Seconds since the Unix epoch
+    pub installed_at: u64,
+    pub files: BTreeMap<String, String>,
+    /// Symlinks copied with the files, e.g. `lib/libwasmedge.so`; they carry no checksum
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub links: BTreeSet<String>,
+    /// Set when the x86_64 build was installed on Apple Silicon by `install --allow-rosetta`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rosetta: bool,
+}
+
+/// SBOM-like summary of an install, for compliance tooling to ingest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallSbom {
+    /// Name and version of the tool that performed the install
+    pub tool: String,
+    pub name: String,
+    pub version: String,
+    pub os: TargetOS,
+    pub arch: TargetArch,
+    pub linkage: Linkage,
+    pub asset: String,
+    pub asset_url: String,
+    pub checksum: SbomChecksum,
+    pub signature: SignatureStatus,
+    /// Seconds since the Unix epoch
+    pub installed_at: u64,
+}
+
+/// What an install would fetch, pinned by `install --manifest-only` so that
+/// `install --from-manifest` can fetch exactly the same archive on another machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallLock {
+    pub version: String,
+    pub os: TargetOS,
+    pub arch: TargetArch,
+    pub linkage: Linkage,
+    pub asset: String,
+    /// Where the archive was resolved to; when missing (e.g. the lock came from an install
+    /// manifest) it is fetched from the configured release source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub checksum: SbomChecksum,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SbomChecksum {
+    pub algorithm: String,
+    pub value: String,
+}
+
+/// Whether the installed archive's signature was checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    NotChecked,
+    /// The checksum the archive was verified against came from a checksum file whose detached
+    /// signature verified, see `install --verify-signature`
+    ChecksumFileVerified,
+}
+
+/// A file whose on-disk state no longer matches the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileProblem {
+    Missing { path: String },
+    Modified { path: String },
+}
+
+impl std::fmt::Display for FileProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { path } => write!(f, "missing: {path}"),
+            Self::Modified { path } => write!(f, "modified: {path}"),
+        }
+    }
+}
+
+impl InstallManifest {
+    pub fn path(version_dir: &Path) -> PathBuf {
+        version_dir.join(MANIFEST_FILE_NAME)
+    }
+
+    /// Directory holding the manifest of an install made with `--prefix`, whose files are kept
+    /// directly under `prefix` rather than in a version directory.
+    pub fn prefix_dir(prefix: &Path) -> PathBuf {
+        prefix.join("share").join("wasmedgeup")
+    }
+
+    /// Hashes the files currently in `version_dir` into a new manifest.
+    ///
+    /// Symlinks are listed in `links`; the files they point to are recorded under their own names.
+    pub fn record(
+        version_dir: &Path,
+        asset: &Asset,
+        os: TargetOS,
+        arch: TargetArch,
+        archive_checksum: &str,
+    ) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        let mut links = BTreeSet::new();
+        for entry in WalkDir::new(version_dir).min_depth(1) {
+            let entry = entry.map_err(|e| Error::Io {
+                action: "walk version directory".to_string(),
+                path: version_dir.display().to_string(),
+                source: e.into(),
+            })?;
+            if entry.file_type().is_symlink() {
+                links.insert(relative_name(version_dir, entry.path()));
+                continue;
+            }
+            if !entry.file_type().is_file() || entry.file_name() == MANIFEST_FILE_NAME {
+                continue;
+            }
+
+            let checksum = hash_file(entry.path())?;
+            files.insert(relative_name(version_dir, entry.path()), checksum);
+        }
+
+        let installed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Ok(Self {
+            version: asset.version.to_string(),
+            os,
+            arch,
+            archive: asset.archive_name.clone(),
+            archive_checksum: archive_checksum.to_string(),
+            linkage: asset.linkage,
+            installed_at,
+            files,
+            links,
+            rosetta: false,
+        })
+    }
+
+    pub fn write(&self, version_dir: &Path) -> Result<()> {
+        let path = Self::path(version_dir);
+        let contents = serde_json::to_vec_pretty(self).context(ManifestSnafu {
+            path: path.display().to_string(),
+        })?;
+        std::fs::write(&path, contents).context(IoSnafu {
+            action: "write install manifest".to_string(),
+            path: path.display().to_string(),
+        })
+    }
+
+    /// Reads the manifest of the version installed in `version_dir`.
+    ///
+    /// Versions installed before manifests were introduced have none and yield
+    /// [`Error::ManifestNotFound`].
+    pub fn load(version_dir: &Path, version: &str) -> Result<Self> {
+        let path = Self::path(version_dir);
+        let contents = match std::fs::read(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(Error::ManifestNotFound {
+                    version: version.to_string(),
+                });
+            }
+            Err(source) => {
+                return Err(Error::Io {
+                    action: "read install manifest".to_string(),
+                    path: path.display().to_string(),
+                    source,
+                });
+            }
+        };
+        serde_json::from_slice(&contents).context(ManifestSnafu {
+            path: path.display().to_string(),
+        })
+    }
+
+    /// Pins the archive this version was installed from, for `install --from-manifest`.
+    pub fn to_lock(&self) -> InstallLock {
+        InstallLock {
+            version: self.version.clone(),
+            os: self.os,
+            arch: self.arch,
+            linkage: self.linkage,
+            asset: self.archive.clone(),
+            url: None,
+            checksum: SbomChecksum {
+                algorithm: "sha256".to_string(),
+                value: self.archive_checksum.clone(),
+            },
+        }
+    }
+
+    /// Summarizes the install for `--sbom`; `asset_url` is where the archive was fetched from.
+    pub fn sbom(&self, asset_url: &str, signature: SignatureStatus) -> InstallSbom {
+        InstallSbom {
+            tool: format!("wasmedgeup {}", env!("CARGO_PKG_VERSION")),
+            name: "WasmEdge".to_string(),
+            version: self.version.clone(),
+            os: self.os,
+            arch: self.arch,
+            linkage: self.linkage,
+            asset: self.archive.clone(),
+            asset_url: asset_url.to_string(),
+            checksum: SbomChecksum {
+                algorithm: "sha256".to_string(),
+                value: self.archive_checksum.clone(),
+            },
+            signature,
+            installed_at: self.installed_at,
+        }
+    }
+
+    /// Re-hashes every recorded file and returns the ones that are missing or changed.
+    ///
+    /// Files added after installation (e.g. plugins) are not reported.
+    pub fn verify(&self, version_dir: &Path) -> Result<Vec<FileProblem>> {
+        let mut problems = Vec::new();
+        for (name, expected) in &self.files {
+            let path = version_dir.join(name);
+            if !path.is_file() {
+                problems.push(FileProblem::Missing { path: name.clone() });
+                continue;
+            }
+            if hash_file(&path)? != *expected {
+                problems.push(FileProblem::Modified { path: name.clone() });
+            }
+        }
+        Ok(problems)
+    }
+
+    /// Removes the recorded files and links from `root`, then the nested directories left empty
+    /// by it (e.g. `include/wasmedge`). Top-level directories such as `bin` and `lib` are kept
+    /// even when empty, since a shared prefix may have had them before.
+    ///
+    /// Returns how many entries were removed; ones that are already gone are skipped.
+    pub fn remove_files(&self, root: &Path) -> Result<usize> {
+        let mut removed = 0;
+        let mut parents = BTreeSet::new();
+        for name in self.files.keys().chain(&self.links) {
+            let path = root.join(name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => {
+                    return Err(Error::Io {
+                        action: "remove installed file".to_string(),
+                        path: path.display().to_string(),
+                        source,
+                    });
+                }
+            }
+            parents.extend(Path::new(name).ancestors().skip(1).map(Path::to_path_buf));
+        }
+
+        // Deepest first, so a directory is only tried once its subdirectories are gone.
+        for dir in parents.iter().rev().filter(|d| d.components().count() > 1) {
+            if std::fs::remove_dir(root.join(dir)).is_ok() {
+                tracing::debug!(dir = %dir.display(), "Removed empty directory");
+            }
+        }
+        Ok(removed)
+    }
+}
+
+impl InstallLock {
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_vec_pretty(self).context(ManifestSnafu {
+            path: path.display().to_string(),
+        })?;
+        std::fs::write(path, contents).context(IoSnafu {
+            action: "write install lockfile".to_string(),
+            path: path.display().to_string(),
+        })
+    }
+
+    /// Reads a lockfile, rejecting checksum algorithms other than SHA-256 and versions that are
+    /// not exact (which would have to be resolved again).
+    ///
+    /// The install manifest of an installed version (`.wasmedgeup-manifest.json`) is accepted
+    /// as well, to reproduce that install elsewhere.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read(path).context(IoSnafu {
+            action: "read install lockfile".to_string(),
+            path: path.display().to_string(),
+        })?;
+        let lock = match serde_json::from_slice::<Self>(&contents) {
+            Ok(lock) => lock,
+            Err(source) => match serde_json::from_slice::<InstallManifest>(&contents) {
+                Ok(manifest) => manifest.to_lock(),
+                Err(_) => {
+                    return Err(Error::Manifest {
+                        path: path.display().to_string(),
+                        source,
+                    })
+                }
+            },
+        };
+        if semver::Version::parse(&lock.version).is_err() {
+            return Err(Error::InvalidLockfile {
+                path: path.display().to_string(),
+                reason: format!("`{}` is not an exact version", lock.version),
+            });
+        }
+        if !lock.checksum.algorithm.eq_ignore_ascii_case("sha256") {
+            return Err(Error::InvalidLockfile {
+                path: path.display().to_string(),
+                reason: format!(
+                    "unsupported checksum algorithm `{}`",
+                    lock.checksum.algorithm
+                ),
+            });
+        }
+        Ok(lock)
+    }
+}
+
+impl InstallSbom {
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_vec_pretty(self).context(ManifestSnafu {
+            path: path.display().to_string(),
+        })?;
+        std::fs::write(path, contents).context(IoSnafu {
+            action: "write SBOM".to_string(),
+            path: path.display().to_string(),
+        })
+    }
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path).context(IoSnafu {
+        action: "open file for hashing".to_string(),
+        path: path.display().to_string(),
+    })?;
+    sha256_hex_file(&mut file)
+}
+
+fn relative_name(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,7 +1,9 @@
 # wasmedgeup shell setup for Fish
 # The {WASMEDGE_BIN_DIR} placeholder is expected to be replaced by the actual WasmEdge bin path.
 
-if not contains "{WASMEDGE_BIN_DIR}" $PATH
+# With `--prepend-path` only the first entry counts, so another wasmedge earlier on PATH
+# cannot shadow this one
+if not contains "{WASMEDGE_BIN_DIR}" $PATH{WASMEDGE_PATH_FIRST}
     # Prepending path
     set -gx PATH "{WASMEDGE_BIN_DIR}" $PATH
 end
@@ -2,9 +2,10 @@
 # wasmedgeup shell setup
 # The {WASMEDGE_BIN_DIR} placeholder is expected to be replaced by the actual WasmEdge bin path.
 
-# affix colons on either side of $PATH to simplify matching
+# affix colons on either side of $PATH to simplify matching; with `--prepend-path` only a
+# match at the front counts, so another wasmedge earlier on PATH cannot shadow this one
 case ":${PATH}:" in
-    *:"{WASMEDGE_BIN_DIR}":*)
+    {WASMEDGE_PATH_ANYWHERE}:"{WASMEDGE_BIN_DIR}":*)
         ;;
     *)
         # Prepending path
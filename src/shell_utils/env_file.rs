@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use snafu::ResultExt;
+
+use crate::prelude::*;
+
+/// Syntax of the file written by `install --write-env-file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EnvFileShell {
+    /// POSIX shells: sh, bash, zsh, ...
+    Sh,
+    Fish,
+    /// csh and tcsh
+    Csh,
+    #[value(alias = "pwsh")]
+    Powershell,
+}
+
+impl Default for EnvFileShell {
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self::Powershell
+        } else {
+            Self::Sh
+        }
+    }
+}
+
+impl EnvFileShell {
+    /// Script that sets `WASMEDGE_VERSION` to `version` and puts `bin_dir` first on PATH.
+    pub fn script(self, version: &str, bin_dir: &Path) -> String {
+        let bin_dir = bin_dir.to_string_lossy();
+        let header = "# Written by `wasmedgeup install --write-env-file`";
+        match self {
+            Self::Sh => format!(
+                "{header}\nexport WASMEDGE_VERSION={version}\nexport PATH={bin}:\"$PATH\"\n",
+                version = quote_sh(version),
+                bin = quote_sh(&bin_dir),
+            ),
+            Self::Fish => format!(
+                "{header}\nset -gx WASMEDGE_VERSION {version}\nset -gx PATH {bin} $PATH\n",
+                version = quote_fish(version),
+                bin = quote_fish(&bin_dir),
+            ),
+            Self::Csh => format!(
+                "{header}\nsetenv WASMEDGE_VERSION {version}\nsetenv PATH {bin}:\"$PATH\"\n",
+                version = quote_sh(version),
+                bin = quote_sh(&bin_dir),
+            ),
+            Self::Powershell => format!(
+                "{header}\n$env:WASMEDGE_VERSION = {version}\n$env:PATH = {bin} + [IO.Path]::PathSeparator + $env:PATH\n",
+                version = quote_powershell(version),
+                bin = quote_powershell(&bin_dir),
+            ),
+        }
+    }
+}
+
+/// Writes the [`EnvFileShell::script`] for `version` and `bin_dir` to `path`.
+pub fn write_env_file(
+    path: &Path,
+    shell: EnvFileShell,
+    version: &str,
+    bin_dir: &Path,
+) -> Result<()> {
+    std::fs::write(path, shell.script(version, bin_dir)).context(IoSnafu {
+        action: "write env file".to_string(),
+        path: path.display().to_string(),
+    })
+}
+
+/// Single-quotes `value` for POSIX shells and csh, which take nothing inside `'...'` literally
+/// but cannot escape a `'` there.
+fn quote_sh(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'"'"'"#))
+}
+
+fn quote_fish(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn quote_powershell(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
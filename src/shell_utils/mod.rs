@@ -1,9 +1,37 @@
+use std::path::{Path, PathBuf};
+
+use crate::environment::Environment;
+
+mod env_file;
+pub use env_file::{write_env_file, EnvFileShell};
+
 #[cfg(unix)]
 mod unix;
 #[cfg(unix)]
-pub use unix::{get_available_shells, setup_path, uninstall_path};
+pub use unix::{get_available_shells, setup_path, setup_path_with, uninstall_path};
 
 #[cfg(windows)]
 mod windows;
 #[cfg(windows)]
-pub use windows::{setup_path, uninstall_path};
+pub use windows::{setup_path, setup_path_for, uninstall_path, uninstall_path_for, PathScope};
+
+/// The first `wasmedge` on the current PATH when it is not the one under `install_dir`, e.g.
+/// from a system package, which a new shell would still run instead of this install unless
+/// its `bin` comes first.
+///
+/// `None` when the first one found is this install's, or when there is none.
+pub fn shadowing_wasmedge(install_dir: &Path) -> Option<PathBuf> {
+    let path = Environment::current().var_os("PATH")?;
+    let binary = format!("wasmedge{}", std::env::consts::EXE_SUFFIX);
+    let found = std::env::split_paths(&path)
+        .map(|dir| dir.join(&binary))
+        .find(|candidate| candidate.is_file())?;
+
+    let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let install_dir = canonical(install_dir);
+    let ours = canonical(&found).starts_with(&install_dir)
+        || found
+            .parent()
+            .is_some_and(|dir| canonical(dir).starts_with(&install_dir));
+    (!ours).then_some(found)
+}
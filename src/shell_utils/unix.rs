@@ -1,6 +1,5 @@
-use crate::prelude::*;
+use crate::{environment::Environment, prelude::*};
 
-use dirs::home_dir;
 use snafu::OptionExt;
 use std::fs::{read_to_string, remove_file, OpenOptions};
 use std::io::Write;
@@ -11,20 +10,29 @@ use std::path::{Path, PathBuf};
 /// Returns the path constructed by joining $XDG_CONFIG_HOME (or ~/.config if unset)
 /// with the provided subpath components.
 fn xdg_config_path(subpath: &[&str]) -> Option<PathBuf> {
-    let base = std::env::var("XDG_CONFIG_HOME")
-        .ok()
-        .filter(|s| !s.is_empty())
-        .map(PathBuf::from)
-        .or_else(|| home_dir().map(|h| h.join(".config")))?;
-
-    let mut path = base;
+    let mut path = Environment::current().config_dir()?;
     for component in subpath {
         path.push(component);
     }
     Some(path)
 }
 
+/// The user's login shell, from `$SHELL`.
+fn login_shell() -> String {
+    Environment::current().var("SHELL").unwrap_or_default()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    Environment::current().home_dir()
+}
+
 pub fn setup_path(install_dir: &Path) -> Result<()> {
+    setup_path_with(install_dir, false)
+}
+
+/// Like [`setup_path`]; with `prepend`, the env scripts put the install's `bin` first on PATH
+/// even when it is on PATH already, behind another `wasmedge` that would shadow it.
+pub fn setup_path_with(install_dir: &Path, prepend: bool) -> Result<()> {
     use std::fs::read_to_string;
 
     let mut written = vec![];
@@ -34,7 +42,7 @@ pub fn setup_path(install_dir: &Path) -> Result<()> {
 
         // Write each script only once
         if !written.contains(&env_script) {
-            shell.write_script(&env_script, install_dir)?;
+            shell.write_script(&env_script, install_dir, prepend)?;
             written.push(env_script);
         }
         let source_line = shell.source_line(install_dir);
@@ -51,10 +59,11 @@ pub fn setup_path(install_dir: &Path) -> Result<()> {
                 path: rc.display().to_string(),
             })?;
             if !rc_dir.is_dir() {
-                std::fs::create_dir_all(rc_dir)?;
+                std::fs::create_dir_all(rc_dir)
+                    .map_err(|source| shell_config_error(rc_dir, source))?;
             }
 
-            append_file(&rc, line_to_write)?;
+            append_file(&rc, line_to_write).map_err(|source| shell_config_error(&rc, source))?;
         }
     }
 
@@ -117,6 +126,7 @@ pub fn get_supported_shells() -> Vec<Shell> {
         Box::new(Zsh),
         Box::new(Fish),
         Box::new(Nushell),
+        Box::new(Tcsh),
     ]
 }
 
@@ -150,7 +160,7 @@ pub trait UnixShell: Send + Sync {
         format!(r#". "{}/env""#, install_dir.to_string_lossy())
     }
 
-    fn write_script(&self, script: &ShellScript, install_dir: &Path) -> Result<()> {
+    fn write_script(&self, script: &ShellScript, install_dir: &Path, prepend: bool) -> Result<()> {
         let wasmedge_bin = format!("{}/bin", install_dir.to_string_lossy());
         let wasmedge_lib = format!("{}/{}", install_dir.to_string_lossy(), LIB_DIR);
         let wasmedge_plugin = format!("{}/plugin", install_dir.to_string_lossy());
@@ -159,7 +169,9 @@ pub trait UnixShell: Send + Sync {
             .template
             .replace("{WASMEDGE_BIN_DIR}", &wasmedge_bin)
             .replace("{WASMEDGE_LIB_DIR}", &wasmedge_lib)
-            .replace("{WASMEDGE_PLUGIN_DIR}", &wasmedge_plugin);
+            .replace("{WASMEDGE_PLUGIN_DIR}", &wasmedge_plugin)
+            .replace("{WASMEDGE_PATH_ANYWHERE}", if prepend { "" } else { "*" })
+            .replace("{WASMEDGE_PATH_FIRST}", if prepend { "[1]" } else { "" });
 
         let mut file = std::fs::OpenOptions::new()
             .write(true)
@@ -224,8 +236,8 @@ pub struct Zsh;
 
 impl Zsh {
     fn zdotdir() -> Result<PathBuf> {
-        match std::env::var("ZDOTDIR") {
-            Ok(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        match Environment::current().var_os("ZDOTDIR") {
+            Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
             _ => Err(Error::Unknown),
         }
     }
@@ -233,13 +245,11 @@ impl Zsh {
 
 impl UnixShell for Zsh {
     fn is_present(&self) -> bool {
-        matches!(std::env::var("SHELL"), Ok(sh) if sh.ends_with("/zsh"))
-            || is_command_in_path("zsh")
+        login_shell().ends_with("/zsh") || is_command_in_path("zsh")
     }
 
     fn potential_rc_paths(&self) -> Vec<PathBuf> {
-        let home_env = std::env::var("HOME").ok().map(PathBuf::from);
-        [Zsh::zdotdir().ok(), home_env]
+        [Zsh::zdotdir().ok(), home_dir()]
             .iter()
             .filter_map(|dir| dir.as_ref().map(|p| p.join(".zshenv")))
             .collect()
@@ -269,8 +279,7 @@ impl UnixShell for Zsh {
 pub struct Fish;
 impl UnixShell for Fish {
     fn is_present(&self) -> bool {
-        matches!(std::env::var("SHELL"), Ok(sh) if sh.ends_with("/fish"))
-            || is_command_in_path("fish")
+        login_shell().ends_with("/fish") || is_command_in_path("fish")
     }
 
     // > "$XDG_CONFIG_HOME/fish/conf.d" (or "~/.config/fish/conf.d" if that variable is unset) for the user
@@ -307,7 +316,7 @@ impl UnixShell for Fish {
 pub struct Nushell;
 impl UnixShell for Nushell {
     fn is_present(&self) -> bool {
-        matches!(std::env::var("SHELL"), Ok(sh) if sh.ends_with("/nu")) || is_command_in_path("nu")
+        login_shell().ends_with("/nu") || is_command_in_path("nu")
     }
 
     fn potential_rc_paths(&self) -> Vec<PathBuf> {
@@ -337,8 +346,56 @@ impl UnixShell for Nushell {
     }
 }
 
+// csh/tcsh Implementation
+#[derive(Debug, Default)]
+pub struct Tcsh;
+impl UnixShell for Tcsh {
+    // Unlike the other shells, not detected from PATH: macOS ships both binaries, which says
+    // nothing about whether anyone uses them.
+    fn is_present(&self) -> bool {
+        let shell = login_shell();
+        shell.ends_with("/tcsh")
+            || shell.ends_with("/csh")
+            || self.potential_rc_paths().iter().any(|rc| rc.is_file())
+    }
+
+    fn potential_rc_paths(&self) -> Vec<PathBuf> {
+        [".tcshrc", ".cshrc"]
+            .iter()
+            .filter_map(|name| home_dir().map(|dir| dir.join(name)))
+            .collect()
+    }
+
+    fn effective_rc_files(&self) -> Vec<PathBuf> {
+        let candidates = self.potential_rc_paths();
+        let existing: Vec<PathBuf> = candidates
+            .iter()
+            .filter(|rc| rc.is_file())
+            .cloned()
+            .collect();
+
+        if !existing.is_empty() {
+            return existing;
+        }
+
+        // tcsh reads ~/.cshrc when there is no ~/.tcshrc, and csh only ever reads ~/.cshrc.
+        candidates.into_iter().skip(1).take(1).collect()
+    }
+
+    fn env_script(&self) -> ShellScript {
+        ShellScript {
+            template: include_str!("env.csh"),
+            name: "env.csh",
+        }
+    }
+
+    fn source_line(&self, install_dir: &Path) -> String {
+        format!(r#"source "{}/env.csh""#, install_dir.to_string_lossy())
+    }
+}
+
 fn is_command_in_path(command_name: &str) -> bool {
-    let Ok(path) = std::env::var("PATH") else {
+    let Some(path) = Environment::current().var_os("PATH") else {
         return false;
     };
 
@@ -350,7 +407,21 @@ fn is_command_in_path(command_name: &str) -> bool {
         .any(|p| p.is_file())
 }
 
-fn append_file(path: &Path, line: &str) -> Result<()> {
+/// Error for an rc file (or its directory) that cannot be written, e.g. because it is read-only
+/// or on a read-only filesystem. A hint on how to get past it is logged alongside.
+fn shell_config_error(path: &Path, source: std::io::Error) -> Error {
+    tracing::warn!(
+        path = %path.display(),
+        "Could not update the shell config; make it writable, or pass --setup-path=false to skip PATH setup"
+    );
+    Error::Io {
+        action: "update shell config".to_string(),
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+fn append_file(path: &Path, line: &str) -> std::io::Result<()> {
     let mut file = std::fs::OpenOptions::new()
         .append(true)
         .create(true)
@@ -1,30 +1,57 @@
 use crate::error::{Result, WindowsRegistrySnafu};
+use clap::ValueEnum;
 use snafu::ResultExt;
+use std::io::ErrorKind;
 use std::path::Path;
 use winreg::enums::*;
-use winreg::RegKey;
+use winreg::types::{FromRegValue, ToRegValue};
+use winreg::{RegKey, RegValue};
+
+const USER_ENVIRONMENT: &str = "Environment";
+const MACHINE_ENVIRONMENT: &str = r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment";
+
+/// Which `Path` environment variable the install directory is added to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum PathScope {
+    /// The current user's PATH (HKCU)
+    #[default]
+    User,
+    /// The system PATH shared by all users (HKLM); requires administrator privileges
+    Machine,
+}
 
 pub fn setup_path(install_dir: &Path) -> Result<()> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let env = hkcu
-        .open_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
-        .context(WindowsRegistrySnafu)?;
-
-    let current_path = match env.get_value("Path") {
-        Ok(path) => path,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
-        Err(e) => return Err(e).context(WindowsRegistrySnafu),
-    };
+    setup_path_for(install_dir, PathScope::User, false)
+}
+
+/// Adds the install's `bin` to the `scope` PATH: at the end, or with `prepend` at the front,
+/// moving it there when it is further back, so that no other `wasmedge` shadows it.
+pub fn setup_path_for(install_dir: &Path, scope: PathScope, prepend: bool) -> Result<()> {
+    let env = open_environment(scope)?;
+    let (current_path, vtype) = read_path(&env)?;
     let bin_path = format!("{}\\{}", install_dir.display(), "bin");
 
     // Normalize paths for comparison and to avoid duplicates with different casing
     // And since we cannot assume that the paths are ASCII strings, we can only use to_lowercase etc.
     let norm_bin_path = bin_path.to_lowercase();
-    let already_exists = current_path
-        .split(';')
-        .any(|p| p.trim().to_lowercase() == norm_bin_path);
+    let is_bin_path = |p: &str| p.trim().to_lowercase() == norm_bin_path;
+
+    if prepend {
+        if current_path.split(';').next().is_some_and(is_bin_path) {
+            return Ok(());
+        }
+        let new_path = std::iter::once(bin_path.as_str())
+            .chain(
+                current_path
+                    .split(';')
+                    .filter(|&p| !p.is_empty() && !is_bin_path(p)),
+            )
+            .collect::<Vec<_>>()
+            .join(";");
+        return write_path(&env, &new_path, vtype);
+    }
 
-    if already_exists {
+    if current_path.split(';').any(is_bin_path) {
         return Ok(());
     }
 
@@ -34,23 +61,16 @@ pub fn setup_path(install_dir: &Path) -> Result<()> {
         format!("{};{}", current_path, bin_path)
     };
 
-    env.set_value("Path", &new_path)
-        .context(WindowsRegistrySnafu)?;
-
-    Ok(())
+    write_path(&env, &new_path, vtype)
 }
 
 pub fn uninstall_path(install_dir: &Path) -> Result<()> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let env = hkcu
-        .open_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
-        .context(WindowsRegistrySnafu)?;
-
-    let current_path: String = match env.get_value("Path") {
-        Ok(path) => path,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
-        Err(e) => return Err(e).context(WindowsRegistrySnafu),
-    };
+    uninstall_path_for(install_dir, PathScope::User)
+}
+
+pub fn uninstall_path_for(install_dir: &Path, scope: PathScope) -> Result<()> {
+    let env = open_environment(scope)?;
+    let (current_path, vtype) = read_path(&env)?;
 
     if current_path.is_empty() {
         return Ok(());
@@ -74,8 +94,46 @@ pub fn uninstall_path(install_dir: &Path) -> Result<()> {
         .collect::<Vec<_>>()
         .join(";");
 
-    env.set_value("Path", &new_path)
-        .context(WindowsRegistrySnafu)?;
+    write_path(&env, &new_path, vtype)
+}
+
+fn open_environment(scope: PathScope) -> Result<RegKey> {
+    let (root, subkey) = match scope {
+        PathScope::User => (HKEY_CURRENT_USER, USER_ENVIRONMENT),
+        PathScope::Machine => (HKEY_LOCAL_MACHINE, MACHINE_ENVIRONMENT),
+    };
+
+    RegKey::predef(root)
+        .open_subkey_with_flags(subkey, KEY_READ | KEY_WRITE)
+        .map_err(|e| match (scope, e.kind()) {
+            (PathScope::Machine, ErrorKind::PermissionDenied) => std::io::Error::new(
+                ErrorKind::PermissionDenied,
+                "administrator privileges required to change the system PATH; \
+                 run from an elevated prompt or use `--scope user`",
+            ),
+            _ => e,
+        })
+        .context(WindowsRegistrySnafu)
+}
 
-    Ok(())
+/// Reads `Path` together with its value type. It is usually `REG_EXPAND_SZ`, and writing it
+/// back as `REG_SZ` would stop entries such as `%SystemRoot%\system32` from expanding.
+fn read_path(env: &RegKey) -> Result<(String, RegType)> {
+    match env.get_raw_value("Path") {
+        Ok(raw) => {
+            let path = String::from_reg_value(&raw).context(WindowsRegistrySnafu)?;
+            Ok((path, raw.vtype))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok((String::new(), REG_EXPAND_SZ)),
+        Err(e) => Err(e).context(WindowsRegistrySnafu),
+    }
+}
+
+fn write_path(env: &RegKey, path: &str, vtype: RegType) -> Result<()> {
+    let value = RegValue {
+        vtype,
+        ..path.to_reg_value()
+    };
+    env.set_raw_value("Path", &value)
+        .context(WindowsRegistrySnafu)
 }
@@ -0,0 +1,98 @@
+use crate::environment::Environment;
+use crate::target::TargetArch;
+
+/// Variables QEMU user-mode emulation reads. They are only set when a process is started
+/// through `qemu-<arch>` by hand or configured for a binfmt handler.
+const QEMU_VARS: &[&str] = &[
+    "QEMU_LD_PREFIX",
+    "QEMU_CPU",
+    "QEMU_GUEST_BASE",
+    "QEMU_STRACE",
+];
+
+/// A sign that wasmedgeup runs under user-mode emulation or for a build of another
+/// architecture, where the architecture it was built for says little about what to install.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emulation {
+    /// What gave it away, e.g. `QEMU_LD_PREFIX is set`
+    pub marker: String,
+    /// The architecture the environment seems to target, when the marker names one
+    pub target: Option<TargetArch>,
+}
+
+/// Looks for signs of emulation in the [current environment](Environment::current) and, on
+/// Linux, in `/proc/cpuinfo`.
+///
+/// Registered binfmt handlers (`/proc/sys/fs/binfmt_misc/qemu-*`) are not a sign by
+/// themselves: they only show that the host can run foreign binaries, not that this one is.
+pub fn detect_emulation() -> Option<Emulation> {
+    let env = Environment::current();
+    let cpuinfo = if cfg!(target_os = "linux") {
+        std::fs::read_to_string("/proc/cpuinfo").ok()
+    } else {
+        None
+    };
+    emulation_from(|name| env.var(name), cpuinfo.as_deref())
+}
+
+/// [`detect_emulation`] on the given variables and `/proc/cpuinfo` contents.
+pub fn emulation_from(
+    var: impl Fn(&str) -> Option<String>,
+    cpuinfo: Option<&str>,
+) -> Option<Emulation> {
+    let build = TargetArch::default();
+
+    // Docker BuildKit's platform arguments, when a Dockerfile exports them.
+    for name in ["TARGETARCH", "TARGETPLATFORM"] {
+        let Some(value) = var(name).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        let machine = match name {
+            "TARGETPLATFORM" => value.split('/').nth(1).unwrap_or_default(),
+            _ => value.as_str(),
+        };
+        match TargetArch::from_machine(machine) {
+            Some(target) if target != build => {
+                return Some(Emulation {
+                    marker: format!("{name} is {value}, but this wasmedgeup is a {build:?} build"),
+                    target: Some(target),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(name) = QEMU_VARS.iter().find(|name| var(name).is_some()) {
+        return Some(Emulation {
+            marker: format!("{name} is set"),
+            target: None,
+        });
+    }
+
+    match cpuinfo.and_then(cpuinfo_arch) {
+        Some(cpu) if cpu != build => Some(Emulation {
+            marker: format!(
+                "/proc/cpuinfo describes a {cpu:?} CPU, but this wasmedgeup is a {build:?} build"
+            ),
+            target: None,
+        }),
+        _ => None,
+    }
+}
+
+/// The architecture a `/proc/cpuinfo` listing is for, going by the fields each kernel port
+/// prints.
+fn cpuinfo_arch(cpuinfo: &str) -> Option<TargetArch> {
+    let keys = cpuinfo
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, _)| key.trim());
+    for key in keys {
+        match key {
+            "vendor_id" | "flags" => return Some(TargetArch::X86_64),
+            "CPU implementer" | "Features" => return Some(TargetArch::Aarch64),
+            _ => {}
+        }
+    }
+    None
+}
@@ -1,5 +1,6 @@
 pub mod cpu;
 pub mod detector;
+pub mod emulation;
 pub mod gpu;
 pub mod os;
 pub mod plugins;
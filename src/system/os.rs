@@ -18,7 +18,7 @@ pub fn detect_os() -> (OsSpec, Vec<String>, Vec<String>) {
 
 #[cfg(unix)]
 fn detect_os_unix() -> (OsSpec, Vec<String>, Vec<String>) {
-    let notes = Vec::new();
+    let mut notes = Vec::new();
     let mut errors = Vec::new();
 
     let os_type = TargetOS::default();
@@ -33,6 +33,23 @@ fn detect_os_unix() -> (OsSpec, Vec<String>, Vec<String>) {
         errors.push(format!("uname: {e}"));
         None
     });
+    // `uname -srm` ends with the machine name, e.g. `aarch64` or `arm64` for the same CPU.
+    let machine = kernel.as_deref().and_then(|k| k.split_whitespace().last());
+    if let Some(machine) = machine {
+        match TargetArch::from_machine(machine) {
+            Some(reported) if reported != arch => notes.push(format!(
+                "uname reports {machine}, but this wasmedgeup build is for {arch:?}; it may run emulated"
+            )),
+            Some(_) => {}
+            None => notes.push(format!("uname reports unrecognized architecture {machine}")),
+        }
+    }
+    if let Some(emulation) = crate::system::emulation::detect_emulation() {
+        notes.push(format!(
+            "{}; this may be an emulated environment, so pass --arch to install",
+            emulation.marker
+        ));
+    }
 
     let libc = detect_libc().unwrap_or_else(|e| {
         errors.push(format!("libc: {e}"));
@@ -1,12 +1,19 @@
-use clap::ValueEnum;
-use serde::Serialize;
+use std::ffi::OsStr;
 
-#[derive(Debug, Clone, Copy, ValueEnum, Serialize)]
+use clap::{
+    builder::{EnumValueParser, PossibleValue, TypedValueParser},
+    ValueEnum,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::Linkage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
 pub enum TargetOS {
     Linux,
     Ubuntu,
-    /// aliases: [darwin, macos]
-    #[value(alias("macos"))]
+    /// aliases: [darwin, macos, osx]
+    #[value(aliases(["macos", "osx"]))]
     Darwin,
     Windows,
 }
@@ -32,6 +39,18 @@ impl Default for TargetOS {
     }
 }
 
+impl TargetOS {
+    /// Whether builds for this OS run on the host, e.g. `Ubuntu` builds on any Linux host.
+    pub fn matches_host(self) -> bool {
+        let host = match self {
+            Self::Linux | Self::Ubuntu => "linux",
+            Self::Darwin => "macos",
+            Self::Windows => "windows",
+        };
+        host == std::env::consts::OS
+    }
+}
+
 #[cfg(target_os = "linux")]
 fn get_ubuntu_version() -> Option<(u32, u32)> {
     use std::fs;
@@ -67,15 +86,142 @@ fn get_ubuntu_version() -> Option<(u32, u32)> {
     None
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize, Default)]
 pub enum TargetArch {
-    /// aliases: [x86_64, amd64]
-    #[value(name = "x86_64", alias("amd64"))]
+    /// aliases: [x86_64, amd64, x64]
+    #[value(name = "x86_64", aliases(["amd64", "x64"]))]
+    #[serde(alias = "x86_64", alias = "amd64")]
     #[cfg_attr(target_arch = "x86_64", default)]
     X86_64,
 
     /// aliases: [aarch64, arm64]
     #[value(alias("arm64"))]
+    #[serde(alias = "aarch64", alias = "arm64")]
     #[cfg_attr(target_arch = "aarch64", default)]
     Aarch64,
 }
+
+impl TargetArch {
+    /// The architecture a machine name such as `uname -m` prints stands for.
+    ///
+    /// Linux reports 64-bit ARM as `aarch64` on most ABIs but as `arm64` on some, as macOS
+    /// does; both are [`TargetArch::Aarch64`].
+    pub fn from_machine(machine: &str) -> Option<Self> {
+        match machine.trim().to_ascii_lowercase().as_str() {
+            "x86_64" | "amd64" | "x64" => Some(Self::X86_64),
+            "aarch64" | "arm64" => Some(Self::Aarch64),
+            _ => None,
+        }
+    }
+}
+
+/// A build the WasmEdge releases publish, as listed by `wasmedgeup targets`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupportedTarget {
+    pub os: TargetOS,
+    pub arch: TargetArch,
+    /// The C library the build links against
+    pub libc: &'static str,
+    pub linkage: Linkage,
+}
+
+/// Every `--os`/`--arch`/linkage combination releases have builds for. Whether a particular
+/// release has each one varies; see [`crate::api::Asset`] for the archive names.
+pub const SUPPORTED_TARGETS: &[SupportedTarget] = &[
+    SupportedTarget::new(
+        TargetOS::Linux,
+        TargetArch::X86_64,
+        "glibc",
+        Linkage::Dynamic,
+    ),
+    SupportedTarget::new(
+        TargetOS::Linux,
+        TargetArch::Aarch64,
+        "glibc",
+        Linkage::Dynamic,
+    ),
+    SupportedTarget::new(
+        TargetOS::Linux,
+        TargetArch::X86_64,
+        "glibc",
+        Linkage::Static,
+    ),
+    SupportedTarget::new(
+        TargetOS::Linux,
+        TargetArch::Aarch64,
+        "glibc",
+        Linkage::Static,
+    ),
+    SupportedTarget::new(
+        TargetOS::Ubuntu,
+        TargetArch::X86_64,
+        "glibc",
+        Linkage::Dynamic,
+    ),
+    SupportedTarget::new(
+        TargetOS::Ubuntu,
+        TargetArch::Aarch64,
+        "glibc",
+        Linkage::Dynamic,
+    ),
+    SupportedTarget::new(
+        TargetOS::Darwin,
+        TargetArch::X86_64,
+        "libSystem",
+        Linkage::Dynamic,
+    ),
+    SupportedTarget::new(
+        TargetOS::Darwin,
+        TargetArch::Aarch64,
+        "libSystem",
+        Linkage::Dynamic,
+    ),
+    SupportedTarget::new(
+        TargetOS::Windows,
+        TargetArch::X86_64,
+        "msvcrt",
+        Linkage::Dynamic,
+    ),
+];
+
+impl SupportedTarget {
+    const fn new(os: TargetOS, arch: TargetArch, libc: &'static str, linkage: Linkage) -> Self {
+        Self {
+            os,
+            arch,
+            libc,
+            linkage,
+        }
+    }
+}
+
+/// Parses `--arch`: any [`TargetArch`] value, or `native` for the host architecture.
+///
+/// `native` lets cross-OS installs opt into the host architecture explicitly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TargetArchParser;
+
+impl TypedValueParser for TargetArchParser {
+    type Value = TargetArch;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        if value.eq_ignore_ascii_case("native") {
+            return Ok(TargetArch::default());
+        }
+        EnumValueParser::<TargetArch>::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let native = PossibleValue::new("native").help("The architecture of this machine");
+        let values = TargetArch::value_variants()
+            .iter()
+            .filter_map(ValueEnum::to_possible_value)
+            .chain([native]);
+        Some(Box::new(values))
+    }
+}
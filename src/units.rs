@@ -0,0 +1,142 @@
+//! Sizes and durations as shown to users, and sizes and cache lifetimes as accepted from them.
+//!
+//! Sizes always use binary units (1 KiB = 1024 bytes), so that the same number of bytes reads
+//! the same in the progress bars, the install summary and anywhere a size is given.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+/// A number of bytes, displayed as e.g. `512 B` or `1.50 MiB`.
+///
+/// Parsing is lenient about how a size is written, but not about what it means: the unit is
+/// case-insensitive and may be separated by a space, and a comma may stand in for the decimal
+/// point, e.g. `1,5MiB`. Only `B` and the binary units `KiB` up to `PiB` are accepted; `K`,
+/// `KB` and the like are rejected, as they commonly mean 1000-based units too. A comma
+/// followed by exactly three digits is rejected, as it could be digit grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{value:.2} {}", UNITS[unit])
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let invalid = |reason: &str| format!("invalid size '{s}': {reason}");
+
+        let unit = unit.trim().to_ascii_lowercase();
+        let exponent = match unit.as_str() {
+            "" | "b" => 0,
+            _ => match UNITS.iter().position(|u| u.eq_ignore_ascii_case(&unit)) {
+                Some(i) => i as u32,
+                None => {
+                    let prefix = unit.strip_suffix('b').unwrap_or(&unit);
+                    return Err(
+                        match UNITS[1..]
+                            .iter()
+                            .find(|u| u[..1].eq_ignore_ascii_case(prefix))
+                        {
+                            Some(binary) => invalid(&format!(
+                                "'{}' is ambiguous; write {binary} for 1024-based units",
+                                unit.to_ascii_uppercase()
+                            )),
+                            None => invalid("expected a unit such as KiB, MiB or GiB"),
+                        },
+                    );
+                }
+            },
+        };
+        let multiplier = 1024u64.pow(exponent);
+
+        if number.contains(',') && number.contains('.') {
+            return Err(invalid(
+                "use either ',' or '.' as the decimal point, not both",
+            ));
+        }
+        if number
+            .split_once(',')
+            .is_some_and(|(_, fraction)| fraction.len() == 3)
+        {
+            return Err(invalid(
+                "a comma before three digits may be digit grouping; write e.g. 1.5MiB or 1500KiB",
+            ));
+        }
+        let number = number.replace(',', ".");
+        if let Ok(whole) = number.parse::<u64>() {
+            return whole
+                .checked_mul(multiplier)
+                .map(Self)
+                .ok_or_else(|| invalid("too large"));
+        }
+        let value = number
+            .parse::<f64>()
+            .map_err(|_| invalid("expected a number followed by an optional unit"))?;
+        let bytes = (value * multiplier as f64).round();
+        if bytes >= u64::MAX as f64 {
+            return Err(invalid("too large"));
+        }
+        Ok(Self(bytes as u64))
+    }
+}
+
+/// A duration displayed in seconds with one decimal, e.g. `3.4s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seconds(pub Duration);
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}s", self.0.as_secs_f64())
+    }
+}
+
+/// How long a cache entry is trusted without checking it against the server: `never` for no
+/// expiry, or a number with an optional `s`, `m`, `h` or `d` unit, e.g. `90`, `30m` or `7d`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ttl(pub Option<Duration>);
+
+impl FromStr for Ttl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("never") {
+            return Ok(Self(None));
+        }
+        let invalid = |reason: &str| format!("invalid lifetime '{s}': {reason}");
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let number = number
+            .parse::<u64>()
+            .map_err(|_| invalid("expected a whole number followed by s, m, h or d, or never"))?;
+        let seconds = match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return Err(invalid("expected a unit of s, m, h or d")),
+        };
+        number
+            .checked_mul(seconds)
+            .map(|secs| Self(Some(Duration::from_secs(secs))))
+            .ok_or_else(|| invalid("too large"))
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use semver::Version;
+use wasmedgeup::{
+    api::Asset,
+    cache::{DownloadCache, ListingCache},
+    target::{TargetArch, TargetOS},
+};
+
+fn test_asset() -> Asset {
+    Asset::new(
+        &Version::new(0, 14, 1),
+        &TargetOS::Linux,
+        &TargetArch::X86_64,
+    )
+}
+
+#[tokio::test]
+async fn test_cache_store_and_lookup() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = DownloadCache::new(tmp.path().join("downloads"));
+    let asset = test_asset();
+
+    assert!(cache.lookup(&asset).await.is_none());
+
+    let archive = tmp.path().join("archive.tar.gz");
+    std::fs::write(&archive, b"archive bytes").unwrap();
+    cache.store(&asset, &archive, "abc123").await.unwrap();
+
+    let hit = cache.lookup(&asset).await.expect("cache hit");
+    assert_eq!(hit.checksum, "abc123");
+    assert_eq!(hit.path, cache.archive_path(&asset));
+    assert_eq!(std::fs::read(&hit.path).unwrap(), b"archive bytes");
+    assert!(hit.path.starts_with(cache.root().join("0.14.1")));
+}
+
+#[tokio::test]
+async fn test_cache_requires_checksum() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = DownloadCache::new(tmp.path());
+    let asset = test_asset();
+
+    let archive_path = cache.archive_path(&asset);
+    std::fs::create_dir_all(archive_path.parent().unwrap()).unwrap();
+    std::fs::write(&archive_path, b"archive bytes").unwrap();
+
+    assert!(
+        cache.lookup(&asset).await.is_none(),
+        "an archive without a recorded checksum must not be a cache hit"
+    );
+}
+
+#[tokio::test]
+async fn test_cache_evict() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = DownloadCache::new(tmp.path());
+    let asset = test_asset();
+
+    let archive = tmp.path().join("archive.tar.gz");
+    std::fs::write(&archive, b"archive bytes").unwrap();
+    cache.store(&asset, &archive, "abc123").await.unwrap();
+
+    cache.evict(&asset).await;
+    assert!(cache.lookup(&asset).await.is_none());
+    assert!(!cache.archive_path(&asset).exists());
+    assert!(!cache.checksum_path(&asset).exists());
+}
+
+#[tokio::test]
+async fn test_cache_ttl_marks_old_entries_expired() {
+    let tmp = tempfile::tempdir().unwrap();
+    let asset = test_asset();
+    let archive = tmp.path().join("archive.tar.gz");
+    std::fs::write(&archive, b"archive bytes").unwrap();
+    let cache = DownloadCache::new(tmp.path().join("downloads"));
+    cache.store(&asset, &archive, "abc123").await.unwrap();
+
+    assert!(!cache.lookup(&asset).await.unwrap().expired);
+    let within = cache.clone().with_ttl(Some(Duration::from_secs(3600)));
+    assert!(!within.lookup(&asset).await.unwrap().expired);
+    let expired = cache.with_ttl(Some(Duration::ZERO));
+    let hit = expired.lookup(&asset).await.unwrap();
+    assert!(hit.expired);
+    assert_eq!(hit.checksum, "abc123");
+}
+
+#[test]
+fn test_cache_keeps_sources_apart() {
+    let tmp = tempfile::tempdir().unwrap();
+    let asset = test_asset();
+    let official = DownloadCache::new(tmp.path())
+        .with_source("https://github.com/WasmEdge/WasmEdge/releases/download/");
+    let fork = DownloadCache::new(tmp.path())
+        .with_source("https://github.com/someone/WasmEdge/releases/download");
+    let mirror = DownloadCache::new(tmp.path()).with_source("https://mirror.example/wasmedge");
+
+    // The official releases keep the layout from before sources were told apart.
+    assert_eq!(
+        official.archive_path(&asset),
+        DownloadCache::new(tmp.path()).archive_path(&asset)
+    );
+    assert_ne!(fork.archive_path(&asset), official.archive_path(&asset));
+    assert_ne!(fork.archive_path(&asset), mirror.archive_path(&asset));
+    assert_ne!(fork.checksum_path(&asset), official.checksum_path(&asset));
+    assert!(fork
+        .archive_path(&asset)
+        .ends_with(format!("0.14.1/{}", asset.archive_name)));
+}
+
+#[tokio::test]
+async fn test_listing_cache_store_and_lookup() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = ListingCache::new(tmp.path().join("listings"));
+    let releases = vec![
+        Version::new(0, 14, 1),
+        Version::parse("0.15.0-rc.1").unwrap(),
+    ];
+
+    assert!(cache.lookup("api stable").await.is_none());
+    cache.store("api stable", &releases).await;
+    assert_eq!(cache.lookup("api stable").await.unwrap(), releases);
+    assert!(cache.lookup("api all").await.is_none());
+
+    let expired = cache.clone().with_ttl(Some(Duration::ZERO));
+    assert!(expired.lookup("api stable").await.is_none());
+    let never = cache.with_ttl(None);
+    assert_eq!(never.lookup("api stable").await.unwrap(), releases);
+}
+
+#[tokio::test]
+async fn test_listing_cache_ignores_unreadable_listings() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = ListingCache::new(tmp.path());
+
+    std::fs::write(cache.listing_path("garbled"), "0.14.1\nnot a version\n").unwrap();
+    assert!(cache.lookup("garbled").await.is_none());
+    std::fs::write(cache.listing_path("empty"), "").unwrap();
+    assert!(cache.lookup("empty").await.is_none());
+}
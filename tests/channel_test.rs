@@ -0,0 +1,75 @@
+use semver::Version;
+use wasmedgeup::{
+    api::{newest_matching, partial_version_req, Channel, WasmEdgeApiClient},
+    error::Error,
+};
+
+fn releases() -> Vec<Version> {
+    [
+        "0.15.1-rc.1",
+        "0.15.0",
+        "0.14.1",
+        "0.14.1-rc.2",
+        "0.14.0",
+        "0.13.5",
+    ]
+    .iter()
+    .map(|v| Version::parse(v).unwrap())
+    .collect()
+}
+
+#[test]
+fn test_latest_and_stable_pick_newest_stable() {
+    for name in ["latest", "stable", "STABLE"] {
+        let channel = Channel::find(name).expect("known channel");
+        assert_eq!(channel.select(&releases()).unwrap(), Version::new(0, 15, 0));
+    }
+}
+
+#[test]
+fn test_lts_picks_newest_in_line() {
+    let channel = Channel::find("lts").expect("known channel");
+    assert_eq!(channel.select(&releases()).unwrap(), Version::new(0, 14, 1));
+}
+
+#[test]
+fn test_lts_without_matching_release() {
+    let channel = Channel::find("lts").expect("known channel");
+    let result = channel.select(&[Version::new(0, 15, 0)]);
+    assert!(matches!(result, Err(Error::VersionNotFound { .. })));
+}
+
+#[test]
+fn test_partial_versions_select_newest_in_line() {
+    let newest = |partial: &str| {
+        let req = partial_version_req(partial).expect("partial version");
+        newest_matching(&releases(), &req)
+    };
+    assert_eq!(newest("0.14"), Some(Version::new(0, 14, 1)));
+    assert_eq!(newest("0.13"), Some(Version::new(0, 13, 5)));
+    assert_eq!(newest("0"), Some(Version::new(0, 15, 0)));
+    assert_eq!(newest("0.16"), None);
+    assert_eq!(newest("1"), None);
+
+    // Full versions stay exact, and anything else is not a partial version.
+    for not_partial in ["0.14.1", "0.14.1-rc.1", "0.x", "0.", "", "v0.14", "latest"] {
+        assert!(partial_version_req(not_partial).is_none(), "{not_partial}");
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_channel_lists_valid_ones() {
+    let client = WasmEdgeApiClient::default();
+    match client.resolve_version("nightly").await {
+        Err(e @ Error::UnknownChannel { .. }) => {
+            let message = e.to_string();
+            assert!(message.contains("latest, stable, lts"), "{message}");
+        }
+        other => panic!("expected UnknownChannel, got {other:?}"),
+    }
+
+    assert!(matches!(
+        client.resolve_version("v0.14.1").await,
+        Err(Error::SemVer { .. })
+    ));
+}
@@ -0,0 +1,96 @@
+#![cfg(unix)]
+
+use serial_test::serial;
+use wasmedgeup::error::Error;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{install_from, linux_archive, linux_asset, sha256_hex, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_install_downloads_a_corrupt_archive_again() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    let mut corrupt = archive.clone();
+    corrupt[archive.len() / 2] ^= 0xff;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/releases/download/{VERSION}/{}",
+            asset.archive_name
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(corrupt))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let (result, tmpdir) = install_from(&mock).await;
+    result.expect("install failed");
+    assert!(tmpdir.path().join("install_target/bin/wasmedge").exists());
+    let downloads = mock.downloads(&asset.archive_name).await;
+    assert_eq!(downloads, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_uppercase_checksum_file_entries_verify() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(
+        VERSION,
+        &asset.archive_name,
+        archive,
+        &checksum.to_ascii_uppercase(),
+    )
+    .await;
+
+    let published = mock
+        .client()
+        .get_release_checksum(&asset.version, &asset)
+        .await
+        .unwrap();
+    assert_eq!(published, checksum);
+
+    let (result, _tmpdir) = install_from(&mock).await;
+    result.expect("install failed");
+    let downloads = mock.downloads(&asset.archive_name).await;
+    assert_eq!(downloads, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_rejects_checksum_mismatch() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let wrong = sha256_hex(b"something else");
+    mock.mount_release(VERSION, &asset.archive_name, archive, &wrong)
+        .await;
+
+    let (result, tmpdir) = install_from(&mock).await;
+    assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+    // A mismatch is retried a few times in case the transfer was corrupted, then given up on.
+    let downloads = mock.downloads(&asset.archive_name).await;
+    assert_eq!(downloads, 3);
+    // The staging directory goes away on errors as well, not just on success or Ctrl-C.
+    assert!(!tmpdir
+        .path()
+        .join("work")
+        .join(&asset.install_name)
+        .exists());
+}
@@ -0,0 +1,144 @@
+#![cfg(unix)]
+
+use semver::Version;
+use wasmedgeup::{
+    api::{
+        ApiDigest, Asset, ChecksumSource, Explicit, Published, SelectedSource, SumsFile,
+        WasmEdgeApiClient,
+    },
+    error::Error,
+    target::{TargetArch, TargetOS},
+};
+use wiremock::ResponseTemplate;
+
+mod mock_server;
+use mock_server::{linux_archive, MockRelease};
+
+const VERSION: &str = "0.9.0";
+
+fn asset() -> (Version, Asset) {
+    let version = Version::parse(VERSION).unwrap();
+    let asset = Asset::new(&version, &TargetOS::Linux, &TargetArch::X86_64);
+    (version, asset)
+}
+
+/// A release that publishes its archive but no `SHA256SUM` file.
+async fn unpublished_release() -> (MockRelease, Version, Asset) {
+    let mock = MockRelease::start().await;
+    let (version, asset) = asset();
+    mock.mount_file(VERSION, &asset.archive_name, linux_archive(&version))
+        .await;
+    mock.mount_status(VERSION, "SHA256SUM", ResponseTemplate::new(404))
+        .await;
+    (mock, version, asset)
+}
+
+#[tokio::test]
+async fn test_sums_file_source() {
+    let mock = MockRelease::start().await;
+    let (version, asset) = asset();
+    let sums = format!(
+        "{}  other.tar.gz\n{}  {}\n",
+        "1".repeat(64),
+        "2".repeat(64),
+        asset.archive_name
+    );
+    mock.mount_file(VERSION, "SHA256SUM", sums.into_bytes())
+        .await;
+
+    let checksum = SumsFile.checksum(&mock.client(), &version, &asset).await;
+    assert_eq!(checksum.unwrap(), "2".repeat(64));
+}
+
+#[tokio::test]
+async fn test_sums_file_lists_published_files() {
+    let (mock, version, asset) = unpublished_release().await;
+    let client = mock.client();
+    assert_eq!(SumsFile.listed(&client, &version).await.unwrap(), None);
+
+    let mock = MockRelease::start().await;
+    let sums = format!("{}  {}\n\n", "1".repeat(64), asset.archive_name);
+    mock.mount_file(VERSION, "SHA256SUM", sums.into_bytes())
+        .await;
+    let listed = SumsFile.listed(&mock.client(), &version).await.unwrap();
+    assert_eq!(listed, Some(vec![asset.archive_name]));
+}
+
+#[tokio::test]
+async fn test_sums_file_source_reports_unpublished_checksums() {
+    let (mock, version, asset) = unpublished_release().await;
+    let result = SumsFile.checksum(&mock.client(), &version, &asset).await;
+    assert!(
+        matches!(result, Err(Error::ChecksumsUnpublished { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_explicit_source() {
+    let source = Explicit::new(&"AB".repeat(32));
+    let (version, asset) = asset();
+    // Nothing is fetched: the client points nowhere reachable.
+    let client = WasmEdgeApiClient::default().with_release_base_url("http://127.0.0.1:9/");
+    let checksum = source.checksum(&client, &version, &asset).await;
+    assert_eq!(checksum.unwrap(), "ab".repeat(32));
+}
+
+#[tokio::test]
+async fn test_api_digest_source() {
+    let mock = MockRelease::start().await;
+    let (version, asset) = asset();
+    let digest = format!("sha256:{}", "C".repeat(64));
+    mock.mount_api_release(
+        VERSION,
+        &[("other.tar.gz", None), (&asset.archive_name, Some(&digest))],
+    )
+    .await;
+
+    let checksum = ApiDigest.checksum(&mock.client(), &version, &asset).await;
+    assert_eq!(checksum.unwrap(), "c".repeat(64));
+}
+
+#[tokio::test]
+async fn test_api_digest_source_without_digest() {
+    let mock = MockRelease::start().await;
+    let (version, asset) = asset();
+    mock.mount_api_release(VERSION, &[(&asset.archive_name, None)])
+        .await;
+
+    let result = ApiDigest.checksum(&mock.client(), &version, &asset).await;
+    assert!(
+        matches!(result, Err(Error::ChecksumNotFound { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_published_source_falls_back_to_api_digest() {
+    let (mock, version, asset) = unpublished_release().await;
+    let client = mock.client();
+    let result = Published.checksum(&client, &version, &asset).await;
+    assert!(
+        matches!(result, Err(Error::ChecksumsUnpublished { .. })),
+        "without a digest the checksum file's error is kept: {result:?}"
+    );
+
+    let digest = format!("sha256:{}", "d".repeat(64));
+    mock.mount_api_release(VERSION, &[(&asset.archive_name, Some(&digest))])
+        .await;
+    let checksum = Published.checksum(&client, &version, &asset).await;
+    assert_eq!(checksum.unwrap(), "d".repeat(64));
+}
+
+#[test]
+fn test_checksum_source_selection() {
+    let client = WasmEdgeApiClient::default();
+    assert_eq!(
+        client.checksum_source(Some(&"e".repeat(64))),
+        SelectedSource::Explicit(Explicit::new(&"e".repeat(64)))
+    );
+    assert_eq!(
+        client.checksum_source(None),
+        SelectedSource::Published(Published)
+    );
+}
@@ -0,0 +1,40 @@
+use std::io::{Cursor, Seek, SeekFrom, Write};
+
+use sha2::{Digest, Sha256};
+use wasmedgeup::api::{sha256_hex, sha256_hex_file, sha256_hex_pipelined, WasmEdgeApiClient};
+
+const MB: usize = 1024 * 1024;
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_pipelined_hash_matches_sequential() {
+    for len in [0, 1, MB - 1, MB, MB + 1, 3 * MB + 7] {
+        let data = data(len);
+        let expected = hex::encode(Sha256::digest(&data));
+        assert_eq!(sha256_hex(&mut Cursor::new(&data)).unwrap(), expected);
+        assert_eq!(
+            sha256_hex_pipelined(&mut Cursor::new(&data)).unwrap(),
+            expected,
+            "pipelined digest differs for {len} bytes"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_verify_file_checksum_rewinds() {
+    let data = data(2 * MB + 3);
+    let expected = hex::encode(Sha256::digest(&data));
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(&data).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(sha256_hex_file(&mut file).unwrap(), expected);
+    file.seek(SeekFrom::Start(0)).unwrap();
+    WasmEdgeApiClient::verify_file_checksum(&mut file, &expected)
+        .await
+        .unwrap();
+    assert_eq!(file.stream_position().unwrap(), 0);
+}
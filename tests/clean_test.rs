@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use tempfile::tempdir;
+use wasmedgeup::{
+    cli::{CommandContext, CommandExecutor},
+    commands::clean::CleanArgs,
+    fs::{staging_marker, TempDirGuard},
+};
+
+fn stage(dir: &Path) {
+    std::fs::create_dir_all(dir.join("bin")).unwrap();
+    std::fs::write(dir.join("bin/wasmedge"), vec![0; 2048]).unwrap();
+}
+
+async fn clean(tmpdir: &Path, install_dir: &Path, dry_run: bool) {
+    let args = CleanArgs {
+        tmpdir: Some(tmpdir.to_path_buf()),
+        path: Some(install_dir.to_path_buf()),
+        dry_run,
+    };
+    args.execute(CommandContext::default()).await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_clean_removes_only_marked_staging_dirs_of_dead_processes() {
+    let root = tempdir().unwrap();
+    let tmpdir = root.path().join("tmp");
+    let install_dir = root.path().join("wasmedge");
+
+    // A crashed install: the guard never ran, so the directory and its marker remain.
+    let crashed = tmpdir.join("WasmEdge-0.14.1-Linux");
+    stage(&crashed);
+    std::mem::forget(TempDirGuard::staging(&crashed).unwrap());
+    std::fs::write(staging_marker(&crashed), format!("{}\n", u32::MAX)).unwrap();
+    let on_target = install_dir.join(".wasmedgeup-tmp/WasmEdge-0.15.0-Linux");
+    stage(&on_target);
+    std::mem::forget(TempDirGuard::staging(&on_target).unwrap());
+
+    let unmarked = tmpdir.join("WasmEdge-0.13.5-Linux");
+    stage(&unmarked);
+    let foreign = tmpdir.join("build-cache");
+    stage(&foreign);
+    std::fs::write(staging_marker(&foreign), "1\n").unwrap();
+    let mut running = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let in_use = tmpdir.join("WasmEdge-plugin-wasi_nn-0.14.1");
+    stage(&in_use);
+    std::fs::write(staging_marker(&in_use), format!("{}\n", running.id())).unwrap();
+
+    clean(&tmpdir, &install_dir, true).await;
+    assert!(crashed.exists() && on_target.exists());
+
+    clean(&tmpdir, &install_dir, false).await;
+    running.kill().unwrap();
+    running.wait().unwrap();
+    assert!(!crashed.exists());
+    assert!(!staging_marker(&crashed).exists());
+    assert!(!on_target.exists());
+    assert!(unmarked.exists());
+    assert!(foreign.exists());
+    assert!(in_use.exists());
+    assert!(staging_marker(&in_use).exists());
+}
+
+#[tokio::test]
+async fn test_staging_guard_removes_its_marker() {
+    let root = tempdir().unwrap();
+    let dir = root.path().join("WasmEdge-0.14.1-Linux");
+    stage(&dir);
+
+    let guard = TempDirGuard::staging(&dir).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(staging_marker(&dir)).unwrap(),
+        format!("{}\n", std::process::id())
+    );
+    guard.close().await.unwrap();
+    assert!(!dir.exists());
+    assert!(!staging_marker(&dir).exists());
+
+    stage(&dir);
+    let kept = TempDirGuard::staging(&dir).unwrap().disarm();
+    assert!(kept.exists());
+    assert!(!staging_marker(&dir).exists());
+}
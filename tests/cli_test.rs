@@ -0,0 +1,727 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use serial_test::serial;
+use wasmedgeup::{
+    api::{RepoSlug, WasmEdgeApiClient},
+    cli::{Cli, Commands},
+    commands::install::{InstallArgs, ReadOnlyScope},
+    shell_utils::EnvFileShell,
+    target::{TargetArch, TargetOS},
+};
+
+fn parse_install(args: &[&str]) -> Result<InstallArgs, clap::Error> {
+    let argv = ["wasmedgeup", "install", "latest"].iter().chain(args);
+    match Cli::try_parse_from(argv)?.commands {
+        Some(Commands::Install(args)) => Ok(args),
+        other => panic!("expected install command, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_arch_aliases() {
+    for alias in ["x86_64", "amd64", "x64", "X86_64", "AMD64"] {
+        let args = parse_install(&["--arch", alias]).unwrap();
+        assert!(
+            matches!(args.arch, Some(TargetArch::X86_64)),
+            "{alias} should map to x86_64"
+        );
+    }
+    for alias in ["aarch64", "arm64", "ARM64"] {
+        let args = parse_install(&["--arch", alias]).unwrap();
+        assert!(
+            matches!(args.arch, Some(TargetArch::Aarch64)),
+            "{alias} should map to aarch64"
+        );
+    }
+}
+
+#[test]
+fn test_machine_names_normalize_arch() {
+    for machine in ["aarch64", "arm64", "ARM64", "aarch64\n"] {
+        assert_eq!(
+            TargetArch::from_machine(machine),
+            Some(TargetArch::Aarch64),
+            "{machine:?}"
+        );
+    }
+    for machine in ["x86_64", "amd64", "x64"] {
+        assert_eq!(
+            TargetArch::from_machine(machine),
+            Some(TargetArch::X86_64),
+            "{machine:?}"
+        );
+    }
+    assert_eq!(TargetArch::from_machine("riscv64"), None);
+
+    // Manifests written by hand or by other tools may use machine names too.
+    for name in ["\"Aarch64\"", "\"aarch64\"", "\"arm64\""] {
+        let arch: TargetArch = serde_json::from_str(name).unwrap();
+        assert_eq!(arch, TargetArch::Aarch64, "{name}");
+    }
+    assert_eq!(
+        serde_json::to_string(&TargetArch::Aarch64).unwrap(),
+        "\"Aarch64\""
+    );
+}
+
+#[test]
+fn test_os_aliases() {
+    for alias in ["darwin", "Darwin", "macos", "osx"] {
+        let args = parse_install(&["--os", alias]).unwrap();
+        assert!(
+            matches!(args.os, Some(TargetOS::Darwin)),
+            "{alias} should map to Darwin"
+        );
+    }
+    let args = parse_install(&["--os", "Ubuntu"]).unwrap();
+    assert!(matches!(args.os, Some(TargetOS::Ubuntu)));
+}
+
+#[test]
+fn test_invalid_arch_lists_possible_values() {
+    let err = parse_install(&["--arch", "riscv64"]).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("possible values: x86_64, aarch64"),
+        "unexpected error message: {message}"
+    );
+}
+
+#[test]
+fn test_invalid_os_lists_possible_values() {
+    let err = parse_install(&["--os", "freebsd"]).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("possible values: linux, ubuntu, darwin, windows"),
+        "unexpected error message: {message}"
+    );
+}
+
+#[test]
+fn test_ephemeral_run_collects_trailing_args() {
+    let args = parse_install(&["--ephemeral-run", "--", "--version"]).unwrap();
+    assert!(args.ephemeral_run);
+    assert_eq!(args.run_args, vec!["--version".to_string()]);
+}
+
+#[test]
+fn test_ephemeral_conflicts_with_path() {
+    assert!(parse_install(&["--ephemeral", "--path", "/tmp/wasmedge"]).is_err());
+    assert!(parse_install(&["--ephemeral-run", "--path", "/tmp/wasmedge"]).is_err());
+    assert!(parse_install(&["--", "--version"]).is_err());
+}
+
+#[test]
+fn test_prefix_conflicts_with_path_and_ephemeral() {
+    let args = parse_install(&["--prefix", "/usr/local"]).unwrap();
+    assert_eq!(
+        args.prefix.as_deref(),
+        Some(std::path::Path::new("/usr/local"))
+    );
+    assert!(parse_install(&["--prefix", "/usr/local", "--path", "/tmp/wasmedge"]).is_err());
+    assert!(parse_install(&["--prefix", "/usr/local", "--ephemeral"]).is_err());
+    assert!(
+        Cli::try_parse_from(["wasmedgeup", "remove", "--prefix", "/usr/local", "--all"]).is_err()
+    );
+}
+
+#[test]
+fn test_read_only_scope_defaults_to_files() {
+    assert_eq!(parse_install(&[]).unwrap().read_only, None);
+    assert_eq!(
+        parse_install(&["--read-only"]).unwrap().read_only,
+        Some(ReadOnlyScope::Files)
+    );
+    assert_eq!(
+        parse_install(&["--read-only=all"]).unwrap().read_only,
+        Some(ReadOnlyScope::All)
+    );
+    assert!(parse_install(&["--read-only=everything"]).is_err());
+    assert!(parse_install(&["--read-only", "--prefix", "/usr/local"]).is_err());
+}
+
+#[test]
+fn test_multi_collects_versions() {
+    let args = parse_install(&["0.14.0", "0.13.5", "--multi", "-j", "2", "--fail-fast"]).unwrap();
+    assert!(args.multi && args.fail_fast);
+    assert_eq!(args.version, "latest");
+    assert_eq!(
+        args.versions,
+        vec!["0.14.0".to_string(), "0.13.5".to_string()]
+    );
+    assert_eq!(args.jobs, Some(2));
+
+    assert!(parse_install(&["0.14.0"]).is_err());
+    assert!(parse_install(&["--jobs", "2"]).is_err());
+    assert!(parse_install(&["0.14.0", "--multi", "--jobs", "0"]).is_err());
+    assert!(parse_install(&["0.14.0", "--multi", "--prefix", "/usr/local"]).is_err());
+}
+
+#[test]
+fn test_shell_requires_write_env_file() {
+    let args = parse_install(&["--write-env-file", "wasmedge.env", "--shell", "fish"]).unwrap();
+    assert_eq!(args.write_env_file, Some(PathBuf::from("wasmedge.env")));
+    assert_eq!(args.shell, Some(EnvFileShell::Fish));
+    assert!(parse_install(&["--write-env-file", "e", "--shell", "pwsh"]).is_ok());
+
+    assert!(parse_install(&["--shell", "fish"]).is_err());
+    assert!(parse_install(&["--write-env-file", "e", "--print-url"]).is_err());
+}
+
+#[test]
+fn test_expected_checksum_must_be_sha256() {
+    let checksum = "AB".repeat(32);
+    let args = parse_install(&["--sha", &checksum]).unwrap();
+    assert_eq!(args.expected_checksum, Some("ab".repeat(32)));
+
+    assert!(parse_install(&["--expected-checksum", "abcd"]).is_err());
+    assert!(parse_install(&["--expected-checksum", &"g".repeat(64)]).is_err());
+    assert!(parse_install(&["--expected-checksum", &"a".repeat(128)]).is_err());
+}
+
+#[test]
+fn test_temp_on_target_conflicts_with_tmpdir() {
+    assert!(parse_install(&["--temp-on-target"]).unwrap().temp_on_target);
+    assert!(parse_install(&["--temp-on-target", "--tmpdir", "/tmp/x"]).is_err());
+    assert!(parse_install(&["--temp-on-target", "--ephemeral"]).is_err());
+}
+
+#[test]
+fn test_repo_selects_release_sources() {
+    for flag in ["--repo", "--channel"] {
+        let cli =
+            Cli::try_parse_from(["wasmedgeup", flag, "my-org/WasmEdge.fork", "list"]).unwrap();
+        let client = cli.context().client;
+        assert_eq!(client.repo.as_str(), "my-org/WasmEdge.fork");
+        assert_eq!(
+            client.git_url,
+            "https://github.com/my-org/WasmEdge.fork.git"
+        );
+        assert_eq!(
+            client.release_base_url,
+            "https://github.com/my-org/WasmEdge.fork/releases/download"
+        );
+    }
+
+    let client = Cli::try_parse_from(["wasmedgeup", "list"])
+        .unwrap()
+        .context()
+        .client;
+    assert_eq!(client.repo.as_str(), "WasmEdge/WasmEdge");
+}
+
+#[test]
+fn test_repo_rejects_invalid_slugs() {
+    for slug in [
+        "WasmEdge",
+        "a/b/c",
+        "/WasmEdge",
+        "owner/",
+        "-owner/repo",
+        "owner/..",
+        "own er/repo",
+    ] {
+        let flag = format!("--repo={slug}");
+        let err = Cli::try_parse_from(["wasmedgeup", &flag, "list"]).unwrap_err();
+        assert!(
+            err.to_string().contains("expected the form 'owner/name'"),
+            "{slug} should be rejected: {err}"
+        );
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn test_scope_defaults_to_user() {
+    use wasmedgeup::shell_utils::PathScope;
+
+    assert_eq!(parse_install(&[]).unwrap().scope, PathScope::User);
+    let args = parse_install(&["--scope", "machine"]).unwrap();
+    assert_eq!(args.scope, PathScope::Machine);
+}
+
+#[test]
+fn test_setup_path_flags() {
+    let setup = |args: &[&str]| parse_install(args).unwrap().should_setup_path();
+
+    assert!(setup(&[]));
+    assert!(setup(&["--setup-path"]));
+    assert!(setup(&["--setup-path=true"]));
+    assert!(!setup(&["--setup-path=false"]));
+    assert!(!setup(&["--no-setup-path"]));
+
+    // The last of the two flags wins.
+    assert!(setup(&["--no-setup-path", "--setup-path"]));
+    assert!(!setup(&["--setup-path", "--no-setup-path"]));
+    assert!(parse_install(&["--setup-path=maybe"]).is_err());
+}
+
+#[test]
+fn test_user_agent_override() {
+    let client = Cli::try_parse_from(["wasmedgeup", "list"])
+        .unwrap()
+        .context()
+        .client;
+    assert!(client.user_agent().starts_with("wasmedgeup/"));
+
+    let cli = Cli::try_parse_from(["wasmedgeup", "--user-agent", "ci-bot/1.0", "list"]).unwrap();
+    assert_eq!(cli.context().client.user_agent(), "ci-bot/1.0");
+
+    assert!(Cli::try_parse_from(["wasmedgeup", "--user-agent", "bad\nagent", "list"]).is_err());
+}
+
+#[test]
+#[serial]
+fn test_cacert_flag_and_env() {
+    let ca_cert = |args: &[&str]| {
+        let argv = ["wasmedgeup"].iter().chain(args).chain(&["list"]);
+        Cli::try_parse_from(argv).unwrap().context().client.ca_cert
+    };
+
+    assert_eq!(
+        ca_cert(&["--cacert", "/etc/proxy-ca.pem"]),
+        Some(PathBuf::from("/etc/proxy-ca.pem"))
+    );
+
+    std::env::set_var("WASMEDGEUP_CACERT", "/etc/env-ca.pem");
+    let from_env = ca_cert(&[]);
+    let flag_wins = ca_cert(&["--cacert", "/etc/proxy-ca.pem"]);
+    std::env::remove_var("WASMEDGEUP_CACERT");
+    assert_eq!(from_env, Some(PathBuf::from("/etc/env-ca.pem")));
+    assert_eq!(flag_wins, Some(PathBuf::from("/etc/proxy-ca.pem")));
+}
+
+#[test]
+fn test_danger_insecure_is_opt_in() {
+    let insecure = |args: &[&str]| {
+        let argv = ["wasmedgeup"].iter().chain(args).chain(&["list"]);
+        Cli::try_parse_from(argv)
+            .unwrap()
+            .context()
+            .client
+            .danger_insecure
+    };
+
+    assert!(!insecure(&[]));
+    assert!(insecure(&["--danger-insecure"]));
+}
+
+#[test]
+fn test_from_manifest_replaces_version() {
+    let parse = |args: &[&str]| Cli::try_parse_from(["wasmedgeup", "install"].iter().chain(args));
+
+    match parse(&["--from-manifest", "wasmedge.lock.json"])
+        .unwrap()
+        .commands
+    {
+        Some(Commands::Install(args)) => {
+            assert!(args.version.is_empty());
+            assert_eq!(
+                args.from_manifest,
+                Some(PathBuf::from("wasmedge.lock.json"))
+            );
+        }
+        other => panic!("expected install command, got {other:?}"),
+    }
+    assert!(parse(&[]).is_err());
+    assert!(parse(&["0.14.1", "--from-manifest", "wasmedge.lock.json"]).is_err());
+    assert!(parse(&["--from-manifest", "a.json", "--os", "linux"]).is_err());
+    assert!(parse_install(&["--manifest-only", "a.json", "--from-manifest", "b.json"]).is_err());
+}
+
+#[test]
+fn test_include_exclude_globs() {
+    let args = parse_install(&[
+        "--include",
+        "bin/*",
+        "--include",
+        "lib64/**",
+        "--exclude",
+        "*.h",
+    ])
+    .unwrap();
+    assert_eq!(args.include.len(), 2);
+    assert_eq!(args.exclude[0].as_str(), "*.h");
+
+    assert!(parse_install(&["--include", "bin/[*"]).is_err());
+}
+
+#[test]
+fn test_resolve_via() {
+    use wasmedgeup::api::ResolveVia;
+
+    let resolve_via = |args: &[&str]| {
+        let argv = ["wasmedgeup"].iter().chain(args).chain(&["list"]);
+        Cli::try_parse_from(argv)
+            .unwrap()
+            .context()
+            .client
+            .resolve_via
+    };
+    assert_eq!(resolve_via(&[]), ResolveVia::Auto);
+    assert_eq!(resolve_via(&["--resolve-via", "api"]), ResolveVia::Api);
+    assert_eq!(resolve_via(&["--resolve-via", "git"]), ResolveVia::Git);
+    assert!(Cli::try_parse_from(["wasmedgeup", "--resolve-via", "ftp", "list"]).is_err());
+}
+
+#[test]
+fn test_arch_native_selects_host_arch() {
+    let args = parse_install(&["--arch", "native"]).unwrap();
+    assert!(matches!(
+        (args.arch, TargetArch::default()),
+        (Some(TargetArch::X86_64), TargetArch::X86_64)
+            | (Some(TargetArch::Aarch64), TargetArch::Aarch64)
+    ));
+    assert!(parse_install(&["--arch", "NATIVE"]).unwrap().arch.is_some());
+}
+
+#[tokio::test]
+async fn test_cross_os_install_requires_arch() {
+    use wasmedgeup::{
+        cli::{CommandContext, CommandExecutor},
+        error::Error,
+    };
+
+    let foreign = if cfg!(windows) { "linux" } else { "windows" };
+    let args = parse_install(&["--os", foreign]).unwrap();
+    let result = args.execute(CommandContext::default()).await;
+    assert!(
+        matches!(result, Err(Error::CrossInstallArchRequired { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_emulated_install_requires_arch() {
+    use wasmedgeup::{
+        cli::{CommandContext, CommandExecutor},
+        environment::Environment,
+        error::Error,
+    };
+
+    let _active = Environment::new()
+        .with_var("QEMU_LD_PREFIX", "/usr/aarch64-linux-gnu")
+        .activate();
+    let result = parse_install(&[])
+        .unwrap()
+        .execute(CommandContext::default())
+        .await;
+    assert!(
+        matches!(&result, Err(Error::EmulatedArchRequired { marker, .. }) if marker == "QEMU_LD_PREFIX is set"),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn test_env_file_defaults_to_dotenv() {
+    let cli = Cli::try_parse_from(["wasmedgeup", "--env-file", "list"]).unwrap();
+    assert_eq!(cli.env_file, Some(PathBuf::from(".env")));
+    let cli = Cli::try_parse_from(["wasmedgeup", "--env-file=ci.env", "list"]).unwrap();
+    assert_eq!(cli.env_file, Some(PathBuf::from("ci.env")));
+    let cli = Cli::try_parse_from(["wasmedgeup", "list"]).unwrap();
+    assert_eq!(cli.env_file, None);
+}
+
+#[test]
+fn test_mode_is_octal_with_full_owner_access() {
+    assert_eq!(parse_install(&["--mode", "750"]).unwrap().mode, Some(0o750));
+    assert_eq!(
+        parse_install(&["--mode", "0755"]).unwrap().mode,
+        Some(0o755)
+    );
+    assert!(parse_install(&["--mode", "644"]).is_err());
+    assert!(parse_install(&["--mode", "789"]).is_err());
+    assert!(parse_install(&["--mode", "17777"]).is_err());
+}
+
+#[test]
+fn test_json_requires_check_update() {
+    let cli = Cli::try_parse_from(["wasmedgeup", "--check-update", "--json"]).unwrap();
+    assert!(cli.check_update && cli.json);
+    assert!(Cli::try_parse_from(["wasmedgeup", "--json", "list"]).is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_xdg_layout_is_opt_in() {
+    let home = tempfile::tempdir().unwrap();
+    let data_home = home.path().join("data");
+    // Lists the one version installed in each candidate location.
+    for (dir, version) in [
+        (data_home.join("wasmedge"), "0.14.1"),
+        (home.path().join(".local/share/wasmedge"), "0.14.0"),
+    ] {
+        std::fs::create_dir_all(dir.join("versions").join(version)).unwrap();
+    }
+    let listed = |args: &[&str], xdg_data_home: Option<&PathBuf>| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"));
+        command.args(args).args(["list"]).env("HOME", home.path());
+        match xdg_data_home {
+            Some(dir) => command.env("XDG_DATA_HOME", dir),
+            None => command.env_remove("XDG_DATA_HOME"),
+        };
+        let output = command.env_remove("WASMEDGEUP_XDG").output().unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    };
+
+    // Without `--xdg` the default stays `~/.wasmedge`, even when it does not exist yet.
+    assert_eq!(listed(&[], Some(&data_home)), "");
+    assert_eq!(listed(&[], None), "");
+    assert_eq!(listed(&["--xdg"], None), "0.14.0");
+    assert_eq!(listed(&["--xdg"], Some(&data_home)), "0.14.1");
+
+    std::fs::create_dir_all(home.path().join(".wasmedge/versions/0.13.5")).unwrap();
+    assert_eq!(listed(&[], Some(&data_home)), "0.13.5");
+    assert_eq!(listed(&["--xdg"], Some(&data_home)), "0.14.1");
+}
+
+#[test]
+fn test_url_replaces_version() {
+    let argv = |args: &[&str]| -> Result<InstallArgs, clap::Error> {
+        let argv = ["wasmedgeup", "install"].iter().chain(args);
+        match Cli::try_parse_from(argv)?.commands {
+            Some(Commands::Install(args)) => Ok(args),
+            other => panic!("expected install command, got {other:?}"),
+        }
+    };
+    let url = "https://ci.example.com/WasmEdge-0.15.0-Linux.tar.gz";
+    let args = argv(&["--url", url, "--label", "v0.15.0-ci.1"]).unwrap();
+    assert_eq!(args.url.unwrap().as_str(), url);
+    assert_eq!(args.label.unwrap().to_string(), "0.15.0-ci.1");
+    assert!(argv(&["0.14.1", "--url", url]).is_err());
+    assert!(argv(&["--url", url, "--label", "nightly"]).is_err());
+    assert!(argv(&["0.14.1", "--label", "0.15.0"]).is_err());
+}
+
+#[test]
+fn test_interactive_fails_without_terminal() {
+    let args = parse_install(&[]).unwrap();
+    assert!(!args.interactive);
+    let argv = ["wasmedgeup", "install", "--interactive"];
+    assert!(Cli::try_parse_from(argv).is_ok());
+    assert!(Cli::try_parse_from(["wasmedgeup", "install", "0.14.1", "-i"]).is_err());
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .args(["install", "--interactive"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--interactive needs a terminal"),
+        "{stderr}"
+    );
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .args(["install", "--interactive", "--yes"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cannot prompt with --yes"), "{stderr}");
+    assert!(parse_install(&["-y"]).unwrap().yes);
+}
+
+#[test]
+fn test_quiet_errors_prints_only_the_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |flags: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+            .args(flags)
+            .args(["--retries", "0", "install", "--url"])
+            .arg("http://127.0.0.1:9/WasmEdge-0.14.1-Linux.tar.gz")
+            .arg("--path")
+            .arg(dir.path().join("install"))
+            .arg("--tmpdir")
+            .arg(dir.path().join("work"))
+            .output()
+            .unwrap()
+    };
+
+    let noisy = run(&[]);
+    assert_eq!(noisy.status.code(), Some(1));
+    assert!(!noisy.stdout.is_empty(), "{noisy:?}");
+
+    let quiet = run(&["--quiet-errors"]);
+    assert_eq!(quiet.status.code(), Some(1));
+    assert!(quiet.stdout.is_empty(), "{quiet:?}");
+    let stderr = String::from_utf8_lossy(&quiet.stderr);
+    assert_eq!(stderr.lines().count(), 1, "{stderr}");
+    assert!(stderr.contains("asset check"), "{stderr}");
+}
+
+#[test]
+fn test_quiet_and_json_lines_failures_keep_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |flags: &[&str], install_flags: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+            .args(flags)
+            .args(["--retries", "0", "install"])
+            .args(install_flags)
+            .arg("--url")
+            .arg("http://127.0.0.1:9/WasmEdge-0.14.1-Linux.tar.gz")
+            .arg("--path")
+            .arg(dir.path().join("install"))
+            .arg("--tmpdir")
+            .arg(dir.path().join("work"))
+            .output()
+            .unwrap()
+    };
+
+    let quiet = run(&["--quiet"], &[]);
+    assert_eq!(quiet.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&quiet.stderr);
+    assert!(stderr.contains("Diagnostics for bug reports"), "{stderr}");
+    assert!(stderr.contains("HEAD http://127.0.0.1:9/"), "{stderr}");
+
+    let json_lines = run(&[], &["--json-lines"]);
+    assert_eq!(json_lines.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&json_lines.stderr);
+    assert!(!stderr.contains("Diagnostics for bug reports"), "{stderr}");
+    let events = String::from_utf8(json_lines.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    let [.., diagnostics, error] = &events[..] else {
+        panic!("{events:?}");
+    };
+    assert_eq!(diagnostics["event"], "diagnostics");
+    let report = diagnostics["report"].as_str().unwrap();
+    assert!(report.contains("HEAD http://127.0.0.1:9/"), "{report}");
+    assert_eq!(error["event"], "error");
+}
+
+#[test]
+fn test_targets_lists_values_install_accepts() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .arg("targets")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(
+        lines.next().unwrap().split_whitespace().collect::<Vec<_>>(),
+        ["OS", "ARCH", "LIBC", "LINKAGE"]
+    );
+
+    let rows = lines
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert!(rows.contains(&vec!["linux", "aarch64", "glibc", "static"]));
+    for row in &rows {
+        let args = parse_install(&["--os", row[0], "--arch", row[1]]);
+        assert!(args.is_ok(), "{row:?} is not accepted by install");
+    }
+    assert!(!rows.iter().any(|row| row[..2] == ["windows", "aarch64"]));
+}
+
+#[test]
+fn test_from_env_fills_in_options_not_given_as_flags() {
+    let env = |name: &str| {
+        let value = match name {
+            "WASMEDGEUP_VERSION" => "0.14.1",
+            "WASMEDGEUP_PATH" => "/opt/wasmedge",
+            "WASMEDGEUP_OS" => "Linux",
+            "WASMEDGEUP_ARCH" => "arm64",
+            "WASMEDGEUP_SETUP_PATH" => "0",
+            "WASMEDGEUP_PREPEND_PATH" => "yes",
+            "WASMEDGEUP_PREFER_STATIC" => "",
+            "WASMEDGEUP_MIRROR" => "https://mirror.example/wasmedge/",
+            _ => return None,
+        };
+        Some(value.to_string())
+    };
+    let parse = |args: &[&str]| match Cli::try_parse_from(
+        ["wasmedgeup", "install", "--from-env"].iter().chain(args),
+    )
+    .unwrap()
+    .commands
+    {
+        Some(Commands::Install(args)) => args,
+        other => panic!("expected install command, got {other:?}"),
+    };
+
+    let mut args = parse(&[]);
+    let client = args.apply_env(WasmEdgeApiClient::new(), env).unwrap();
+    assert_eq!(args.version, "0.14.1");
+    assert_eq!(args.path, Some(PathBuf::from("/opt/wasmedge")));
+    assert_eq!(args.os, Some(TargetOS::Linux));
+    assert_eq!(args.arch, Some(TargetArch::Aarch64));
+    assert!(!args.should_setup_path());
+    assert!(args.prepend_path);
+    assert!(!args.prefer_static);
+    assert_eq!(client.release_base_url, "https://mirror.example/wasmedge");
+
+    let mut args = parse(&["0.13.5", "--prefix", "/usr/local", "--arch", "x86_64"]);
+    args.apply_env(WasmEdgeApiClient::new(), env).unwrap();
+    assert_eq!(args.version, "0.13.5");
+    assert_eq!(args.path, None);
+    assert_eq!(args.arch, Some(TargetArch::X86_64));
+
+    let mut args = parse(&[]);
+    let repo: RepoSlug = "example/WasmEdge".parse().unwrap();
+    let client = WasmEdgeApiClient::new().with_repo(repo.clone());
+    let client = args.apply_env(client, env).unwrap();
+    assert_eq!(client.release_base_url, repo.release_base_url());
+
+    let mut args = parse(&[]);
+    args.apply_env(WasmEdgeApiClient::new(), |_| None).unwrap();
+    assert_eq!(args.version, "latest");
+
+    let mut args = parse(&[]);
+    let err = args
+        .apply_env(WasmEdgeApiClient::new(), |name| {
+            (name == "WASMEDGEUP_SETUP_PATH").then(|| "maybe".to_string())
+        })
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("WASMEDGEUP_SETUP_PATH=maybe"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_verify_signature_requires_key() {
+    assert!(parse_install(&["--verify-signature"]).is_err());
+    let args = parse_install(&["--verify-signature", "--signature-key", "key.asc"]).unwrap();
+    assert!(args.verify_signature);
+    assert!(parse_install(&[
+        "--verify-signature",
+        "--signature-key",
+        "key.asc",
+        "--no-verify"
+    ])
+    .is_err());
+}
+
+#[tokio::test]
+async fn test_list_window_requires_remote_and_an_ordered_range() {
+    use wasmedgeup::{
+        cli::{CommandContext, CommandExecutor},
+        error::Error,
+    };
+
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(["wasmedgeup", "list"].iter().chain(args))
+            .map(|cli| cli.commands.unwrap())
+    };
+    assert!(parse(&["--remote", "--since", "0.13.0", "--until", "0.14.1"]).is_ok());
+    assert!(parse(&["--remote", "--until", "0.14"]).is_ok());
+    assert!(parse(&["--since", "0.13.0"]).is_err());
+    assert!(parse(&["--remote", "--since", "latest"]).is_err());
+
+    let list = parse(&["--remote", "--since", "0.15", "--until", "0.14.1"]).unwrap();
+    let err = list.execute(CommandContext::default()).await.unwrap_err();
+    assert!(matches!(err, Error::EmptyVersionWindow { .. }), "{err:?}");
+    assert_eq!(
+        err.to_string(),
+        "--since 0.15 is newer than --until 0.14.1, so no version can match"
+    );
+}
@@ -3,9 +3,10 @@ use sha2::{Digest, Sha256};
 use std::io::{Read, Seek, SeekFrom, Write};
 use tempfile::NamedTempFile;
 use wasmedgeup::{
-    api::{latest_installed_version, Asset, WasmEdgeApiClient},
+    api::{latest_installed_version, Asset, Linkage, WasmEdgeApiClient},
     commands::install::InstallArgs,
     error::Error,
+    target::{TargetArch, TargetOS},
 };
 
 #[tokio::test]
@@ -28,6 +29,12 @@ async fn test_checksum_verification() {
 
     assert!(verify_result.is_ok(), "Checksum verification failed");
 
+    temp_file.seek(SeekFrom::Start(0)).unwrap();
+    let upper = checksum.to_ascii_uppercase();
+    let verify_result =
+        WasmEdgeApiClient::verify_file_checksum(temp_file.as_file_mut(), &upper).await;
+    assert!(verify_result.is_ok(), "Uppercase checksum did not verify");
+
     let mut temp_file = NamedTempFile::new().unwrap();
     temp_file.write_all(b"different data").unwrap();
     temp_file.seek(SeekFrom::Start(0)).unwrap();
@@ -82,13 +89,12 @@ fn test_latest_installed_version_prerelease() {
 #[tokio::test]
 async fn test_get_release_checksum() {
     let client = WasmEdgeApiClient::default();
-    let version = client.latest_release().unwrap();
+    let version = client.latest_release().await.unwrap();
     let mut args = InstallArgs {
         version: "latest".to_string(),
         path: None,
         tmpdir: None,
-        os: None,
-        arch: None,
+        ..Default::default()
     };
     let os = args.os.get_or_insert_default();
     let arch = args.arch.get_or_insert_default();
@@ -113,3 +119,150 @@ async fn test_get_release_checksum() {
     let result = client.get_release_checksum(&invalid_version, &asset).await;
     assert!(matches!(result, Err(Error::ChecksumNotFound { .. })));
 }
+
+#[test]
+fn test_asset_names_and_urls() {
+    let version = Version::parse("0.14.1").unwrap();
+    let asset = Asset::new(&version, &TargetOS::Linux, &TargetArch::Aarch64);
+    assert_eq!(
+        asset.archive_name,
+        "WasmEdge-0.14.1-manylinux2014_aarch64.tar.gz"
+    );
+    assert_eq!(asset.install_name, "WasmEdge-0.14.1-Linux");
+    assert_eq!(
+        asset.url().unwrap().as_str(),
+        "https://github.com/WasmEdge/WasmEdge/releases/download/0.14.1/WasmEdge-0.14.1-manylinux2014_aarch64.tar.gz"
+    );
+
+    let base = "https://mirror.example/wasmedge/";
+    let asset = Asset::with_linkage(
+        &version,
+        &TargetOS::Ubuntu,
+        &TargetArch::X86_64,
+        Linkage::Static,
+    )
+    .unwrap();
+    assert_eq!(
+        asset.download_url(base).unwrap().as_str(),
+        "https://mirror.example/wasmedge/0.14.1/WasmEdge-0.14.1-manylinux2014_x86_64_static.tar.gz"
+    );
+    assert_eq!(
+        asset.checksum_url(base).unwrap().as_str(),
+        "https://mirror.example/wasmedge/0.14.1/SHA256SUM"
+    );
+    assert_eq!(
+        asset.checksum_signature_url(base).unwrap().as_str(),
+        "https://mirror.example/wasmedge/0.14.1/SHA256SUM.asc"
+    );
+    assert_eq!(
+        asset.checksum_url(base).unwrap(),
+        WasmEdgeApiClient::default()
+            .with_release_base_url(base)
+            .checksum_url(&version)
+            .unwrap()
+    );
+
+    let windows = Asset::new(&version, &TargetOS::Windows, &TargetArch::X86_64);
+    assert_eq!(windows.archive_name, "WasmEdge-0.14.1-windows.zip");
+    assert_eq!(
+        Asset::with_linkage(
+            &version,
+            &TargetOS::Windows,
+            &TargetArch::X86_64,
+            Linkage::Static
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_close_versions() {
+    use wasmedgeup::api::close_versions;
+
+    let releases: Vec<Version> = ["0.15.0", "0.14.1", "0.14.0", "0.13.5", "0.4.1"]
+        .iter()
+        .map(|v| Version::parse(v).unwrap())
+        .collect();
+    let close = |v: &str| close_versions(&Version::parse(v).unwrap(), &releases);
+    assert_eq!(close("0.41.1"), ["0.4.1", "0.14.1"]);
+    assert_eq!(close("0.14.2"), ["0.14.1", "0.14.0", "0.15.0"]);
+    assert!(close("2.0.0").is_empty());
+
+    let err = Error::VersionNotPublished {
+        version: "0.14.2".to_string(),
+        close: close("0.14.2"),
+    };
+    assert!(err
+        .to_string()
+        .ends_with("; did you mean 0.14.1, 0.14.0 or 0.15.0?"));
+}
+
+#[test]
+fn test_version_window() {
+    use wasmedgeup::api::{in_version_window, VersionBound};
+
+    let releases: Vec<Version> = [
+        "0.15.0",
+        "0.15.0-rc.1",
+        "0.14.1",
+        "0.14.0",
+        "0.13.5",
+        "0.13.0",
+        "0.12.1",
+    ]
+    .iter()
+    .map(|v| Version::parse(v).unwrap())
+    .collect();
+    let window = |since: Option<&str>, until: Option<&str>| {
+        let since = since.map(|b| b.parse::<VersionBound>().unwrap());
+        let until = until.map(|b| b.parse::<VersionBound>().unwrap());
+        releases
+            .iter()
+            .filter(|v| in_version_window(v, since.as_ref(), until.as_ref()))
+            .map(Version::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        window(Some("0.13.0"), Some("v0.14.1")),
+        ["0.14.1", "0.14.0", "0.13.5", "0.13.0"]
+    );
+    assert_eq!(
+        window(Some("0.14.1"), None),
+        ["0.15.0", "0.15.0-rc.1", "0.14.1"]
+    );
+    assert_eq!(window(None, Some("0.13")), ["0.13.5", "0.13.0", "0.12.1"]);
+    assert_eq!(window(Some("0.15"), None), ["0.15.0", "0.15.0-rc.1"]);
+    assert_eq!(
+        window(None, Some("0.15.0-rc.1")).first().unwrap(),
+        "0.15.0-rc.1"
+    );
+
+    assert_eq!("0.14".parse::<VersionBound>().unwrap().to_string(), "0.14");
+    assert!("0.14.x".parse::<VersionBound>().is_err());
+    assert!("latest".parse::<VersionBound>().is_err());
+}
+
+#[test]
+fn test_verbose_http_redacts_credentials() {
+    use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+    use wasmedgeup::api::request_log::{redact_headers, redact_url};
+
+    let url = url::Url::parse(
+        "https://user:pw@mirror.example/WasmEdge.tar.gz?X-Amz-Signature=abc&response-content-type=gzip",
+    )
+    .unwrap();
+    let redacted = redact_url(&url);
+    assert!(
+        !redacted.contains("pw@") && !redacted.contains("abc"),
+        "{redacted}"
+    );
+    assert!(redacted.contains("user:") && redacted.contains("response-content-type=gzip"));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer ghp_secret"));
+    headers.insert(USER_AGENT, HeaderValue::from_static("wasmedgeup"));
+    assert_eq!(
+        redact_headers(&headers),
+        "authorization: <redacted>, user-agent: wasmedgeup"
+    );
+}
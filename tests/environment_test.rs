@@ -0,0 +1,145 @@
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{cache::DownloadCache, commands::default_path, environment::Environment};
+
+#[test]
+#[serial]
+fn test_default_path_follows_environment() {
+    let home = tempdir().unwrap();
+    let env = Environment::new()
+        .with_home(home.path())
+        .without_var("XDG_DATA_HOME");
+    let _active = env.clone().activate();
+    assert_eq!(default_path().unwrap(), home.path().join(".wasmedge"));
+
+    let _active = env.clone().with_var("XDG_DATA_HOME", "relative").activate();
+    assert_eq!(default_path().unwrap(), home.path().join(".wasmedge"));
+
+    let data_home = home.path().join("data");
+    // The XDG layout is only used with `--xdg`, never just because `XDG_DATA_HOME` is set.
+    let _active = env.with_var("XDG_DATA_HOME", &data_home).activate();
+    assert_eq!(default_path().unwrap(), home.path().join(".wasmedge"));
+}
+
+#[test]
+#[serial]
+fn test_activated_environment_is_restored() {
+    let home = tempdir().unwrap();
+    let before = Environment::current();
+    {
+        let _active = Environment::new()
+            .with_home(home.path())
+            .with_temp_dir(home.path().join("tmp"))
+            .with_var("WASMEDGEUP_TEST_VAR", "set")
+            .activate();
+        let current = Environment::current();
+        assert_eq!(current.home_dir(), Some(home.path().to_path_buf()));
+        assert_eq!(current.temp_dir(), home.path().join("tmp"));
+        assert_eq!(current.var("WASMEDGEUP_TEST_VAR").as_deref(), Some("set"));
+    }
+    assert_eq!(Environment::current(), before);
+    assert_eq!(Environment::current().var("WASMEDGEUP_TEST_VAR"), None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial]
+fn test_download_cache_lives_under_environment_home() {
+    let home = tempdir().unwrap();
+    let _active = Environment::new()
+        .with_home(home.path())
+        .without_var("XDG_CACHE_HOME")
+        .activate();
+    let cache = DownloadCache::default_location().unwrap();
+    assert_eq!(
+        cache.root(),
+        home.path().join(".cache/wasmedgeup/downloads")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_setup_and_uninstall_path_in_fake_home() {
+    use wasmedgeup::shell_utils;
+
+    let home = tempdir().unwrap();
+    let install_dir = home.path().join(".wasmedge");
+    std::fs::create_dir_all(&install_dir).unwrap();
+    std::fs::write(home.path().join(".bashrc"), "alias ll='ls -l'\n").unwrap();
+    // Only the POSIX profile, bash (from its rc file) and zsh (the login shell) are detected.
+    let _active = Environment::new()
+        .with_home(home.path())
+        .with_var("SHELL", "/bin/zsh")
+        .with_var("PATH", "")
+        .without_var("ZDOTDIR")
+        .without_var("XDG_CONFIG_HOME")
+        .activate();
+
+    shell_utils::setup_path(&install_dir).unwrap();
+    let source_line = format!(". \"{}/env\"", install_dir.display());
+    for rc in [".profile", ".bashrc", ".zshenv"] {
+        let content = std::fs::read_to_string(home.path().join(rc)).unwrap();
+        assert!(content.contains(&source_line), "{rc}: {content}");
+    }
+    assert!(install_dir.join("env").is_file());
+    assert!(!home.path().join(".config").exists());
+
+    shell_utils::uninstall_path(&install_dir).unwrap();
+    for rc in [".profile", ".bashrc", ".zshenv"] {
+        let content = std::fs::read_to_string(home.path().join(rc)).unwrap();
+        assert!(!content.contains(&source_line), "{rc}: {content}");
+    }
+    assert_eq!(
+        std::fs::read_to_string(home.path().join(".bashrc")).unwrap(),
+        "alias ll='ls -l'\n"
+    );
+    assert!(!install_dir.join("env").exists());
+}
+
+#[test]
+fn test_env_file_sets_only_unset_allowed_vars() {
+    use wasmedgeup::error::Error;
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(
+        &path,
+        "# proxy and token for local development\n\
+         \n\
+         export HTTPS_PROXY=\"http://proxy.example:3128\"\n\
+         GITHUB_TOKEN='ghp_secret' \n\
+         no_proxy=localhost,.internal # direct\n\
+         WASMEDGEUP_MIRROR=https://file.example/releases\n\
+         PATH=/should/not/change\n",
+    )
+    .unwrap();
+
+    let env = Environment::new()
+        .without_var("HTTPS_PROXY")
+        .without_var("GITHUB_TOKEN")
+        .without_var("no_proxy")
+        .with_var("WASMEDGEUP_MIRROR", "https://shell.example/releases")
+        .with_env_file(&path)
+        .unwrap();
+    assert_eq!(
+        env.var("HTTPS_PROXY").as_deref(),
+        Some("http://proxy.example:3128")
+    );
+    assert_eq!(env.var("GITHUB_TOKEN").as_deref(), Some("ghp_secret"));
+    assert_eq!(env.var("no_proxy").as_deref(), Some("localhost,.internal"));
+    assert_eq!(
+        env.var("WASMEDGEUP_MIRROR").as_deref(),
+        Some("https://shell.example/releases")
+    );
+    assert!(!env.has_override("PATH"));
+
+    std::fs::write(&path, "GITHUB_TOKEN=ok\nGITHUB_TOKEN\n").unwrap();
+    let err = Environment::new().with_env_file(&path).unwrap_err();
+    assert!(
+        matches!(err, Error::InvalidEnvFile { line: 2, .. }),
+        "{err:?}"
+    );
+    std::fs::write(&path, "GITHUB_TOKEN=\"unterminated\n").unwrap();
+    assert!(Environment::new().with_env_file(&path).is_err());
+}
@@ -0,0 +1,262 @@
+#![cfg(unix)]
+
+use std::{
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use semver::Version;
+use tempfile::tempdir;
+use wasmedgeup::{
+    error::Error,
+    fs::{copy_tree, extract_archive, extract_archive_matching, ArchiveFormat, CopyFilter},
+};
+
+mod mock_server;
+use mock_server::linux_archive;
+
+fn archive_file() -> std::fs::File {
+    let archive = linux_archive(&Version::new(0, 14, 1));
+    let mut file = tempfile::tempfile().unwrap();
+    std::io::Write::write_all(&mut file, &archive).unwrap();
+    file
+}
+
+#[tokio::test]
+async fn test_extract_archive_writes_everything() {
+    let dest = tempdir().unwrap();
+    extract_archive(&mut archive_file(), dest.path())
+        .await
+        .unwrap();
+
+    let root = dest.path().join("WasmEdge-0.14.1-Linux");
+    assert!(root.join("bin").join("wasmedge").is_file());
+    assert!(root.join("lib64").join("libwasmedge.so").is_file());
+    assert!(root
+        .join("include")
+        .join("wasmedge")
+        .join("wasmedge.h")
+        .is_file());
+}
+
+#[tokio::test]
+async fn test_extract_archive_matching_skips_other_entries() {
+    let dest = tempdir().unwrap();
+    let bin_only = |path: &Path| {
+        path.components()
+            .nth(1)
+            .is_some_and(|c| c.as_os_str() == "bin")
+    };
+    extract_archive_matching(&mut archive_file(), dest.path(), Some(&bin_only))
+        .await
+        .unwrap();
+
+    let root = dest.path().join("WasmEdge-0.14.1-Linux");
+    assert!(root.join("bin").join("wasmedge").is_file());
+    assert!(!root.join("lib64").exists());
+    assert!(!root.join("include").exists());
+}
+
+#[test]
+fn test_copy_filter_matches_entries_below_wrappers() {
+    let filter = CopyFilter {
+        include: vec!["bin/*".parse().unwrap(), "include/**".parse().unwrap()],
+        exclude: vec!["include/**/*.h".parse().unwrap()],
+        ..Default::default()
+    };
+    let matches = |path: &str, strip| filter.matches_entry(Path::new(path), strip);
+
+    assert!(matches("WasmEdge-0.14.1-Linux/bin/wasmedge", None));
+    assert!(matches("out/WasmEdge-0.14.1-Linux/bin/wasmedge", None));
+    assert!(!matches("WasmEdge-0.14.1-Linux/lib64/libwasmedge.so", None));
+    assert!(!matches(
+        "WasmEdge-0.14.1-Linux/include/wasmedge/wasmedge.h",
+        None
+    ));
+    // The directories the layout is told from are always extracted.
+    assert!(matches("WasmEdge-0.14.1-Linux", None));
+    assert!(matches("WasmEdge-0.14.1-Linux/lib64", None));
+    assert!(matches("WasmEdge-0.14.1-Linux/README.md", None));
+
+    assert!(matches("a/b/bin/wasmedge", Some(2)));
+    assert!(!matches("a/lib64/libwasmedge.so", Some(1)));
+    assert!(matches("bin/wasmedge", Some(0)));
+}
+
+#[tokio::test]
+async fn test_extract_archive_matching_with_copy_filter() {
+    let dest = tempdir().unwrap();
+    let filter = CopyFilter {
+        include: vec!["lib64/*".parse().unwrap()],
+        ..Default::default()
+    };
+    let selected = |path: &Path| filter.matches_entry(path, None);
+    let written = extract_archive_matching(&mut archive_file(), dest.path(), Some(&selected))
+        .await
+        .unwrap();
+
+    assert_eq!(written, 1);
+    let root = dest.path().join("WasmEdge-0.14.1-Linux");
+    assert!(root.join("lib64").join("libwasmedge.so").is_file());
+    assert!(!root.join("bin").exists());
+    assert!(!root.join("include").exists());
+}
+
+#[tokio::test]
+async fn test_extract_and_copy_preserve_mtimes() {
+    // The test archive's entries all carry a timestamp of (close to) the Unix epoch.
+    let is_archive_time = |path: &Path| {
+        let mtime = std::fs::metadata(path).unwrap().modified().unwrap();
+        mtime < UNIX_EPOCH + Duration::from_secs(86_400)
+    };
+
+    let dest = tempdir().unwrap();
+    extract_archive(&mut archive_file(), dest.path())
+        .await
+        .unwrap();
+    let root = dest.path().join("WasmEdge-0.14.1-Linux");
+    assert!(is_archive_time(&root.join("include/wasmedge/wasmedge.h")));
+
+    let install = tempdir().unwrap();
+    copy_tree(&root, install.path()).await.unwrap();
+    assert!(is_archive_time(
+        &install.path().join("include/wasmedge/wasmedge.h")
+    ));
+    assert!(is_archive_time(&install.path().join("bin/wasmedge")));
+}
+
+#[tokio::test]
+async fn test_unsupported_compression_is_named() {
+    let mut zstd = [0u8; 64];
+    zstd[..4].copy_from_slice(&[0x28, 0xb5, 0x2f, 0xfd]);
+    assert_eq!(ArchiveFormat::detect(&zstd), Some(ArchiveFormat::Zstd));
+
+    let mut file = tempfile::tempfile().unwrap();
+    std::io::Write::write_all(&mut file, &zstd).unwrap();
+    let dest = tempdir().unwrap();
+    let result = extract_archive(&mut file, dest.path()).await;
+    match result {
+        Err(e @ Error::UnsupportedArchiveFormat { .. }) => {
+            assert!(e.to_string().contains("zstd"), "{e}");
+        }
+        other => panic!("expected UnsupportedArchiveFormat, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_extract_bzip2_tar() {
+    let mut builder = tar::Builder::new(Vec::new());
+    let contents = b"#!/bin/sh\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(
+            &mut header,
+            "WasmEdge-0.14.1-Linux/bin/wasmedge",
+            &contents[..],
+        )
+        .unwrap();
+    let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::best());
+    std::io::Write::write_all(&mut encoder, &builder.into_inner().unwrap()).unwrap();
+    let tar_bz2 = encoder.finish().unwrap();
+    assert_eq!(ArchiveFormat::detect(&tar_bz2), Some(ArchiveFormat::Bzip2));
+
+    let mut file = tempfile::tempfile().unwrap();
+    std::io::Write::write_all(&mut file, &tar_bz2).unwrap();
+    let dest = tempdir().unwrap();
+    extract_archive(&mut file, dest.path()).await.unwrap();
+    let wasmedge = dest.path().join("WasmEdge-0.14.1-Linux/bin/wasmedge");
+    assert_eq!(std::fs::read(wasmedge).unwrap(), contents);
+}
+
+#[tokio::test]
+async fn test_extract_uncompressed_tar() {
+    let mut builder = tar::Builder::new(Vec::new());
+    let contents = b"#!/bin/sh\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "bin/wasmedge", &contents[..])
+        .unwrap();
+    let tarball = builder.into_inner().unwrap();
+    assert_eq!(ArchiveFormat::detect(&tarball), Some(ArchiveFormat::Tar));
+
+    let mut file = tempfile::tempfile().unwrap();
+    std::io::Write::write_all(&mut file, &tarball).unwrap();
+    let dest = tempdir().unwrap();
+    extract_archive(&mut file, dest.path()).await.unwrap();
+    assert!(dest.path().join("bin").join("wasmedge").is_file());
+}
+
+#[tokio::test]
+async fn test_failed_extraction_removes_written_entries() {
+    let mut builder = tar::Builder::new(Vec::new());
+    for name in [
+        "WasmEdge-0.14.1-Linux/bin/wasmedge",
+        "WasmEdge-0.14.1-Linux/lib64/libwasmedge.so",
+    ] {
+        let contents = b"content\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, &contents[..])
+            .unwrap();
+    }
+    let mut tarball = builder.into_inner().unwrap();
+    // Break the checksum of the second header, after the first entry has been written.
+    tarball[1024 + 148] ^= 0x01;
+
+    let mut file = tempfile::tempfile().unwrap();
+    std::io::Write::write_all(&mut file, &tarball).unwrap();
+    let dest = tempdir().unwrap();
+    std::fs::write(dest.path().join("download.tar"), b"kept").unwrap();
+    let result = extract_archive(&mut file, dest.path()).await;
+    assert!(matches!(result, Err(Error::Extract { .. })), "{result:?}");
+
+    let left = std::fs::read_dir(dest.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect::<Vec<_>>();
+    assert_eq!(left, ["download.tar"]);
+}
+
+#[tokio::test]
+async fn test_archive_without_files_is_an_error() {
+    let tar_gz = |directories: &[&str]| {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for name in directories {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_size(0);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, std::io::empty())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    };
+
+    for archive in [
+        tar_gz(&[]),
+        tar_gz(&["WasmEdge-0.14.1-Linux/", "WasmEdge-0.14.1-Linux/bin/"]),
+    ] {
+        let mut file = tempfile::tempfile().unwrap();
+        std::io::Write::write_all(&mut file, &archive).unwrap();
+        let dest = tempdir().unwrap();
+        let result = extract_archive(&mut file, dest.path()).await;
+        assert!(
+            matches!(result, Err(Error::NoInstallableFiles { .. })),
+            "{result:?}"
+        );
+        let e = result.unwrap_err().to_string();
+        assert!(e.contains("no installable files"), "{e}");
+    }
+}
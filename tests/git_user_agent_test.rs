@@ -0,0 +1,35 @@
+#![cfg(unix)]
+// libgit2's User-Agent is process-wide and only set once, so this test has a binary of its own.
+
+mod mock_server;
+use mock_server::MockRelease;
+use wasmedgeup::api::ResolveVia;
+
+#[tokio::test]
+async fn test_git_listing_carries_user_agent() {
+    let mock = MockRelease::start().await;
+    mock.mount_tags(&["0.14.1"]).await;
+
+    let client = mock
+        .client()
+        .with_resolve_via(ResolveVia::Git)
+        .with_user_agent("ci-bot/1.0");
+    client.latest_release().await.unwrap();
+    // A second listing, e.g. another version of `install --multi`, keeps the same agent.
+    client.latest_release().await.unwrap();
+
+    let requests = mock.server.received_requests().await.unwrap();
+    let git = requests
+        .iter()
+        .filter(|r| r.url.path().starts_with("/WasmEdge.git"))
+        .collect::<Vec<_>>();
+    assert!(!git.is_empty(), "{requests:?}");
+    for request in git {
+        let header = request.headers["user-agent"].to_str().unwrap();
+        assert!(
+            header.contains("ci-bot/1.0"),
+            "{} sent {header}",
+            request.url
+        );
+    }
+}
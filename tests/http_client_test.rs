@@ -0,0 +1,368 @@
+#![cfg(unix)]
+
+use clap::Parser;
+use semver::Version;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    api::{Asset, ReleasesFilter, ResolveVia, WasmEdgeApiClient},
+    cli::Cli,
+    error::Error,
+    http::HttpClientConfig,
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{
+    expiring_redirect_server, flaky_server, linux_archive, linux_asset, pooled_server, sha256_hex,
+    MockRelease, VERSION,
+};
+use test_utils::setup_test_environment;
+
+async fn download_and_verify(base_url: &str, asset: &Asset, checksum: &str) {
+    let tmpdir = tempdir().unwrap();
+    let client = WasmEdgeApiClient::default()
+        .with_retries(2)
+        .with_release_base_url(format!("{base_url}/releases/download"));
+    let downloaded = client
+        .download_asset(asset, tmpdir.path(), true)
+        .await
+        .expect("download failed");
+    let mut file = downloaded.reopen().unwrap();
+    WasmEdgeApiClient::verify_file_checksum(&mut file, checksum)
+        .await
+        .expect("downloaded archive is corrupt");
+}
+
+#[tokio::test]
+async fn test_download_without_head_support() {
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let asset_path = format!("/releases/download/{VERSION}/{}", asset.archive_name);
+    Mock::given(method("HEAD"))
+        .and(path(asset_path.clone()))
+        .respond_with(ResponseTemplate::new(405))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(asset_path))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(archive.clone()))
+        .mount(&mock.server)
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let downloaded = mock
+        .client()
+        .download_asset(&asset, tmpdir.path(), true)
+        .await
+        .expect("download failed");
+    assert_eq!(std::fs::read(downloaded.path()).unwrap(), archive);
+}
+
+#[tokio::test]
+async fn test_download_resumes_with_range_request() {
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    let base_url = flaky_server(archive, true).await;
+
+    download_and_verify(&base_url, &asset, &checksum).await;
+}
+
+#[tokio::test]
+async fn test_download_resumes_after_the_presigned_url_expired() {
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    let base_url = expiring_redirect_server(archive).await;
+
+    download_and_verify(&base_url, &asset, &checksum).await;
+}
+
+#[tokio::test]
+async fn test_download_restarts_without_range_support() {
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    let base_url = flaky_server(archive, false).await;
+
+    download_and_verify(&base_url, &asset, &checksum).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_requests_carry_user_agent() {
+    for (user_agent, expected) in [(None, "wasmedgeup/"), (Some("ci-bot/1.0"), "ci-bot/1.0")] {
+        let mock = MockRelease::start().await;
+        mock.mount_tags(&[VERSION]).await;
+        let (asset, _checksum) = mock.mount_linux_release().await;
+
+        let mut client = mock.client();
+        if let Some(user_agent) = user_agent {
+            client = client.with_user_agent(user_agent);
+        }
+        let version = client.latest_release().await.unwrap();
+        client.get_release_checksum(&version, &asset).await.unwrap();
+        let tmpdir = tempdir().unwrap();
+        client
+            .download_asset(&asset, tmpdir.path(), true)
+            .await
+            .unwrap();
+
+        let requests = mock.server.received_requests().await.unwrap();
+        // The ref listing over git smart-HTTP, the checksum file and the asset. libgit2 keeps
+        // the first User-Agent of the process, so git_user_agent_test.rs covers the listing.
+        assert!(requests.len() >= 3, "{requests:?}");
+        for request in requests
+            .into_iter()
+            .filter(|r| !r.url.path().starts_with("/WasmEdge.git"))
+        {
+            let header = request.headers["user-agent"].to_str().unwrap();
+            assert!(header.contains(expected), "{} sent {header}", request.url);
+        }
+    }
+}
+
+/// Self-signed CA certificate, only used to check that PEM files are loaded.
+const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBkjCCATegAwIBAgIUEkhnePB6bqmjfYyzF4mCagQaHVMwCgYIKoZIzj0EAwIw
+HTEbMBkGA1UEAwwSd2FzbWVkZ2V1cCB0ZXN0IENBMCAXDTI2MTAxNDAzMzQ1M1oY
+DzIxMjYwOTIwMDMzNDUzWjAdMRswGQYDVQQDDBJ3YXNtZWRnZXVwIHRlc3QgQ0Ew
+WTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQ88U1bJKvROj7/bRGEt2l+nN0suMxN
+WHT8GiVAXndZu6q56LRI/BdGrj/IuB+WWDH4JolhiKVDHhdon5TVa24vo1MwUTAd
+BgNVHQ4EFgQUzQAH9MDZCRkWMXKv2zpS3AstHqAwHwYDVR0jBBgwFoAUzQAH9MDZ
+CRkWMXKv2zpS3AstHqAwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNJADBG
+AiEAuL22MU7q1XCoJN/Kgkdth8/w46apH5FAHpSMHdiHCDECIQCwZWR44IAG2kvy
+dEKCPc06VjHeWkrQzq54tF17YMVwSg==
+-----END CERTIFICATE-----
+";
+
+#[tokio::test]
+async fn test_cacert_is_loaded_for_requests() {
+    let mock = MockRelease::start().await;
+    let (asset, checksum) = mock.mount_linux_release().await;
+
+    let dir = tempdir().unwrap();
+    let good = dir.path().join("ca.pem");
+    std::fs::write(&good, TEST_CA_PEM).unwrap();
+    let client = mock.client().with_ca_cert(&good);
+    assert_eq!(
+        client
+            .get_release_checksum(&asset.version, &asset)
+            .await
+            .unwrap(),
+        checksum
+    );
+
+    for (name, contents) in [("empty.pem", ""), ("garbage.pem", "not a certificate")] {
+        let bad = dir.path().join(name);
+        std::fs::write(&bad, contents).unwrap();
+        let client = mock.client().with_ca_cert(&bad);
+        let result = client.get_release_checksum(&asset.version, &asset).await;
+        assert!(
+            matches!(&result, Err(Error::InvalidCaCert { path, .. }) if path.ends_with(name)),
+            "{result:?}"
+        );
+    }
+
+    let missing = mock.client().with_ca_cert(dir.path().join("missing.pem"));
+    let result = missing.get_release_checksum(&asset.version, &asset).await;
+    assert!(
+        matches!(result, Err(Error::InvalidCaCert { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_requests_of_one_run_share_a_connection() {
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let sums = format!("{}  {}\n", sha256_hex(&archive), asset.archive_name);
+    let listing = serde_json::json!([{ "tag_name": VERSION, "draft": false }]).to_string();
+    let download = |file: &str| format!("/releases/download/{VERSION}/{file}");
+    let (base_url, connections) = pooled_server(vec![
+        ("/api/releases".to_string(), listing.into_bytes()),
+        (download("SHA256SUM"), sums.into_bytes()),
+        (download(&asset.archive_name), archive),
+    ])
+    .await;
+
+    let client = WasmEdgeApiClient::default()
+        .with_retries(0)
+        .with_resolve_via(ResolveVia::Api)
+        .with_releases_api_url(format!("{base_url}/api/releases"))
+        .with_release_base_url(format!("{base_url}/releases/download"));
+    let tmpdir = tempdir().unwrap();
+
+    // A clone, as handed to each version of `install --multi`, shares the pool too.
+    let version = client.clone().latest_release().await.unwrap();
+    assert_eq!(version.to_string(), VERSION);
+    let checksum = client.get_release_checksum(&version, &asset).await.unwrap();
+    for _ in 0..2 {
+        let downloaded = client
+            .download_asset(&asset, tmpdir.path(), true)
+            .await
+            .unwrap();
+        let mut file = downloaded.reopen().unwrap();
+        WasmEdgeApiClient::verify_file_checksum(&mut file, &checksum)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        connections.load(std::sync::atomic::Ordering::SeqCst),
+        1,
+        "every request should reuse the pooled connection"
+    );
+}
+
+#[tokio::test]
+async fn test_redirects_drop_credentials_across_hosts() {
+    let origin = wiremock::MockServer::start().await;
+    let mirror = wiremock::MockServer::start().await;
+    let redirect = |to: String| ResponseTemplate::new(302).insert_header("Location", to);
+    Mock::given(path("/cross"))
+        .respond_with(redirect(format!("{}/asset", mirror.uri())))
+        .mount(&origin)
+        .await;
+    Mock::given(path("/same"))
+        .respond_with(redirect(format!("{}/asset", origin.uri())))
+        .mount(&origin)
+        .await;
+    Mock::given(path("/loop"))
+        .respond_with(redirect(format!("{}/loop", origin.uri())))
+        .mount(&origin)
+        .await;
+    for server in [&origin, &mirror] {
+        Mock::given(path("/asset"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(server)
+            .await;
+    }
+
+    let client = HttpClientConfig::new().build().unwrap();
+    let get = |url: String| client.get(url).bearer_auth("secret").send();
+    for route in ["cross", "same"] {
+        let response = get(format!("{}/{route}", origin.uri())).await.unwrap();
+        assert!(response.status().is_success());
+    }
+    let authorized = |requests: Vec<wiremock::Request>| {
+        requests
+            .iter()
+            .filter(|r| r.url.path() == "/asset")
+            .map(|r| r.headers.contains_key("authorization"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        authorized(mirror.received_requests().await.unwrap()),
+        [false]
+    );
+    assert_eq!(
+        authorized(origin.received_requests().await.unwrap()),
+        [true]
+    );
+
+    let result = get(format!("{}/loop", origin.uri())).await;
+    assert!(result.is_err_and(|e| e.is_redirect()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_verbose_http_logs_metadata_without_the_token() {
+    #[derive(Clone, Default)]
+    struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mock = MockRelease::start().await;
+    mock.mount_api_releases(&[("0.14.1", false)]).await;
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = mock
+        .client()
+        .with_resolve_via(ResolveVia::Api)
+        .with_github_token("ghp_secret")
+        .with_verbose_http(true);
+    assert_eq!(
+        client.latest_release().await.unwrap(),
+        Version::new(0, 14, 1)
+    );
+
+    let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert!(logs.contains("HTTP response"), "{logs}");
+    assert!(
+        logs.contains("/api/releases") && logs.contains("status=200"),
+        "{logs}"
+    );
+    assert!(logs.contains("content_length="), "{logs}");
+    assert!(logs.contains("authorization: <redacted>"), "{logs}");
+    assert!(!logs.contains("ghp_secret"), "{logs}");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_env_file_configures_token_mirror_and_proxy() {
+    use wasmedgeup::environment::Environment;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let uri = mock.server.uri();
+    Mock::given(method("GET"))
+        .and(path("/api/releases"))
+        .and(header("authorization", "Bearer ghp_from_file"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            { "tag_name": VERSION, "draft": false }
+        ])))
+        .mount(&mock.server)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let env_file = dir.path().join(".env");
+    std::fs::write(
+        &env_file,
+        format!("GITHUB_TOKEN=ghp_from_file\nWASMEDGEUP_MIRROR={uri}/mirror/\nHTTP_PROXY={uri}\n"),
+    )
+    .unwrap();
+    let _base = Environment::new()
+        .without_var("GITHUB_TOKEN")
+        .without_var("WASMEDGEUP_MIRROR")
+        .without_var("HTTP_PROXY")
+        .without_var("http_proxy")
+        .activate();
+    let cli = Cli::try_parse_from([
+        "wasmedgeup",
+        &format!("--env-file={}", env_file.display()),
+        "--resolve-via",
+        "api",
+        "list",
+    ])
+    .unwrap();
+    let _active = cli.environment().unwrap().activate();
+    let client = cli.context().client;
+    assert_eq!(client.release_base_url, format!("{uri}/mirror"));
+    assert!(!format!("{client:?}").contains("ghp_from_file"));
+
+    // The proxy is the mock server, so the listing arrives there whatever host it names.
+    let client = client.with_releases_api_url("http://releases.invalid/api/releases");
+    let releases = client.releases(ReleasesFilter::All, 10).await.unwrap();
+    assert_eq!(releases, [Version::parse(VERSION).unwrap()]);
+}
@@ -0,0 +1,206 @@
+#![cfg(unix)]
+
+use clap::Parser;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    cache::DownloadCache,
+    cli::{Cli, CommandContext, CommandExecutor},
+    commands::install::InstallArgs,
+    target::{TargetArch, TargetOS},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{
+    linux_archive, linux_archive_in, linux_asset, sha256_hex, tar_gz, MockRelease, VERSION,
+};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_refresh_bypasses_caches() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let (asset, _) = mock.mount_linux_release().await;
+
+    let tmpdir = tempdir().unwrap();
+    let args = InstallArgs {
+        version: VERSION.to_string(),
+        path: Some(tmpdir.path().join("wasmedge")),
+        tmpdir: Some(tmpdir.path().join("work")),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_setup_path: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client().with_refresh(true),
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("install failed");
+
+    let cache = DownloadCache::default_location()
+        .unwrap()
+        .with_source(&mock.client().release_base_url);
+    assert!(cache.lookup(&asset).await.is_none());
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(!requests.is_empty());
+    // libgit2 lists tags over smart-HTTP without the client's default headers.
+    let http_requests = requests
+        .iter()
+        .filter(|r| !r.url.path().contains("WasmEdge.git"));
+    for request in http_requests {
+        assert_eq!(
+            request.headers.get("cache-control").unwrap(),
+            "no-cache",
+            "{}",
+            request.url
+        );
+        assert_eq!(request.headers.get("pragma").unwrap(), "no-cache");
+    }
+
+    let cli = Cli::try_parse_from(["wasmedgeup", "--refresh", "list"]).unwrap();
+    assert!(cli.context().client.refresh);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_cache_does_not_mix_up_release_sources() {
+    let (_home, _home_path) = setup_test_environment();
+    let asset = linux_asset();
+    let root = format!("WasmEdge-{VERSION}-Linux");
+    let build =
+        |greeting: &'static [u8]| tar_gz([(format!("{root}/bin/wasmedge"), greeting, 0o755)]);
+    let upstream = MockRelease::start().await;
+    let upstream_archive = build(b"#!/bin/sh\necho upstream\n");
+    upstream
+        .mount_release(
+            VERSION,
+            &asset.archive_name,
+            upstream_archive.clone(),
+            &sha256_hex(&upstream_archive),
+        )
+        .await;
+    let fork = MockRelease::start().await;
+    let fork_archive = build(b"#!/bin/sh\necho fork\n");
+    fork.mount_release(
+        VERSION,
+        &asset.archive_name,
+        fork_archive.clone(),
+        &sha256_hex(&fork_archive),
+    )
+    .await;
+    assert_ne!(upstream_archive, fork_archive);
+
+    let tmpdir = tempdir().unwrap();
+    let install = |mock: &MockRelease| {
+        let args = InstallArgs {
+            version: VERSION.to_string(),
+            path: Some(tmpdir.path().join("wasmedge")),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_setup_path: true,
+            force: true,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    let installed = || std::fs::read(tmpdir.path().join("wasmedge/bin/wasmedge")).unwrap();
+
+    install(&upstream).await.expect("upstream install failed");
+    assert_eq!(installed(), b"#!/bin/sh\necho upstream\n");
+    // Same version and archive name, but from another source: its own archive is downloaded.
+    install(&fork).await.expect("fork install failed");
+    assert_eq!(installed(), b"#!/bin/sh\necho fork\n");
+    let fork_downloads = fork.downloads(&asset.archive_name).await;
+    assert_eq!(fork_downloads, 1);
+
+    // Both stay cached, each under its own source.
+    upstream.server.reset().await;
+    install(&upstream)
+        .await
+        .expect("cached upstream install failed");
+    assert_eq!(installed(), b"#!/bin/sh\necho upstream\n");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_expired_cache_entries_are_checked_against_the_release() {
+    use wasmedgeup::units::Ttl;
+
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let install = |ttl: &str| {
+        let args = InstallArgs {
+            version: VERSION.to_string(),
+            path: Some(tmpdir.path().join("wasmedge")),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_setup_path: true,
+            force: true,
+            checksum_cache_ttl: Some(ttl.parse::<Ttl>().unwrap()),
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    let fetched = |requests: &[wiremock::Request], file: &str| {
+        requests
+            .iter()
+            .filter(|r| r.method.as_str() == "GET" && r.url.path().ends_with(file))
+            .count()
+    };
+
+    install("never").await.expect("first install failed");
+    let cache = DownloadCache::default_location()
+        .unwrap()
+        .with_source(&mock.client().release_base_url);
+    assert_eq!(cache.lookup(&asset).await.unwrap().checksum, checksum);
+
+    // Fresh within the lifetime: neither the checksum file nor the archive is fetched.
+    mock.server.reset().await;
+    install("1d").await.expect("cached install failed");
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(fetched(&requests, "SHA256SUM"), 0);
+    assert_eq!(fetched(&requests, &asset.archive_name), 0);
+
+    // Expired but unchanged: only the checksum file is fetched, and the entry is renewed.
+    mock.server.reset().await;
+    mock.mount_release(VERSION, &asset.archive_name, Vec::new(), &checksum)
+        .await;
+    install("0").await.expect("revalidated install failed");
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(fetched(&requests, "SHA256SUM"), 1);
+    assert_eq!(fetched(&requests, &asset.archive_name), 0);
+
+    // Republished with different files: the new archive replaces the cached one.
+    let republished = linux_archive_in(&format!("WasmEdge-{VERSION}-Linux-rebuilt"));
+    let new_checksum = sha256_hex(&republished);
+    mock.server.reset().await;
+    mock.mount_release(VERSION, &asset.archive_name, republished, &new_checksum)
+        .await;
+    install("0")
+        .await
+        .expect("install of the republished archive failed");
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(fetched(&requests, "SHA256SUM"), 1);
+    assert_eq!(fetched(&requests, &asset.archive_name), 1);
+    assert_eq!(cache.lookup(&asset).await.unwrap().checksum, new_checksum);
+}
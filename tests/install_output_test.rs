@@ -0,0 +1,152 @@
+#![cfg(unix)]
+
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{commands::install::InstallArgs, error::Error};
+use wiremock::ResponseTemplate;
+
+mod mock_server;
+mod test_utils;
+use mock_server::{install_with, linux_archive, linux_asset, sha256_hex, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_install_writes_sbom() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let (asset, checksum) = mock.mount_linux_release().await;
+
+    let out = tempdir().unwrap();
+    let sbom_path = out.path().join("sbom.json");
+    let (result, _tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            sbom: Some(sbom_path.clone()),
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install failed");
+
+    let sbom: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&sbom_path).unwrap()).unwrap();
+    assert_eq!(sbom["version"], VERSION);
+    assert_eq!(sbom["asset"], asset.archive_name);
+    assert_eq!(
+        sbom["asset_url"],
+        format!(
+            "{}/releases/download/{VERSION}/{}",
+            mock.server.uri(),
+            asset.archive_name
+        )
+    );
+    assert_eq!(sbom["checksum"]["algorithm"], "sha256");
+    assert_eq!(sbom["checksum"]["value"], checksum);
+    assert_eq!(sbom["os"], "Linux");
+    assert_eq!(sbom["arch"], "X86_64");
+    assert_eq!(sbom["linkage"], "dynamic");
+    assert_eq!(sbom["signature"], "not_checked");
+    assert!(sbom["installed_at"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_failed_install_writes_diagnostics() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let sums = format!("{}  {}\n", sha256_hex(b"archive"), asset.archive_name);
+    mock.mount_file(VERSION, "SHA256SUM", sums.into_bytes())
+        .await;
+    mock.mount_status(VERSION, &asset.archive_name, ResponseTemplate::new(404))
+        .await;
+
+    let report = tempdir().unwrap();
+    let path = report.path().join("diagnostics.txt");
+    let args = InstallArgs {
+        diagnostics: Some(path.clone()),
+        ..Default::default()
+    };
+    let (result, _tmpdir) = install_with(&mock, args).await;
+    assert!(matches!(result, Err(Error::AssetNotFound { .. })));
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let asset_url = format!(
+        "{}/releases/download/{VERSION}/{}",
+        mock.server.uri(),
+        asset.archive_name
+    );
+    for expected in [
+        format!("version: {VERSION}"),
+        "target: Linux X86_64".to_string(),
+        "retries: 0".to_string(),
+        format!(
+            "GET {}/releases/download/{VERSION}/SHA256SUM -> 200 OK",
+            mock.server.uri()
+        ),
+        format!("HEAD {asset_url} -> 404 Not Found"),
+        format!("error: {}", result.unwrap_err()),
+    ] {
+        assert!(
+            text.contains(&expected),
+            "{expected:?} missing from:\n{text}"
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn test_json_lines_streams_install_events() {
+    let (_home, home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_file(VERSION, &asset.archive_name, archive).await;
+
+    let tmpdir = tempdir().unwrap();
+    let url = format!(
+        "{}/releases/download/{VERSION}/{}",
+        mock.server.uri(),
+        asset.archive_name
+    );
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .args(["install", "--json-lines", "--no-setup-path", "--url", &url])
+        .args(["--sha", &checksum, "--os", "linux", "--arch", "x86_64"])
+        .arg("--path")
+        .arg(tmpdir.path().join("install_target"))
+        .arg("--tmpdir")
+        .arg(tmpdir.path().join("work"))
+        .env("HOME", &home_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let events = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    let names = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names.first(), Some(&"resolve"));
+    assert_eq!(events[0]["version"], VERSION);
+    let downloads = events
+        .iter()
+        .filter(|event| event["event"] == "download")
+        .collect::<Vec<_>>();
+    assert_eq!(downloads.last().unwrap()["pct"], 100);
+    assert!(downloads
+        .windows(2)
+        .all(|w| w[0]["pct"].as_u64() < w[1]["pct"].as_u64()));
+    assert_eq!(
+        names[names.len() - 3..],
+        ["verify", "extract", "done"],
+        "{names:?}"
+    );
+    assert_eq!(events[events.len() - 3]["checksum"], checksum);
+    assert_eq!(events[events.len() - 1]["version"], VERSION);
+}
@@ -17,8 +17,7 @@ async fn execute_install_test(version: String, install_dir: PathBuf, tmpdir: Tem
         version,
         path: Some(install_dir.clone()),
         tmpdir: Some(tmpdir.path().to_path_buf()),
-        os: None,
-        arch: None,
+        ..Default::default()
     };
 
     let client = WasmEdgeApiClient::default();
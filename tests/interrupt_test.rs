@@ -0,0 +1,106 @@
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use semver::Version;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    api::{Asset, ResolveVia},
+    cli::{CommandContext, CommandExecutor},
+    commands::install::InstallArgs,
+    error::Error,
+    target::{TargetArch, TargetOS},
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+mod mock_server;
+use mock_server::{linux_archive, sha256_hex, MockRelease};
+
+const VERSION: &str = "0.14.1";
+
+// Kept in its own test binary: the Ctrl-C handler installed by the install replaces the
+// default SIGINT behavior for the whole process.
+#[tokio::test]
+#[serial]
+async fn test_interrupt_removes_staging_directory() {
+    let mock = MockRelease::start().await;
+    let version = Version::parse(VERSION).unwrap();
+    let asset = Asset::new(&version, &TargetOS::Linux, &TargetArch::X86_64);
+    let archive = linux_archive(&version);
+    let sums = format!("{}  {}\n", sha256_hex(&archive), asset.archive_name);
+    mock.mount_file(VERSION, "SHA256SUM", sums.into_bytes())
+        .await;
+    mock.mount_status(
+        VERSION,
+        &asset.archive_name,
+        ResponseTemplate::new(200)
+            .set_body_bytes(archive)
+            .set_delay(Duration::from_secs(30)),
+    )
+    .await;
+
+    let tmpdir = tempdir().unwrap();
+    let work = tmpdir.path().join("work");
+    let args = InstallArgs {
+        version: VERSION.to_string(),
+        path: Some(tmpdir.path().join("install_target")),
+        tmpdir: Some(work.clone()),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+
+    let staging = work.join(&asset.install_name);
+    let interrupt = tokio::spawn(async move {
+        while !staging.exists() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        send_sigint();
+    });
+
+    let result = args.execute(ctx).await;
+    interrupt.await.unwrap();
+
+    assert!(matches!(result, Err(Error::Interrupted)), "{result:?}");
+    assert_eq!(Error::Interrupted.exit_code(), 130);
+    assert!(!work.join(&asset.install_name).exists());
+    assert!(!tmpdir.path().join("install_target").exists());
+}
+
+fn send_sigint() {
+    std::process::Command::new("kill")
+        .args(["-INT", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_interrupt_cancels_latest_version_lookup() {
+    let mock = MockRelease::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/releases"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+        .mount(&mock.server)
+        .await;
+    let client = mock.client().with_resolve_via(ResolveVia::Api);
+
+    let interrupt = tokio::spawn(async {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        send_sigint();
+    });
+    let result = client.latest_release().await;
+    interrupt.await.unwrap();
+
+    assert!(matches!(result, Err(Error::Interrupted)), "{result:?}");
+}
@@ -0,0 +1,124 @@
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use clap::Parser;
+use semver::Version;
+use serial_test::serial;
+use wasmedgeup::{
+    api::{ReleasesFilter, WasmEdgeApiClient},
+    cache::ListingCache,
+    cli::Cli,
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::MockRelease;
+use test_utils::setup_test_environment;
+
+/// How many times `mock` was asked for the API release listing.
+async fn listings(mock: &MockRelease) -> usize {
+    mock.server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/api/releases")
+        .count()
+}
+
+fn cached_client(mock: &MockRelease, cache: &ListingCache) -> WasmEdgeApiClient {
+    mock.client().with_listing_cache(cache.clone())
+}
+
+#[tokio::test]
+async fn test_listing_is_reused_while_fresh() {
+    let mock = MockRelease::start().await;
+    mock.mount_api_releases(&[("0.14.1", false), ("0.14.0", false)])
+        .await;
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = ListingCache::new(tmp.path());
+    let client = cached_client(&mock, &cache);
+
+    let releases = client.releases(ReleasesFilter::All, 10).await.unwrap();
+    assert_eq!(releases[0], Version::new(0, 14, 1));
+    assert_eq!(
+        client.releases(ReleasesFilter::All, 10).await.unwrap(),
+        releases
+    );
+    assert_eq!(
+        client.latest_release().await.unwrap(),
+        Version::new(0, 14, 1)
+    );
+    // The stable listing is cached apart from the full one.
+    assert_eq!(listings(&mock).await, 2);
+
+    let expired = cached_client(&mock, &cache.with_ttl(Some(Duration::ZERO)));
+    expired.releases(ReleasesFilter::All, 10).await.unwrap();
+    assert_eq!(listings(&mock).await, 3);
+}
+
+#[tokio::test]
+async fn test_refresh_bypasses_listing_cache() {
+    let mock = MockRelease::start().await;
+    mock.mount_api_releases(&[("0.14.1", false)]).await;
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = ListingCache::new(tmp.path());
+    let stale = vec![Version::new(0, 13, 5)];
+    let client = cached_client(&mock, &cache);
+    cache
+        .store(&client.listing_cache_key(ReleasesFilter::All), &stale)
+        .await;
+
+    let refreshed = client.clone().with_refresh(true);
+    let releases = refreshed.releases(ReleasesFilter::All, 10).await.unwrap();
+    assert_eq!(releases, vec![Version::new(0, 14, 1)]);
+    assert_eq!(listings(&mock).await, 1);
+    // Neither read nor written: the next run without --refresh still sees the old listing.
+    assert_eq!(
+        client.releases(ReleasesFilter::All, 10).await.unwrap(),
+        stale
+    );
+}
+
+#[tokio::test]
+async fn test_published_check_looks_past_stale_listing() {
+    let mock = MockRelease::start().await;
+    mock.mount_api_releases(&[("0.15.0", false), ("0.14.1", false)])
+        .await;
+    let tmp = tempfile::tempdir().unwrap();
+    let cache = ListingCache::new(tmp.path());
+    let client = cached_client(&mock, &cache);
+    let key = client.listing_cache_key(ReleasesFilter::All);
+    cache.store(&key, &[Version::new(0, 14, 1)]).await;
+
+    client
+        .ensure_published(&Version::new(0, 14, 1))
+        .await
+        .unwrap();
+    assert_eq!(listings(&mock).await, 0);
+    client
+        .ensure_published(&Version::new(0, 15, 0))
+        .await
+        .unwrap();
+    assert_eq!(listings(&mock).await, 1);
+    assert_eq!(cache.lookup(&key).await.unwrap()[0], Version::new(0, 15, 0));
+}
+
+#[test]
+#[serial]
+fn test_listing_cache_ttl_option() {
+    let (_home, _home_path) = setup_test_environment();
+    let default = Cli::try_parse_from(["wasmedgeup", "list"])
+        .unwrap()
+        .context();
+    let cache = default.client.listing_cache.expect("listing cache");
+    assert!(cache.root().ends_with("wasmedgeup/listings"));
+
+    let cli = Cli::try_parse_from(["wasmedgeup", "--listing-cache-ttl", "never", "list"]).unwrap();
+    assert!(cli.listing_cache_ttl.unwrap().0.is_none());
+
+    let refresh = Cli::try_parse_from(["wasmedgeup", "--refresh", "list"]).unwrap();
+    assert!(refresh.context().client.listing_cache.is_none());
+    assert!(Cli::try_parse_from(["wasmedgeup", "--listing-cache-ttl", "1w", "list"]).is_err());
+}
@@ -0,0 +1,133 @@
+#![cfg(unix)]
+
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    commands::install::InstallArgs,
+    error::Error,
+    manifest::{InstallLock, InstallManifest, SbomChecksum},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{
+    install_from, install_with, linux_archive, linux_asset, sha256_hex, MockRelease, VERSION,
+};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_manifest_only_then_install_from_manifest() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive.clone(), &checksum)
+        .await;
+
+    let dir = tempdir().unwrap();
+    let lockfile = dir.path().join("wasmedge.lock.json");
+    let args = InstallArgs {
+        manifest_only: Some(lockfile.clone()),
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, args).await;
+    result.expect("--manifest-only failed");
+    assert!(!tmpdir.path().join("install_target").exists());
+
+    let lock = InstallLock::load(&lockfile).unwrap();
+    assert_eq!(lock.version, VERSION);
+    assert_eq!(lock.asset, asset.archive_name);
+    assert_eq!(lock.checksum.algorithm, "sha256");
+    assert_eq!(lock.checksum.value, checksum);
+    assert_eq!(
+        lock.url.as_deref(),
+        Some(mock.client().asset_url(&asset).unwrap().as_str())
+    );
+
+    // Only the pinned archive is served now: the checksum file must not be needed.
+    let replay = MockRelease::start().await;
+    replay
+        .mount_file(VERSION, &asset.archive_name, archive)
+        .await;
+    let pinned = InstallLock {
+        url: Some(replay.client().asset_url(&asset).unwrap().to_string()),
+        ..lock
+    };
+    pinned.write(&lockfile).unwrap();
+
+    let args = InstallArgs {
+        from_manifest: Some(lockfile.clone()),
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&replay, args).await;
+    result.expect("--from-manifest failed");
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert_eq!(manifest.archive_checksum, checksum);
+
+    let tampered = InstallLock {
+        checksum: SbomChecksum {
+            algorithm: "sha256".to_string(),
+            value: "0".repeat(64),
+        },
+        ..pinned
+    };
+    tampered.write(&lockfile).unwrap();
+    let args = InstallArgs {
+        from_manifest: Some(lockfile),
+        ..Default::default()
+    };
+    let (result, _tmpdir) = install_with(&replay, args).await;
+    assert!(
+        matches!(result, Err(Error::ChecksumMismatch { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_from_install_manifest_without_sums_file() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive.clone(), &checksum)
+        .await;
+    let (result, first) = install_from(&mock).await;
+    result.expect("install failed");
+    let recorded =
+        InstallManifest::path(&first.path().join("install_target/versions").join(VERSION));
+
+    let replay = MockRelease::start().await;
+    replay
+        .mount_file(VERSION, &asset.archive_name, archive)
+        .await;
+    let args = InstallArgs {
+        from_manifest: Some(recorded.clone()),
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&replay, args).await;
+    result.expect("--from-manifest with an install manifest failed");
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    assert!(version_dir.join("bin/wasmedge").is_file());
+    let requests = replay.server.received_requests().await.unwrap();
+    assert!(
+        requests
+            .iter()
+            .all(|r| !r.url.path().ends_with("SHA256SUM")),
+        "{requests:?}"
+    );
+
+    let mut lock = InstallLock::load(&recorded).unwrap();
+    lock.version = "latest".to_string();
+    let lockfile = tmpdir.path().join("floating.lock.json");
+    lock.write(&lockfile).unwrap();
+    let result = InstallLock::load(&lockfile);
+    assert!(
+        matches!(result, Err(Error::InvalidLockfile { .. })),
+        "{result:?}"
+    );
+}
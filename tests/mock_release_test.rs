@@ -0,0 +1,835 @@
+#![cfg(unix)]
+
+use semver::Version;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    api::{Asset, Linkage, ReleasesFilter},
+    cli::{CommandContext, CommandExecutor},
+    commands::install::InstallArgs,
+    error::Error,
+    manifest::InstallManifest,
+    target::{TargetArch, TargetOS},
+};
+use wiremock::{matchers::path, Mock, ResponseTemplate};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{
+    install_from, install_with, linux_archive, linux_archive_in, linux_asset, sha256_hex, tar_gz,
+    MockRelease, VERSION,
+};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_install_from_mock_release() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let (result, tmpdir) = install_from(&mock).await;
+    result.expect("install failed");
+
+    let install_dir = tmpdir.path().join("install_target");
+    assert!(install_dir.join("bin").join("wasmedge").exists());
+    assert!(install_dir
+        .join("versions")
+        .join(VERSION)
+        .join("include")
+        .join("wasmedge")
+        .join("wasmedge.h")
+        .exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_without_checksum_file() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_status(VERSION, "SHA256SUM", ResponseTemplate::new(404))
+        .await;
+
+    let (result, _tmpdir) = install_from(&mock).await;
+    assert!(matches!(result, Err(Error::ChecksumNotFound { .. })));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_release_without_published_checksums() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    mock.mount_file(VERSION, &asset.archive_name, linux_archive(&asset.version))
+        .await;
+    mock.mount_status(VERSION, "SHA256SUM", ResponseTemplate::new(404))
+        .await;
+
+    let (result, _tmpdir) = install_from(&mock).await;
+    let err = result.expect_err("an unverifiable archive must not be installed");
+    assert!(matches!(err, Error::ChecksumsUnpublished { .. }), "{err:?}");
+    assert!(err.to_string().contains("--no-verify"), "{err}");
+
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            no_verify: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install with --no-verify failed");
+    assert!(tmpdir
+        .path()
+        .join("install_target")
+        .join("bin")
+        .join("wasmedge")
+        .exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_missing_asset() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let sums = format!("{}  {}\n", sha256_hex(b"archive"), asset.archive_name);
+    mock.mount_file(VERSION, "SHA256SUM", sums.into_bytes())
+        .await;
+    mock.mount_status(VERSION, &asset.archive_name, ResponseTemplate::new(404))
+        .await;
+
+    let (result, _tmpdir) = install_from(&mock).await;
+    assert!(
+        matches!(result, Err(Error::AssetNotFound { .. })),
+        "{result:?}"
+    );
+    // The HEAD check fails fast, before the download is attempted.
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(
+        !requests
+            .iter()
+            .any(|r| r.method.as_str() == "GET" && r.url.path().ends_with(&asset.archive_name)),
+        "{requests:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_checksum_not_found_reports_listed_files() {
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let other = format!("WasmEdge-{VERSION}-manylinux_2_28_x86_64.tar.gz");
+    let sums = format!("{}  {other}\n", sha256_hex(b"archive"));
+    mock.mount_file(VERSION, "SHA256SUM", sums.into_bytes())
+        .await;
+
+    let err = mock
+        .client()
+        .get_release_checksum(&asset.version, &asset)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("1 other file(s) listed"), "{err}");
+    match err {
+        Error::ChecksumNotFound { listed, .. } => assert_eq!(listed, [other]),
+        other => panic!("expected ChecksumNotFound, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_prefer_static_installs_static_build() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let version = Version::parse(VERSION).unwrap();
+    let asset = Asset::new_static(&version, &TargetOS::Linux, &TargetArch::X86_64).unwrap();
+    assert_eq!(
+        asset.archive_name,
+        "WasmEdge-0.14.1-manylinux2014_x86_64_static.tar.gz"
+    );
+    let archive = linux_archive(&version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            prefer_static: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install failed");
+
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert_eq!(manifest.linkage, Linkage::Static);
+    assert_eq!(manifest.archive, asset.archive_name);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_prefer_static_falls_back_to_dynamic_build() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            prefer_static: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install failed");
+
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert_eq!(manifest.linkage, Linkage::Dynamic);
+    assert_eq!(manifest.archive, asset.archive_name);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_prerelease_uses_published_asset_name() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let tag = "0.14.1-rc.1";
+    mock.mount_tags(&["0.14.0", tag]).await;
+
+    let client = mock.client();
+    let version = client.resolve_version(tag).await.unwrap();
+    assert_eq!(version.to_string(), tag);
+    let releases = client.releases(ReleasesFilter::All, 10).await.unwrap();
+    assert!(releases.contains(&version));
+
+    // Prereleases of 0.14.x are published under the manylinux2014 name, like 0.14.x itself.
+    let asset = Asset::new(&version, &TargetOS::Linux, &TargetArch::X86_64);
+    let archive_name = "WasmEdge-0.14.1-rc.1-manylinux2014_x86_64.tar.gz";
+    assert_eq!(asset.archive_name, archive_name);
+
+    let archive = linux_archive(&version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(tag, archive_name, archive, &checksum)
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let install_dir = tmpdir.path().join("install_target");
+    let args = InstallArgs {
+        version: tag.to_string(),
+        path: Some(install_dir.clone()),
+        tmpdir: Some(tmpdir.path().join("work")),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client,
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("prerelease install failed");
+
+    let version_dir = install_dir.join("versions").join(tag);
+    let manifest = InstallManifest::load(&version_dir, tag).unwrap();
+    assert_eq!(manifest.archive, archive_name);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_include_exclude_filters() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let args = InstallArgs {
+        include: vec!["bin/*".parse().unwrap(), "include/**".parse().unwrap()],
+        // Excludes win over includes.
+        exclude: vec!["include/**/*.h".parse().unwrap()],
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, args).await;
+    result.expect("install failed");
+
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    assert!(version_dir.join("bin").join("wasmedge").is_file());
+    assert!(!version_dir.join("lib").exists());
+    assert!(!version_dir.join("include").exists());
+
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert_eq!(manifest.files.keys().collect::<Vec<_>>(), ["bin/wasmedge"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_include_matching_nothing_fails() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let args = InstallArgs {
+        include: vec!["share/**".parse().unwrap()],
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, args).await;
+    assert!(matches!(result, Err(Error::NoFilesSelected)), "{result:?}");
+    assert!(!tmpdir
+        .path()
+        .join("install_target/versions")
+        .join(VERSION)
+        .exists());
+}
+
+#[tokio::test]
+async fn test_print_url_does_not_download() {
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+
+    let client = mock.client();
+    let base = format!("{}/releases/download/{VERSION}", mock.server.uri());
+    assert_eq!(
+        client.asset_url(&asset).unwrap().as_str(),
+        format!("{base}/{}", asset.archive_name)
+    );
+    assert_eq!(
+        client.checksum_url(&asset.version).unwrap().as_str(),
+        format!("{base}/SHA256SUM")
+    );
+
+    let args = InstallArgs {
+        print_url: true,
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, args).await;
+    result.expect("--print-url failed");
+
+    assert!(!tmpdir.path().join("install_target").exists());
+    assert!(!tmpdir.path().join("work").exists());
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(requests.is_empty(), "{requests:?}");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_after_install_hook_sees_install_and_fails_install() {
+    let (_home, home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let out = home_path.join("hook.out");
+    let hook = format!(
+        r#"printf '%s %s' "$WASMEDGE_INSTALL_DIR" "$WASMEDGE_VERSION" > '{}'"#,
+        out.display()
+    );
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            after_install: Some(hook),
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install failed");
+    let install_dir = tmpdir.path().join("install_target");
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        format!("{} {VERSION}", install_dir.display())
+    );
+
+    let failing = |ignore_hook_errors| InstallArgs {
+        after_install: Some("exit 3".to_string()),
+        ignore_hook_errors,
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, failing(false)).await;
+    assert!(
+        matches!(result, Err(Error::ChildProcessFailed { code: Some(3), .. })),
+        "{result:?}"
+    );
+    assert!(tmpdir
+        .path()
+        .join("install_target")
+        .join("bin")
+        .join("wasmedge")
+        .exists());
+
+    let (result, _tmpdir) = install_with(&mock, failing(true)).await;
+    result.expect("hook errors should be ignored");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_write_env_file_can_be_sourced() {
+    let (_home, home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let env_file = home_path.join("wasmedge.env");
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            write_env_file: Some(env_file.clone()),
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install failed");
+
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(r#". "$1" && printf '%s\n' "$WASMEDGE_VERSION" && command -v wasmedge"#)
+        .arg("sh")
+        .arg(&env_file)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let bin_dir = tmpdir
+        .path()
+        .join("install_target")
+        .join("versions")
+        .join(VERSION)
+        .join("bin");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{VERSION}\n{}\n", bin_dir.join("wasmedge").display())
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_allow_rosetta_falls_back_to_x86_64_build() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let version = Version::parse(VERSION).unwrap();
+    let x86_64 = Asset::new(&version, &TargetOS::Darwin, &TargetArch::X86_64);
+    let archive = linux_archive(&version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &x86_64.archive_name, archive, &checksum)
+        .await;
+
+    let install = |allow_rosetta| {
+        let tmpdir = tempdir().unwrap();
+        let args = InstallArgs {
+            version: VERSION.to_string(),
+            path: Some(tmpdir.path().join("install_target")),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Darwin),
+            arch: Some(TargetArch::Aarch64),
+            no_cache: true,
+            no_setup_path: true,
+            allow_rosetta,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        async move { (args.execute(ctx).await, tmpdir) }
+    };
+
+    let (result, _tmpdir) = install(false).await;
+    assert!(
+        matches!(result, Err(Error::ChecksumNotFound { .. })),
+        "{result:?}"
+    );
+
+    let (result, tmpdir) = install(true).await;
+    result.expect("install failed");
+    let version_dir = tmpdir
+        .path()
+        .join("install_target")
+        .join("versions")
+        .join(VERSION);
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert!(manifest.rosetta);
+    assert!(matches!(manifest.arch, TargetArch::X86_64));
+    assert_eq!(manifest.archive, x86_64.archive_name);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_expected_checksum_skips_checksum_file() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_status(VERSION, "SHA256SUM", ResponseTemplate::new(404))
+        .await;
+    mock.mount_file(VERSION, &asset.archive_name, archive).await;
+
+    let (result, _tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            expected_checksum: Some(checksum),
+            ..Default::default()
+        },
+    )
+    .await;
+    result.expect("install failed");
+
+    let (result, _tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            expected_checksum: Some(sha256_hex(b"something else")),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(
+        matches!(result, Err(Error::ChecksumMismatch { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_temp_on_target_moves_staged_files_into_place() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let tmpdir = tempdir().unwrap();
+    let install_dir = tmpdir.path().join("install_target");
+    let args = InstallArgs {
+        version: VERSION.to_string(),
+        path: Some(install_dir.clone()),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        no_setup_path: true,
+        temp_on_target: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("install failed");
+
+    let version_dir = install_dir.join("versions").join(VERSION);
+    assert!(version_dir.join("bin").join("wasmedge").exists());
+    assert!(version_dir.join("lib").join("libwasmedge.so").exists());
+    assert!(!version_dir.join("lib64").exists());
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert!(manifest.files.contains_key("lib/libwasmedge.so"));
+    assert!(!install_dir.join(".wasmedgeup-tmp").exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_strip_components_unwraps_nested_archive() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive_in(&format!("dist/WasmEdge-{VERSION}-Linux"));
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let (result, tmpdir) = install_with(&mock, InstallArgs::default()).await;
+    result.expect("install failed");
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    assert!(version_dir.join("bin").join("wasmedge").exists());
+
+    let args = InstallArgs {
+        strip_components: Some(1),
+        ..Default::default()
+    };
+    let (result, _tmpdir) = install_with(&mock, args).await;
+    assert!(
+        matches!(
+            &result,
+            Err(Error::InvalidArchiveStructure { found_file }) if found_file.starts_with("WasmEdge-")
+        ),
+        "got {result:?}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_from_exact_url() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let archive = linux_archive_in("WasmEdge-0.15.0-ci.7-Linux");
+    let checksum = sha256_hex(&archive);
+    Mock::given(path("/artifacts/build-7/wasmedge.tar.gz"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(archive))
+        .mount(&mock.server)
+        .await;
+    let url = url::Url::parse(&format!(
+        "{}/artifacts/build-7/wasmedge.tar.gz",
+        mock.server.uri()
+    ))
+    .unwrap();
+
+    // Without a version in the file name, a label is needed.
+    let args = InstallArgs {
+        url: Some(url.clone()),
+        no_setup_path: true,
+        ..Default::default()
+    };
+    let (result, _tmpdir) = install_with(&mock, args).await;
+    assert!(matches!(result, Err(Error::UrlVersionUnknown { .. })));
+
+    let args = InstallArgs {
+        url: Some(url),
+        label: Some(Version::parse("0.15.0-ci.7").unwrap()),
+        expected_checksum: Some(checksum.clone()),
+        no_setup_path: true,
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, args).await;
+    result.expect("install failed");
+    let version_dir = tmpdir.path().join("install_target/versions/0.15.0-ci.7");
+    assert!(version_dir.join("bin").join("wasmedge").exists());
+    let manifest = InstallManifest::load(&version_dir, "0.15.0-ci.7").unwrap();
+    assert_eq!(manifest.archive, "wasmedge.tar.gz");
+    assert_eq!(manifest.archive_checksum, checksum);
+
+    // Neither the release listing nor a checksum file was consulted.
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(
+        requests
+            .iter()
+            .all(|r| r.url.path().starts_with("/artifacts/")),
+        "{requests:?}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_preserve_existing_leaves_identical_files_untouched() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let tmpdir = tempdir().unwrap();
+    let prefix = tmpdir.path().join("usr_local");
+    let install = |preserve_existing| {
+        let args = InstallArgs {
+            version: VERSION.to_string(),
+            prefix: Some(prefix.clone()),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_cache: true,
+            preserve_existing,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    install(false).await.expect("prefix install failed");
+
+    // Mark the installed files, and change one of them.
+    let marked = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+    let mark = |name: &str| {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(prefix.join(name))
+            .unwrap();
+        file.set_modified(marked).unwrap();
+    };
+    mark("bin/wasmedge");
+    std::fs::write(prefix.join("include/wasmedge/wasmedge.h"), "/* edited */\n").unwrap();
+    mark("include/wasmedge/wasmedge.h");
+    let mtime = |name: &str| {
+        std::fs::metadata(prefix.join(name))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+
+    install(true).await.expect("prefix install failed");
+    assert_eq!(mtime("bin/wasmedge"), marked);
+    assert_ne!(mtime("include/wasmedge/wasmedge.h"), marked);
+    assert_eq!(
+        std::fs::read_to_string(prefix.join("include/wasmedge/wasmedge.h")).unwrap(),
+        "/* header */\n"
+    );
+
+    install(false).await.expect("prefix install failed");
+    assert_ne!(mtime("bin/wasmedge"), marked);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_archive_without_runtime_binary_fails() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = tar_gz([(
+        format!("WasmEdge-{VERSION}-Linux/lib64/libwasmedge.so"),
+        b"not a real library".as_slice(),
+        0o644,
+    )]);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let (result, tmpdir) = install_from(&mock).await;
+    match result {
+        Err(e @ Error::NoInstallableFiles { .. }) => {
+            assert!(e.to_string().contains("bin/wasmedge"), "{e}");
+        }
+        other => panic!("expected NoInstallableFiles, got {other:?}"),
+    }
+    assert!(!tmpdir
+        .path()
+        .join("install_target/versions")
+        .join(VERSION)
+        .join("lib64")
+        .exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn test_from_env_installs_from_mirror() {
+    let (_home, home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let tmpdir = tempdir().unwrap();
+    let target_dir = tmpdir.path().join("install_target");
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .args([
+            "install",
+            "--from-env",
+            "--no-setup-path",
+            "--arch",
+            "x86_64",
+        ])
+        .env("HOME", &home_path)
+        .env("WASMEDGEUP_VERSION", VERSION)
+        .env("WASMEDGEUP_PATH", &target_dir)
+        .env("WASMEDGEUP_TMPDIR", tmpdir.path().join("work"))
+        .env("WASMEDGEUP_OS", "linux")
+        // Overridden by the flag.
+        .env("WASMEDGEUP_ARCH", "aarch64")
+        .env(
+            "WASMEDGEUP_MIRROR",
+            format!("{}/releases/download/", mock.server.uri()),
+        )
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert!(target_dir
+        .join("versions")
+        .join(VERSION)
+        .join("bin/wasmedge")
+        .is_file());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_force_fresh_tmp_removes_stale_staging_files() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let install = |force_fresh_tmp| InstallArgs {
+        version: VERSION.to_string(),
+        path: Some(tmpdir.path().join("install_target")),
+        tmpdir: Some(tmpdir.path().join("work")),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        force_fresh_tmp,
+        ..Default::default()
+    };
+    let ctx = || CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    // A crashed install's leftovers next to the archive's wrapper directory keep it from
+    // being stripped.
+    let stale = tmpdir
+        .path()
+        .join("work")
+        .join(&asset.install_name)
+        .join("leftover");
+    std::fs::create_dir_all(&stale).unwrap();
+    std::fs::write(stale.join("partial"), "stale").unwrap();
+
+    let result = install(false).execute(ctx()).await;
+    assert!(
+        matches!(result, Err(Error::InvalidArchiveStructure { .. })),
+        "{result:?}"
+    );
+
+    std::fs::create_dir_all(&stale).unwrap();
+    std::fs::write(stale.join("partial"), "stale").unwrap();
+    install(true).execute(ctx()).await.expect("install failed");
+    let version_dir = tmpdir.path().join("install_target/versions").join(VERSION);
+    assert!(version_dir.join("bin/wasmedge").exists());
+    assert!(!version_dir.join("leftover").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn test_install_mode_overrides_archive_and_umask() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_home, _home_path) = setup_test_environment();
+    let mode_of =
+        |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+    let root = "WasmEdge-0.14.1-Linux";
+    let archive = tar_gz([
+        (
+            format!("{root}/bin/wasmedge"),
+            b"#!/bin/sh\n".as_slice(),
+            0o777,
+        ),
+        (
+            format!("{root}/lib64/libwasmedge.so"),
+            b"library".as_slice(),
+            0o666,
+        ),
+    ]);
+
+    for (mode, dir_mode, exe_mode, file_mode) in [
+        (None, 0o755, 0o755, 0o644),
+        (Some(0o750), 0o750, 0o750, 0o640),
+    ] {
+        let mock = MockRelease::start().await;
+        let asset = linux_asset();
+        let checksum = sha256_hex(&archive);
+        mock.mount_release(VERSION, &asset.archive_name, archive.clone(), &checksum)
+            .await;
+        let args = InstallArgs {
+            mode,
+            no_setup_path: true,
+            ..Default::default()
+        };
+        let (result, tmpdir) = install_with(&mock, args).await;
+        result.expect("install failed");
+
+        let install_dir = tmpdir.path().join("install_target");
+        let version_dir = install_dir.join("versions").join(VERSION);
+        for dir in [
+            &install_dir,
+            &install_dir.join("versions"),
+            &version_dir,
+            &version_dir.join("bin"),
+        ] {
+            assert_eq!(mode_of(dir), dir_mode, "{}", dir.display());
+        }
+        assert_eq!(mode_of(&version_dir.join("bin/wasmedge")), exe_mode);
+        assert_eq!(mode_of(&version_dir.join("lib/libwasmedge.so")), file_mode);
+    }
+}
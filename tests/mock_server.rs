@@ -0,0 +1,486 @@
+//! A local stand-in for the WasmEdge git repository and its GitHub release downloads.
+//!
+//! Tests include this module with `mod mock_server;` and point a client at it via
+//! [`MockRelease::client`], so installs run end to end without network access.
+
+#![cfg(unix)]
+// Each test binary uses only part of this module.
+#![allow(dead_code)]
+
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use wasmedgeup::{
+    api::{Asset, WasmEdgeApiClient},
+    cli::{CommandContext, CommandExecutor},
+    commands::install::InstallArgs,
+    error::Error,
+    target::{TargetArch, TargetOS},
+};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const FAKE_SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+/// The release most tests install.
+pub const VERSION: &str = "0.14.1";
+
+/// The Linux x86_64 archive of [`VERSION`].
+pub fn linux_asset() -> Asset {
+    Asset::new(
+        &Version::parse(VERSION).unwrap(),
+        &TargetOS::Linux,
+        &TargetArch::X86_64,
+    )
+}
+
+pub async fn install_from(mock: &MockRelease) -> (Result<(), Error>, tempfile::TempDir) {
+    install_with(mock, InstallArgs::default()).await
+}
+
+/// Installs [`VERSION`] for Linux x86_64 from `mock` into a fresh directory without the
+/// download cache, ignoring `args`' location and target fields.
+pub async fn install_with(
+    mock: &MockRelease,
+    args: InstallArgs,
+) -> (Result<(), Error>, tempfile::TempDir) {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let args = InstallArgs {
+        version: VERSION.to_string(),
+        path: Some(tmpdir.path().join("install_target")),
+        tmpdir: Some(tmpdir.path().join("work")),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        ..args
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    (args.execute(ctx).await, tmpdir)
+}
+
+pub struct MockRelease {
+    pub server: MockServer,
+}
+
+impl MockRelease {
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// A client that lists releases from and downloads assets off this server, without retries.
+    pub fn client(&self) -> WasmEdgeApiClient {
+        let uri = self.server.uri();
+        WasmEdgeApiClient::default()
+            .with_retries(0)
+            .with_git_url(format!("{uri}/WasmEdge.git"))
+            .with_releases_api_url(format!("{uri}/api/releases"))
+            .with_release_base_url(format!("{uri}/releases/download"))
+    }
+
+    /// Serves a GitHub API release listing with one release per `(tag, draft)` pair.
+    pub async fn mount_api_releases(&self, releases: &[(&str, bool)]) {
+        let body = releases
+            .iter()
+            .map(|(tag, draft)| serde_json::json!({ "tag_name": tag, "draft": draft }))
+            .collect::<Vec<_>>();
+        Mock::given(method("GET"))
+            .and(path("/api/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serves the GitHub API release `tag` with one asset per `(name, digest)` pair.
+    pub async fn mount_api_release(&self, tag: &str, assets: &[(&str, Option<&str>)]) {
+        let assets = assets
+            .iter()
+            .map(|(name, digest)| serde_json::json!({ "name": name, "digest": digest }))
+            .collect::<Vec<_>>();
+        Mock::given(method("GET"))
+            .and(path(format!("/api/releases/tags/{tag}")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "tag_name": tag, "assets": assets })),
+            )
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serves a git smart-HTTP ref advertisement with one tag per version.
+    pub async fn mount_tags(&self, versions: &[&str]) {
+        Mock::given(method("GET"))
+            .and(path("/WasmEdge.git/info/refs"))
+            .and(query_param("service", "git-upload-pack"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "content-type",
+                        "application/x-git-upload-pack-advertisement",
+                    )
+                    .set_body_bytes(ref_advertisement(versions)),
+            )
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serves `body` at `/releases/download/<version>/<file_name>`.
+    pub async fn mount_file(&self, version: &str, file_name: &str, body: Vec<u8>) {
+        self.mount_status(
+            version,
+            file_name,
+            ResponseTemplate::new(200).set_body_bytes(body),
+        )
+        .await;
+    }
+
+    /// Serves `response` at `/releases/download/<version>/<file_name>`, to `HEAD` requests too.
+    pub async fn mount_status(&self, version: &str, file_name: &str, response: ResponseTemplate) {
+        Mock::given(path(format!("/releases/download/{version}/{file_name}")))
+            .respond_with(response)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serves `archive` together with a `SHA256SUM` file listing `checksum` for it.
+    pub async fn mount_release(
+        &self,
+        version: &str,
+        archive_name: &str,
+        archive: Vec<u8>,
+        checksum: &str,
+    ) {
+        let sums = format!("{checksum}  {archive_name}\n");
+        self.mount_file(version, "SHA256SUM", sums.into_bytes())
+            .await;
+        self.mount_file(version, archive_name, archive).await;
+    }
+
+    /// Serves the [`linux_asset`] release with a matching `SHA256SUM`, returning the asset and
+    /// its checksum.
+    pub async fn mount_linux_release(&self) -> (Asset, String) {
+        let asset = linux_asset();
+        let archive = linux_archive(&asset.version);
+        let checksum = sha256_hex(&archive);
+        self.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+            .await;
+        (asset, checksum)
+    }
+
+    /// Serves a Linux x86_64 release of each of `versions`.
+    pub async fn mount_linux_releases(&self, versions: &[&str]) {
+        for version in versions {
+            let asset = Asset::new(
+                &Version::parse(version).unwrap(),
+                &TargetOS::Linux,
+                &TargetArch::X86_64,
+            );
+            let archive = linux_archive(&asset.version);
+            let checksum = sha256_hex(&archive);
+            self.mount_release(version, &asset.archive_name, archive, &checksum)
+                .await;
+        }
+    }
+
+    /// How many times `file_name` was downloaded, i.e. requested with `GET`.
+    pub async fn downloads(&self, file_name: &str) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| r.method.as_str() == "GET" && r.url.path().ends_with(file_name))
+            .count()
+    }
+}
+
+/// Starts a bare HTTP/1.1 server for `body`, returning its base URL.
+///
+/// The first `GET` response advertises the full length but breaks off halfway through the body,
+/// which a well-behaved server framework refuses to do. Later requests get the whole body,
+/// or with `ranges` the requested `Range` as a `206 Partial Content` (and anything else a 500).
+/// `HEAD` requests are answered with the full length.
+pub async fn flaky_server(body: Vec<u8>, ranges: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let len = body.len();
+        let mut first = true;
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let (is_head, _, range_start) = read_range_start(&mut stream).await;
+            let (head, payload) = match range_start {
+                _ if is_head => (format!("200 OK\r\nContent-Length: {len}"), &body[..0]),
+                _ if first => {
+                    first = false;
+                    (format!("200 OK\r\nContent-Length: {len}"), &body[..len / 2])
+                }
+                Some(start) if ranges => (
+                    format!(
+                        "206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {start}-{}/{len}",
+                        len - start,
+                        len - 1
+                    ),
+                    &body[start..],
+                ),
+                // Resuming is required here, so a retry that starts over fails loudly.
+                None if ranges => (
+                    "500 Internal Server Error\r\nContent-Length: 0".to_string(),
+                    &body[..0],
+                ),
+                _ => (format!("200 OK\r\nContent-Length: {len}"), &body[..]),
+            };
+
+            let head = format!("HTTP/1.1 {head}\r\nConnection: close\r\n\r\n");
+            let _ = stream.write_all(head.as_bytes()).await;
+            let _ = stream.write_all(payload).await;
+            let _ = stream.shutdown().await;
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+/// Like [`flaky_server`] with `ranges`, but downloads redirect to a presigned CDN URL the way
+/// GitHub's do, and each redirect expires the URLs handed out before it.
+///
+/// A request under `/releases/download/` is answered with a `302` to `/cdn/asset?sig=<n>`
+/// with a fresh `n`; one with an expired signature gets a `403`, as from S3. The first `GET`
+/// breaks off halfway through the body, and later ones must resume with a `Range`.
+pub async fn expiring_redirect_server(body: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let len = body.len();
+        let (mut issued, mut first) = (0, true);
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let (is_head, target, range_start) = read_range_start(&mut stream).await;
+            let (head, payload) = if target.starts_with("/releases/download/") {
+                issued += 1;
+                (
+                    format!("302 Found\r\nLocation: /cdn/asset?sig={issued}\r\nContent-Length: 0"),
+                    &body[..0],
+                )
+            } else if target != format!("/cdn/asset?sig={issued}") {
+                (
+                    "403 Forbidden\r\nContent-Length: 19".to_string(),
+                    &b"Request has expired"[..],
+                )
+            } else {
+                match range_start {
+                    _ if is_head => (format!("200 OK\r\nContent-Length: {len}"), &body[..0]),
+                    _ if first => {
+                        first = false;
+                        (format!("200 OK\r\nContent-Length: {len}"), &body[..len / 2])
+                    }
+                    Some(start) => (
+                        format!(
+                            "206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {start}-{}/{len}",
+                            len - start,
+                            len - 1
+                        ),
+                        &body[start..],
+                    ),
+                    None => (
+                        "500 Internal Server Error\r\nContent-Length: 0".to_string(),
+                        &body[..0],
+                    ),
+                }
+            };
+
+            let head = format!("HTTP/1.1 {head}\r\nConnection: close\r\n\r\n");
+            let _ = stream.write_all(head.as_bytes()).await;
+            let _ = stream.write_all(payload).await;
+            let _ = stream.shutdown().await;
+        }
+    });
+
+    format!("http://{addr}")
+}
+
+/// Starts a bare keep-alive HTTP/1.1 server answering `GET <path>` (and `HEAD <path>`) with the
+/// matching body of `routes` (and anything else with a 404), returning its base URL and a count
+/// of the connections it accepted.
+pub async fn pooled_server(
+    routes: Vec<(String, Vec<u8>)>,
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use std::sync::{atomic::Ordering, Arc};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let connections = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let routes = Arc::new(routes);
+
+    let accepted = connections.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let routes = routes.clone();
+            tokio::spawn(async move {
+                while let Some((method, path)) = read_request_path(&mut stream).await {
+                    let body = routes.iter().find(|(p, _)| *p == path).map(|(_, b)| b);
+                    let status = if body.is_some() {
+                        "200 OK"
+                    } else {
+                        "404 Not Found"
+                    };
+                    let body = body.map_or(&[][..], Vec::as_slice);
+                    let head = format!(
+                        "HTTP/1.1 {status}\r\nContent-Length: {}\r\n\r\n",
+                        body.len()
+                    );
+                    let body = if method == "HEAD" { &[][..] } else { body };
+                    if stream.write_all(head.as_bytes()).await.is_err()
+                        || stream.write_all(body).await.is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    (format!("http://{addr}"), connections)
+}
+
+/// Reads a request head and returns the method and path of its request line, or `None` once the
+/// client closes the connection.
+async fn read_request_path(stream: &mut TcpStream) -> Option<(String, String)> {
+    let mut head = Vec::new();
+    let mut byte = [0; 1];
+    // One byte at a time, so that nothing of a pipelined next request is consumed.
+    while !head.ends_with(b"\r\n\r\n") {
+        match stream.read(&mut byte).await {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => head.push(byte[0]),
+        }
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?;
+    Some((method, path.split('?').next().unwrap_or(path).to_string()))
+}
+
+/// Reads a request head and returns whether it is a `HEAD` request, its target with the query
+/// and the start of its `Range: bytes=<start>-` header, if any.
+async fn read_range_start(stream: &mut TcpStream) -> (bool, String, Option<usize>) {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.ends_with(b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let range_start = head.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if !name.eq_ignore_ascii_case("range") {
+            return None;
+        }
+        value
+            .trim()
+            .strip_prefix("bytes=")?
+            .strip_suffix('-')?
+            .parse()
+            .ok()
+    });
+    let target = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or_default()
+        .to_string();
+    (head.starts_with("HEAD "), target, range_start)
+}
+
+fn pkt_line(data: &str) -> String {
+    format!("{:04x}{data}", data.len() + 4)
+}
+
+fn ref_advertisement(versions: &[&str]) -> Vec<u8> {
+    let mut body = pkt_line("# service=git-upload-pack\n");
+    body.push_str("0000");
+    body.push_str(&pkt_line(&format!(
+        "{FAKE_SHA} HEAD\0side-band-64k ofs-delta\n"
+    )));
+    for version in versions {
+        body.push_str(&pkt_line(&format!("{FAKE_SHA} refs/tags/{version}\n")));
+    }
+    body.push_str("0000");
+    body.into_bytes()
+}
+
+/// Builds a `.tar.gz` laid out like an official Linux release of `version`.
+pub fn linux_archive(version: &Version) -> Vec<u8> {
+    linux_archive_in(&format!("WasmEdge-{version}-Linux"))
+}
+
+/// Like [`linux_archive`], with the release files under `root` instead.
+pub fn linux_archive_in(root: &str) -> Vec<u8> {
+    let files: [(String, &[u8], u32); 3] = [
+        (
+            format!("{root}/bin/wasmedge"),
+            b"#!/bin/sh\necho wasmedge\n",
+            0o755,
+        ),
+        (
+            format!("{root}/lib64/libwasmedge.so"),
+            b"not a real library",
+            0o644,
+        ),
+        (
+            format!("{root}/include/wasmedge/wasmedge.h"),
+            b"/* header */\n",
+            0o644,
+        ),
+    ];
+    tar_gz(files)
+}
+
+/// Builds a `.tar.gz` holding the plugin shared object of an official plugin release.
+pub fn plugin_archive(name: &str) -> Vec<u8> {
+    tar_gz([(
+        format!(
+            "libwasmedgePlugin{name}.{}",
+            std::env::consts::DLL_EXTENSION
+        ),
+        b"not a real plugin".as_slice(),
+        0o644,
+    )])
+}
+
+/// Builds a `.tar.gz` of `(path, contents, mode)` files.
+pub fn tar_gz<const N: usize>(files: [(String, &[u8], u32); N]) -> Vec<u8> {
+    let encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    let mut builder = tar::Builder::new(encoder);
+    for (name, contents, mode) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        builder.append_data(&mut header, name, contents).unwrap();
+    }
+    let mut encoder = builder.into_inner().unwrap();
+    encoder.flush().unwrap();
+    encoder.finish().unwrap()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
@@ -0,0 +1,92 @@
+#![cfg(unix)]
+
+use serial_test::serial;
+use wasmedgeup::{commands::install::InstallArgs, error::Error};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{install_with, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_multi_install_reports_failures_and_activates_last_success() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    // 0.13.5 is not published, so only it fails.
+    mock.mount_linux_releases(&[VERSION, "0.14.0"]).await;
+
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            versions: vec!["0.13.5".to_string(), "0.14.0".to_string()],
+            multi: true,
+            jobs: Some(2),
+            no_setup_path: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(
+        matches!(
+            result,
+            Err(Error::BatchInstallFailed {
+                failed: 1,
+                total: 3
+            })
+        ),
+        "{result:?}"
+    );
+
+    let install_dir = tmpdir.path().join("install_target");
+    for version in [VERSION, "0.14.0"] {
+        assert!(install_dir
+            .join("versions")
+            .join(version)
+            .join("bin")
+            .join("wasmedge")
+            .exists());
+    }
+    assert!(!install_dir.join("versions").join("0.13.5").exists());
+    let active = std::fs::read_link(install_dir.join("bin")).unwrap();
+    assert!(
+        active.to_string_lossy().contains("0.14.0"),
+        "expected 0.14.0 to be active, got {}",
+        active.display()
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_multi_install_fail_fast_skips_remaining_versions() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_releases(&["0.14.0"]).await;
+
+    // VERSION is not published here and, with one job, is attempted first.
+    let (result, tmpdir) = install_with(
+        &mock,
+        InstallArgs {
+            versions: vec!["0.14.0".to_string()],
+            multi: true,
+            jobs: Some(1),
+            fail_fast: true,
+            no_setup_path: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(
+        matches!(
+            result,
+            Err(Error::BatchInstallFailed {
+                failed: 1,
+                total: 2
+            })
+        ),
+        "{result:?}"
+    );
+    let install_dir = tmpdir.path().join("install_target");
+    assert!(!install_dir.join("versions").join("0.14.0").exists());
+    assert!(!install_dir.join("bin").exists());
+}
@@ -0,0 +1,103 @@
+#![cfg(unix)]
+
+use clap::Parser;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    api::WasmEdgeApiClient,
+    cli::{Cli, CommandContext, CommandExecutor},
+    commands::install::InstallArgs,
+    error::Error,
+    target::{TargetArch, TargetOS},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{linux_archive, linux_asset, sha256_hex, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_offline_install_uses_only_cache_and_local_archives() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive.clone(), &checksum)
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let install = |client: WasmEdgeApiClient, version: &str, args: InstallArgs| {
+        let args = InstallArgs {
+            version: version.to_string(),
+            path: Some(tmpdir.path().join("wasmedge")),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_setup_path: true,
+            force: true,
+            ..args
+        };
+        let ctx = CommandContext {
+            client,
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+
+    // Nothing cached yet: the checksum file is needed and refused.
+    let offline = mock.client().with_offline(true);
+    let err = install(offline.clone(), VERSION, InstallArgs::default())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::Offline {
+                operation: "checksum file download"
+            }
+        ),
+        "{err:?}"
+    );
+    let err = install(offline.clone(), "latest", InstallArgs::default())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::Offline {
+                operation: "release listing"
+            }
+        ),
+        "{err:?}"
+    );
+    assert!(err.to_string().starts_with("Offline mode: "), "{err}");
+    assert!(mock.server.received_requests().await.unwrap().is_empty());
+
+    install(mock.client(), VERSION, InstallArgs::default())
+        .await
+        .expect("online install failed");
+    let online = mock.server.received_requests().await.unwrap().len();
+
+    install(offline.clone(), VERSION, InstallArgs::default())
+        .await
+        .expect("offline install from the cache failed");
+
+    let local = tmpdir.path().join(&asset.archive_name);
+    std::fs::write(&local, &archive).unwrap();
+    let args = InstallArgs {
+        url: Some(url::Url::from_file_path(&local).unwrap()),
+        expected_checksum: Some(checksum),
+        no_cache: true,
+        ..Default::default()
+    };
+    install(offline, "", args)
+        .await
+        .expect("offline install from a local archive failed");
+    assert_eq!(mock.server.received_requests().await.unwrap().len(), online);
+
+    let cli = Cli::try_parse_from(["wasmedgeup", "--offline", "list"]).unwrap();
+    assert!(cli.context().client.offline);
+    assert!(Cli::try_parse_from(["wasmedgeup", "--offline", "--refresh", "list"]).is_err());
+}
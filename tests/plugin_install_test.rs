@@ -17,8 +17,7 @@ async fn execute_runtime_install(version: String, install_dir: &Path, tmpdir: &T
         version,
         path: Some(install_dir.to_path_buf()),
         tmpdir: Some(tmpdir.path().to_path_buf()),
-        os: None,
-        arch: None,
+        ..Default::default()
     };
 
     let client = WasmEdgeApiClient::default();
@@ -41,6 +40,7 @@ async fn execute_plugin_install(
         tmpdir: Some(tmpdir.path().to_path_buf()),
         runtime,
         path: Some(install_dir.clone()),
+        plugin_dir: None,
     };
 
     let client = WasmEdgeApiClient::default();
@@ -76,6 +76,7 @@ async fn test_plugin_install_latest_runtime() {
     let client = WasmEdgeApiClient::default();
     let resolved_version = client
         .resolve_version(&version)
+        .await
         .expect("resolve latest failed");
 
     let specs = system::detect();
@@ -0,0 +1,193 @@
+#![cfg(unix)]
+
+use clap::Parser;
+use semver::Version;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    cli::{Cli, CommandContext, CommandExecutor},
+    commands::plugin::{install::PluginInstallArgs, version::PluginVersion},
+    error::Error,
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{plugin_archive, sha256_hex, tar_gz, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+async fn test_plugins_list_for_version() {
+    let mock = MockRelease::start().await;
+    let assets = [
+        "WasmEdge-plugin-wasi_nn-ggml-0.15.0-darwin_arm64.tar.gz",
+        "WasmEdge-plugin-wasi_crypto-0.15.0-darwin_arm64.tar.gz",
+        "WasmEdge-plugin-wasi_crypto-0.15.0-manylinux_2_28_x86_64.tar.gz",
+    ]
+    .map(|name| serde_json::json!({ "name": name }));
+    // Authenticated like the release listing, e.g. with a token from `--env-file`.
+    Mock::given(method("GET"))
+        .and(path("/api/releases/tags/0.15.0"))
+        .and(wiremock::matchers::header(
+            "authorization",
+            "Bearer ghp_plugins",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "assets": assets })),
+        )
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let cli = Cli::try_parse_from([
+        "wasmedgeup",
+        "plugins",
+        "list",
+        "0.15.0",
+        "--os",
+        "darwin",
+        "--arch",
+        "arm64",
+        "--json",
+    ])
+    .unwrap();
+    let client = mock.client().with_github_token("ghp_plugins");
+    let ctx = CommandContext {
+        client: client.clone(),
+        no_progress: true,
+    };
+    cli.commands.unwrap().execute(ctx).await.unwrap();
+
+    // Its requests show up in the request diagnostics (and `--verbose-http`) like any other.
+    let records = client.request_log().records();
+    assert!(
+        records
+            .iter()
+            .any(|r| r.method == "GET" && r.url.ends_with("/api/releases/tags/0.15.0")),
+        "{records:?}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_plugin_install_into_plugin_dir() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let version = Version::parse(VERSION).unwrap();
+    let key = wasmedgeup::system::plugins::plugin_platform_key(
+        &wasmedgeup::system::detect().os,
+        &version,
+    )
+    .expect("host has a plugin platform key");
+    let archive = plugin_archive("WasiLogging");
+    mock.mount_release(
+        VERSION,
+        &format!("WasmEdge-plugin-wasi_logging-{VERSION}-{key}.tar.gz"),
+        archive.clone(),
+        &sha256_hex(&archive),
+    )
+    .await;
+
+    let tmpdir = tempdir().unwrap();
+    let install_dir = tmpdir.path().join("install_target");
+    let version_dir = install_dir.join("versions").join(VERSION);
+    std::fs::create_dir_all(&version_dir).unwrap();
+    let plugin_dir = tmpdir.path().join("shared").join("plugins");
+
+    let args = PluginInstallArgs {
+        plugins: vec![PluginVersion::Name("wasi_logging".to_string())],
+        tmpdir: Some(tmpdir.path().join("work")),
+        runtime: None,
+        path: Some(install_dir),
+        plugin_dir: Some(plugin_dir.clone()),
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("plugin install failed");
+
+    let file_name = format!(
+        "libwasmedgePluginWasiLogging.{}",
+        std::env::consts::DLL_EXTENSION
+    );
+    assert!(plugin_dir.join(&file_name).is_file());
+    assert!(!version_dir.join("plugin").exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_plugin_install_verifies_and_never_leaves_a_partial_copy() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let version = Version::parse(VERSION).unwrap();
+    let key = wasmedgeup::system::plugins::plugin_platform_key(
+        &wasmedgeup::system::detect().os,
+        &version,
+    )
+    .expect("host has a plugin platform key");
+    let ext = std::env::consts::DLL_EXTENSION;
+    let archive = tar_gz([
+        (
+            format!("lib/libwasmedgePluginWasiNN.{ext}"),
+            b"not a real plugin".as_slice(),
+            0o644,
+        ),
+        (
+            format!("lib/libwasmedgePluginWasiNNExtra.{ext}"),
+            b"not a real plugin either".as_slice(),
+            0o644,
+        ),
+    ]);
+    let archive_name = format!("WasmEdge-plugin-wasi_nn-{VERSION}-{key}.tar.gz");
+
+    let tmpdir = tempdir().unwrap();
+    let install_dir = tmpdir.path().join("install_target");
+    let plugin_dir = install_dir.join("versions").join(VERSION).join("plugin");
+    std::fs::create_dir_all(&plugin_dir).unwrap();
+    let install = || {
+        let args = PluginInstallArgs {
+            plugins: vec![PluginVersion::Name("wasi_nn".to_string())],
+            tmpdir: Some(tmpdir.path().join("work")),
+            runtime: None,
+            path: Some(install_dir.clone()),
+            plugin_dir: None,
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    let plugin_files = || {
+        std::fs::read_dir(&plugin_dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .count()
+    };
+
+    mock.mount_release(VERSION, &archive_name, archive.clone(), &"0".repeat(64))
+        .await;
+    let err = install().await.unwrap_err();
+    assert!(matches!(err, Error::ChecksumMismatch { .. }), "{err:?}");
+    assert_eq!(plugin_files(), 0);
+
+    mock.server.reset().await;
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &archive_name, archive, &checksum)
+        .await;
+    // One of the two files cannot be written, so neither may stay.
+    let blocker = plugin_dir.join(format!("libwasmedgePluginWasiNNExtra.{ext}"));
+    std::fs::create_dir(&blocker).unwrap();
+    let err = install().await.unwrap_err();
+    assert!(matches!(err, Error::Io { .. }), "{err:?}");
+    assert_eq!(plugin_files(), 0);
+
+    std::fs::remove_dir(&blocker).unwrap();
+    install().await.expect("plugin install failed");
+    assert_eq!(plugin_files(), 2);
+}
@@ -0,0 +1,79 @@
+#![cfg(unix)]
+
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    cli::{CommandContext, CommandExecutor},
+    commands::{install::InstallArgs, remove::RemoveArgs},
+    manifest::InstallManifest,
+    target::{TargetArch, TargetOS},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_prefix_install_and_remove_leave_other_files() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let tmpdir = tempdir().unwrap();
+    let prefix = tmpdir.path().join("usr_local");
+    let unrelated = ["bin/other-tool", "lib/libother.so", "include/other.h"];
+    for name in unrelated {
+        let path = prefix.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, name).unwrap();
+    }
+
+    // Installing twice, the second time with --force, replaces only WasmEdge's own files.
+    for force in [false, true] {
+        let args = InstallArgs {
+            version: VERSION.to_string(),
+            prefix: Some(prefix.clone()),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_cache: true,
+            force,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx).await.expect("prefix install failed");
+    }
+
+    assert!(prefix.join("bin/wasmedge").is_file());
+    assert!(prefix.join("lib/libwasmedge.so").is_file());
+    assert!(!prefix.join("versions").exists());
+    for name in unrelated {
+        assert_eq!(std::fs::read_to_string(prefix.join(name)).unwrap(), name);
+    }
+    let manifest_dir = InstallManifest::prefix_dir(&prefix);
+    let manifest = InstallManifest::load(&manifest_dir, VERSION).unwrap();
+    assert_eq!(manifest.files.len(), 3);
+
+    let args = RemoveArgs {
+        prefix: Some(prefix.clone()),
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("prefix remove failed");
+
+    assert!(!prefix.join("bin/wasmedge").exists());
+    assert!(!prefix.join("lib/libwasmedge.so").exists());
+    assert!(!prefix.join("include/wasmedge").exists());
+    assert!(!manifest_dir.exists());
+    for name in unrelated {
+        assert_eq!(std::fs::read_to_string(prefix.join(name)).unwrap(), name);
+    }
+}
@@ -0,0 +1,163 @@
+#![cfg(unix)]
+
+use clap::Parser;
+use semver::Version;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    api::Asset,
+    cli::{CommandContext, CommandExecutor},
+    commands::install::InstallArgs,
+    target::{TargetArch, TargetOS},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{linux_archive, sha256_hex, MockRelease};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_keep_versions_prunes_older_managed_versions() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    for version in ["0.13.0", "0.14.0", "0.14.1"] {
+        let asset = Asset::new(
+            &Version::parse(version).unwrap(),
+            &TargetOS::Linux,
+            &TargetArch::X86_64,
+        );
+        let archive = linux_archive(&asset.version);
+        let checksum = sha256_hex(&archive);
+        mock.mount_release(version, &asset.archive_name, archive, &checksum)
+            .await;
+    }
+
+    let tmpdir = tempdir().unwrap();
+    let target_dir = tmpdir.path().join("wasmedge");
+    let install = |version: &str, keep_versions| {
+        let args = InstallArgs {
+            version: version.to_string(),
+            path: Some(target_dir.clone()),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_setup_path: true,
+            no_cache: true,
+            keep_versions,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    install("0.14.1", None).await.expect("install failed");
+    install("0.13.0", None).await.expect("install failed");
+    // Not installed by wasmedgeup, as far as it can tell.
+    std::fs::create_dir_all(target_dir.join("versions").join("0.12.0").join("bin")).unwrap();
+
+    install("0.14.0", Some(1)).await.expect("install failed");
+
+    let versions_dir = target_dir.join("versions");
+    // The newest, the active one and the one without a manifest stay.
+    assert!(versions_dir.join("0.14.1").is_dir());
+    assert!(versions_dir.join("0.14.0").is_dir());
+    assert!(versions_dir.join("0.12.0").is_dir());
+    assert!(!versions_dir.join("0.13.0").exists());
+    assert_eq!(
+        std::fs::read_link(target_dir.join("bin")).unwrap(),
+        std::path::Path::new("versions/0.14.0/bin")
+    );
+
+    assert!(InstallArgs::try_parse_from(["install", "0.14.1", "--keep-versions", "0"]).is_err());
+    assert!(InstallArgs::try_parse_from([
+        "install",
+        "0.14.1",
+        "--keep-versions",
+        "2",
+        "--prefix",
+        "/opt"
+    ])
+    .is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_prune_keeps_active_pinned_and_listed_versions() {
+    use wasmedgeup::commands::prune::{PruneArgs, PIN_FILE_NAME};
+
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    let tmpdir = tempdir().unwrap();
+    let target_dir = tmpdir.path().join("wasmedge");
+    for version in ["0.12.1", "0.13.0", "0.14.0", "0.14.1"] {
+        let asset = Asset::new(
+            &Version::parse(version).unwrap(),
+            &TargetOS::Linux,
+            &TargetArch::X86_64,
+        );
+        let archive = linux_archive(&asset.version);
+        let checksum = sha256_hex(&archive);
+        mock.mount_release(version, &asset.archive_name, archive, &checksum)
+            .await;
+        let args = InstallArgs {
+            version: version.to_string(),
+            path: Some(target_dir.clone()),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_setup_path: true,
+            no_cache: true,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx).await.expect("install failed");
+    }
+    let prune = |keep: &[&str], dry_run| {
+        let args = PruneArgs {
+            keep: keep.iter().map(|v| Version::parse(v).unwrap()).collect(),
+            dry_run,
+            path: Some(target_dir.clone()),
+        };
+        args.execute(CommandContext::default())
+    };
+    let versions_dir = target_dir.join("versions");
+    let installed = || {
+        let mut versions = std::fs::read_dir(&versions_dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        versions.sort();
+        versions
+    };
+
+    std::fs::write(
+        target_dir.join(PIN_FILE_NAME),
+        "0.13.0  # production\nlatest\n",
+    )
+    .unwrap();
+    let err = prune(&[], false).await.unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    assert_eq!(installed().len(), 4);
+
+    std::fs::write(
+        target_dir.join(PIN_FILE_NAME),
+        "# pinned\n0.13.0  # production\n",
+    )
+    .unwrap();
+    prune(&["0.14.0"], true).await.expect("dry run failed");
+    assert_eq!(installed().len(), 4);
+
+    prune(&["0.14.0"], false).await.expect("prune failed");
+    assert_eq!(installed(), ["0.13.0", "0.14.0", "0.14.1"]);
+
+    // Only the active version is left without a pin or --keep.
+    std::fs::remove_file(target_dir.join(PIN_FILE_NAME)).unwrap();
+    prune(&[], false).await.expect("prune failed");
+    assert_eq!(installed(), ["0.14.1"]);
+}
@@ -0,0 +1,131 @@
+#![cfg(unix)]
+
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    cli::{CommandContext, CommandExecutor},
+    commands::{install::InstallArgs, reinstall::ReinstallArgs, remove::RemoveArgs},
+    error::Error,
+    manifest::InstallManifest,
+    target::{TargetArch, TargetOS},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{install_from, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_reinstall_restores_active_version() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let (result, tmpdir) = install_from(&mock).await;
+    result.expect("install failed");
+
+    let install_dir = tmpdir.path().join("install_target");
+    let version_dir = install_dir.join("versions").join(VERSION);
+    std::fs::write(version_dir.join("bin").join("wasmedge"), "corrupt").unwrap();
+    std::fs::write(version_dir.join("bin").join("stray"), "stray").unwrap();
+
+    let args = ReinstallArgs {
+        path: Some(install_dir.clone()),
+        tmpdir: Some(tmpdir.path().join("work")),
+        no_cache: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("reinstall failed");
+
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert!(manifest.verify(&version_dir).unwrap().is_empty());
+    assert!(!version_dir.join("bin").join("stray").exists());
+}
+
+#[tokio::test]
+async fn test_reinstall_without_active_version() {
+    let tmpdir = tempdir().unwrap();
+    let args = ReinstallArgs {
+        path: Some(tmpdir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let result = args.execute(CommandContext::default()).await;
+    assert!(matches!(result, Err(Error::InvalidPath { .. })));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn test_read_only_install_can_be_reinstalled_and_removed() {
+    use std::os::unix::fs::PermissionsExt;
+    use wasmedgeup::commands::install::ReadOnlyScope;
+
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+
+    let tmpdir = tempdir().unwrap();
+    let target_dir = tmpdir.path().join("wasmedge");
+    let install = |read_only, force| {
+        let args = InstallArgs {
+            version: VERSION.to_string(),
+            path: Some(target_dir.clone()),
+            tmpdir: Some(tmpdir.path().join("work")),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+            no_cache: true,
+            no_setup_path: true,
+            read_only,
+            force,
+            ..Default::default()
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    let version_dir = target_dir.join("versions").join(VERSION);
+    let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode();
+
+    install(Some(ReadOnlyScope::Files), false)
+        .await
+        .expect("read-only install failed");
+    assert_eq!(mode(&version_dir.join("bin/wasmedge")) & 0o222, 0);
+    assert_ne!(mode(&version_dir.join("bin")) & 0o200, 0);
+
+    // Installing again over it, without `--force`, restores write permission first.
+    install(None, false).await.expect("reinstall failed");
+    assert_ne!(mode(&version_dir.join("bin/wasmedge")) & 0o200, 0);
+
+    install(Some(ReadOnlyScope::All), true)
+        .await
+        .expect("forced read-only install failed");
+    assert_eq!(mode(&version_dir.join("bin/wasmedge")) & 0o222, 0);
+    assert_eq!(mode(&version_dir.join("bin")) & 0o222, 0);
+    assert_eq!(mode(&version_dir) & 0o222, 0);
+
+    install(Some(ReadOnlyScope::All), true)
+        .await
+        .expect("forced reinstall over a read-only install failed");
+
+    let args = RemoveArgs {
+        version: VERSION.to_string(),
+        path: Some(target_dir.clone()),
+        yes: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    args.execute(ctx)
+        .await
+        .expect("removing a read-only install failed");
+    assert!(!version_dir.exists());
+}
@@ -19,8 +19,8 @@ async fn test_remove_single_version() {
 
     let remove_args = RemoveArgs {
         version: version.to_string(),
-        all: false,
         path: Some(test_home.clone()),
+        ..Default::default()
     };
     let ctx = CommandContext {
         client: WasmEdgeApiClient::default(),
@@ -46,8 +46,8 @@ async fn test_remove_multiple_versions() {
     for (idx, version) in ordered_versions.iter().enumerate() {
         let remove_args = RemoveArgs {
             version: (*version).to_string(),
-            all: false,
             path: Some(test_home.clone()),
+            ..Default::default()
         };
         let ctx = CommandContext {
             client: WasmEdgeApiClient::default(),
@@ -97,9 +97,10 @@ async fn test_remove_all_versions() {
     }
 
     let remove_args = RemoveArgs {
-        version: String::new(),
         all: true,
+        yes: true,
         path: Some(test_home.clone()),
+        ..Default::default()
     };
     let ctx = CommandContext {
         client: WasmEdgeApiClient::default(),
@@ -114,14 +115,113 @@ async fn test_remove_all_versions() {
     );
 }
 
+#[tokio::test]
+async fn test_remove_all_keeps_root_with_other_files() {
+    let (_tempdir, test_home) = test_utils::setup_test_environment();
+    let root = test_home.join(".wasmedge");
+    for version in ["0.14.1", "0.15.0"] {
+        setup_mock_version(&root.join("versions").join(version), version).await;
+    }
+    std::fs::write(root.join("notes.txt"), "keep me").unwrap();
+
+    let remove_args = RemoveArgs {
+        all: true,
+        yes: true,
+        path: Some(root.clone()),
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: WasmEdgeApiClient::default(),
+        no_progress: true,
+    };
+    remove_args.execute(ctx).await.unwrap();
+
+    assert!(!root.join("versions").exists());
+    assert!(std::fs::symlink_metadata(root.join("bin")).is_err());
+    assert_eq!(
+        std::fs::read_to_string(root.join("notes.txt")).unwrap(),
+        "keep me"
+    );
+
+    std::fs::remove_file(root.join("notes.txt")).unwrap();
+    setup_mock_version(&root.join("versions/0.15.0"), "0.15.0").await;
+    let remove_args = RemoveArgs {
+        all: true,
+        yes: true,
+        path: Some(root.clone()),
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: WasmEdgeApiClient::default(),
+        no_progress: true,
+    };
+    remove_args.execute(ctx).await.unwrap();
+    assert!(!root.exists(), "an empty install root should be removed");
+}
+
+#[test]
+fn test_uninstall_all_requires_confirmation() {
+    let (_tempdir, test_home) = test_utils::setup_test_environment();
+    let root = test_home.join(".wasmedge");
+    std::fs::create_dir_all(root.join("versions/0.14.1/bin")).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .args(["uninstall", "--all", "--path"])
+        .arg(&root)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pass --yes"), "{stderr}");
+    assert!(root.join("versions/0.14.1/bin").is_dir());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_remove_last_version_through_symlinked_root() {
+    let (_tempdir, test_home) = test_utils::setup_test_environment();
+
+    // The install root is a symlink to a directory that also holds unrelated files.
+    let real_root = test_home.join("volume");
+    let root = test_home.join(".wasmedge");
+    let version = "0.14.1";
+    setup_mock_version(&real_root.join("versions").join(version), version).await;
+    std::fs::write(real_root.join("notes.txt"), "keep me").unwrap();
+    std::os::unix::fs::symlink(&real_root, &root).unwrap();
+
+    let remove_args = RemoveArgs {
+        version: version.to_string(),
+        path: Some(root.clone()),
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: WasmEdgeApiClient::default(),
+        no_progress: true,
+    };
+    remove_args.execute(ctx).await.unwrap();
+
+    assert!(
+        root.symlink_metadata().is_err(),
+        "The symlinked root should be unlinked"
+    );
+    assert!(!real_root.join("versions").join(version).exists());
+    assert_eq!(
+        std::fs::read_to_string(real_root.join("notes.txt")).unwrap(),
+        "keep me",
+        "Files behind the symlink should not be deleted"
+    );
+}
+
 #[tokio::test]
 async fn test_remove_nonexistent_version() {
     let (_tempdir, test_home) = test_utils::setup_test_environment();
 
     let remove_args = RemoveArgs {
         version: "0.99.99".to_string(),
-        all: false,
         path: Some(test_home),
+        ..Default::default()
     };
     let ctx = CommandContext {
         client: WasmEdgeApiClient::default(),
@@ -0,0 +1,216 @@
+#![cfg(unix)]
+
+use semver::Version;
+use serial_test::serial;
+use tempfile::tempdir;
+use wasmedgeup::{
+    api::{ReleasesFilter, ResolveVia},
+    cli::{CommandContext, CommandExecutor},
+    commands::{check_update::CheckUpdateArgs, install::InstallArgs},
+    error::Error,
+    target::{TargetArch, TargetOS},
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{install_from, install_with, MockRelease, VERSION};
+use test_utils::setup_test_environment;
+
+#[tokio::test]
+#[serial]
+async fn test_releases_from_mock_repository() {
+    let mock = MockRelease::start().await;
+    mock.mount_tags(&["0.13.5", "0.14.1", "0.15.0-rc.1", "not-a-version"])
+        .await;
+    let client = mock.client();
+
+    let all = client.releases(ReleasesFilter::All, 10).await.unwrap();
+    assert_eq!(
+        all,
+        ["0.15.0-rc.1", "0.14.1", "0.13.5"].map(|v| Version::parse(v).unwrap())
+    );
+
+    let latest = client.resolve_version("latest").await.unwrap();
+    assert_eq!(latest, Version::parse("0.14.1").unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_resolve_via_api_and_git() {
+    let mock = MockRelease::start().await;
+    mock.mount_api_releases(&[
+        ("0.15.0-rc.1", false),
+        ("0.14.1", false),
+        ("0.16.0", true),
+        ("0.13.5", false),
+    ])
+    .await;
+    mock.mount_tags(&["0.13.5"]).await;
+
+    let api = mock.client().with_resolve_via(ResolveVia::Api);
+    assert_eq!(api.latest_release().await.unwrap(), Version::new(0, 14, 1));
+    // Drafts are not releases.
+    assert_eq!(
+        api.releases(ReleasesFilter::All, 10).await.unwrap(),
+        ["0.15.0-rc.1", "0.14.1", "0.13.5"].map(|v| Version::parse(v).unwrap())
+    );
+
+    let auto = mock.client();
+    assert_eq!(auto.latest_release().await.unwrap(), Version::new(0, 14, 1));
+
+    let git = mock.client().with_resolve_via(ResolveVia::Git);
+    assert_eq!(git.latest_release().await.unwrap(), Version::new(0, 13, 5));
+
+    // Partial versions pick the newest stable release of their line; full ones stay exact.
+    for (requested, expected) in [("0.14", "0.14.1"), ("0", "0.14.1"), ("0.14.1", "0.14.1")] {
+        assert_eq!(
+            api.resolve_version(requested).await.unwrap().to_string(),
+            expected
+        );
+    }
+    assert!(matches!(
+        api.resolve_version("0.15").await,
+        Err(Error::VersionNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_resolve_via_auto_falls_back_to_git() {
+    let mock = MockRelease::start().await;
+    mock.mount_tags(&["0.13.5", "0.14.1"]).await;
+    Mock::given(method("GET"))
+        .and(path("/api/releases"))
+        .respond_with(ResponseTemplate::new(403).set_body_string("API rate limit exceeded"))
+        .mount(&mock.server)
+        .await;
+
+    let auto = mock.client();
+    assert_eq!(auto.latest_release().await.unwrap(), Version::new(0, 14, 1));
+
+    let api = mock.client().with_resolve_via(ResolveVia::Api);
+    let result = api.latest_release().await;
+    assert!(
+        matches!(&result, Err(Error::LatestVersionUnresolved { source }) if matches!(**source, Error::Request { .. })),
+        "{result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_latest_release_retries_then_suggests_explicit_version() {
+    let mock = MockRelease::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/releases"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&mock.server)
+        .await;
+
+    let client = mock
+        .client()
+        .with_resolve_via(ResolveVia::Api)
+        .with_retries(2);
+    let err = client.resolve_version("latest").await.unwrap_err();
+    assert!(
+        matches!(&err, Error::LatestVersionUnresolved { source } if source.is_transient()),
+        "{err:?}"
+    );
+    assert!(
+        err.to_string().contains("Pass an explicit version"),
+        "{err}"
+    );
+
+    // Exact versions never need the release listing.
+    assert_eq!(
+        client.resolve_version(VERSION).await.unwrap(),
+        Version::parse(VERSION).unwrap()
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_check_update_compares_active_version_with_latest() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_linux_release().await;
+    mock.mount_api_releases(&[(VERSION, false), ("0.15.0-rc.1", false)])
+        .await;
+    let args = InstallArgs {
+        no_setup_path: true,
+        ..Default::default()
+    };
+    let (result, tmpdir) = install_with(&mock, args).await;
+    result.expect("install failed");
+    let install_dir = tmpdir.path().join("install_target");
+
+    let check = |client| {
+        let args = CheckUpdateArgs {
+            path: Some(install_dir.clone()),
+            json: true,
+        };
+        let ctx = CommandContext {
+            client,
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+    check(mock.client()).await.expect("no update expected");
+
+    let newer = MockRelease::start().await;
+    newer
+        .mount_api_releases(&[("0.15.0", false), (VERSION, false)])
+        .await;
+    let result = check(newer.client()).await;
+    assert!(
+        matches!(&result, Err(Error::UpdateAvailable { current, latest }) if current == VERSION && latest == "0.15.0"),
+        "got {result:?}"
+    );
+    assert_eq!(result.unwrap_err().exit_code(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unpublished_exact_version_fails_before_download() {
+    let (_home, _home_path) = setup_test_environment();
+    let mock = MockRelease::start().await;
+    mock.mount_api_releases(&[("0.14.1", false), ("0.14.0", false), ("0.13.5", false)])
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let args = InstallArgs {
+        version: "0.41.1".to_string(),
+        path: Some(tmpdir.path().join("install_target")),
+        tmpdir: Some(tmpdir.path().join("work")),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client().with_resolve_via(ResolveVia::Api),
+        no_progress: true,
+    };
+    let err = args.execute(ctx).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::VersionNotPublished { close, .. } if close == &["0.14.1"]),
+        "{err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "Version 0.41.1 not found among the published releases; did you mean 0.14.1?"
+    );
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(
+        requests.iter().all(|r| r.url.path() == "/api/releases"),
+        "{requests:?}"
+    );
+
+    // Listed versions install as before.
+    mock.mount_linux_release().await;
+    let (result, _tmpdir) = install_from(&mock).await;
+    result.expect("install failed");
+}
@@ -116,11 +116,51 @@ fn test_library_path_append() {
     }
 }
 
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_shadowing_wasmedge_finds_an_earlier_binary_on_path() {
+    use wasmedgeup::environment::Environment;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let install_dir = temp_dir.path().join("wasmedge");
+    let system_bin = temp_dir.path().join("usr/bin");
+    let empty_bin = temp_dir.path().join("empty");
+    for bin in [
+        install_dir.join("bin"),
+        system_bin.clone(),
+        empty_bin.clone(),
+    ] {
+        std::fs::create_dir_all(&bin).unwrap();
+    }
+    for bin in [install_dir.join("bin"), system_bin.clone()] {
+        std::fs::write(bin.join("wasmedge"), "#!/bin/sh\n").unwrap();
+    }
+    let shadowing = |dirs: &[&std::path::Path]| {
+        let path = env::join_paths(dirs).unwrap();
+        let _active = Environment::current().with_var("PATH", path).activate();
+        shell_utils::shadowing_wasmedge(&install_dir)
+    };
+    let ours = install_dir.join("bin");
+
+    assert_eq!(
+        shadowing(&[&empty_bin, &system_bin, &ours]),
+        Some(system_bin.join("wasmedge"))
+    );
+    assert_eq!(shadowing(&[&empty_bin, &ours, &system_bin]), None);
+    assert_eq!(shadowing(&[&empty_bin]), None);
+    // The install found through a symlinked directory is still this install.
+    let link = temp_dir.path().join("link");
+    std::os::unix::fs::symlink(&ours, &link).unwrap();
+    assert_eq!(shadowing(&[&link, &system_bin]), None);
+}
+
 #[cfg(all(test, unix))]
 mod setup_uninstall {
     use super::setup_test_environment;
     use serial_test::serial;
     use std::fs;
+    use wasmedgeup::environment::Environment;
     use wasmedgeup::prelude::*;
     use wasmedgeup::shell_utils;
 
@@ -250,6 +290,167 @@ mod setup_uninstall {
         assert!(rc_after.contains(existing));
         assert!(!rc_after.contains(&expected_source));
     }
+
+    #[test]
+    #[serial]
+    fn test_tcsh_rc_file() {
+        let (_tmp_home, home) = setup_test_environment();
+
+        let install_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(install_dir.path().join("bin")).unwrap();
+
+        // Scoped, so later tests see the SHELL they started with.
+        let _shell = Environment::current()
+            .with_var("SHELL", "/bin/tcsh")
+            .activate();
+
+        shell_utils::setup_path(install_dir.path()).unwrap();
+
+        // Without a ~/.tcshrc, ~/.cshrc is created; both csh and tcsh read it.
+        let cshrc = home.join(".cshrc");
+        let expected_source = format!(r#"source "{}/env.csh""#, install_dir.path().display());
+        let content = fs::read_to_string(&cshrc).unwrap();
+        assert!(content.contains(&expected_source), "{content}");
+        assert!(!home.join(".tcshrc").exists());
+
+        let env_csh = fs::read_to_string(install_dir.path().join("env.csh")).unwrap();
+        assert!(env_csh.contains(&format!(
+            r#"setenv PATH "{}/bin:${{PATH}}""#,
+            install_dir.path().display()
+        )));
+        assert!(!env_csh.contains("export "));
+
+        shell_utils::uninstall_path(install_dir.path()).unwrap();
+        assert!(!fs::read_to_string(&cshrc)
+            .unwrap()
+            .contains(&expected_source));
+        assert!(!install_dir.path().join("env.csh").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test_tcsh_prefers_existing_tcshrc() {
+        let (_tmp_home, home) = setup_test_environment();
+        let tcshrc = home.join(".tcshrc");
+        fs::write(&tcshrc, "set prompt = '> '").unwrap();
+
+        let install_dir = tempfile::tempdir().unwrap();
+        let _shell = Environment::current()
+            .with_var("SHELL", "/bin/zsh")
+            .activate();
+
+        // An existing rc file is enough to detect the shell.
+        shell_utils::setup_path(install_dir.path()).unwrap();
+
+        let expected_source = format!(r#"source "{}/env.csh""#, install_dir.path().display());
+        let content = fs::read_to_string(&tcshrc).unwrap();
+        assert_eq!(content, format!("set prompt = '> '\n{expected_source}\n"));
+        assert!(!home.join(".cshrc").exists());
+
+        shell_utils::uninstall_path(install_dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(&tcshrc).unwrap(), "set prompt = '> '\n");
+    }
+
+    #[test]
+    #[serial]
+    fn test_read_only_rc_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_tmp_home, home) = setup_test_environment();
+        let zshenv = home.join(".zshenv");
+        fs::set_permissions(&zshenv, fs::Permissions::from_mode(0o444)).unwrap();
+        if fs::OpenOptions::new().append(true).open(&zshenv).is_ok() {
+            // Running as root, which may write read-only files; a directory cannot be appended to.
+            fs::remove_file(&zshenv).unwrap();
+            fs::create_dir(&zshenv).unwrap();
+        }
+
+        let install_dir = tempfile::tempdir().unwrap();
+        let _shell = Environment::current()
+            .with_var("SHELL", "/bin/zsh")
+            .activate();
+
+        let err = shell_utils::setup_path(install_dir.path()).unwrap_err();
+        match &err {
+            Error::Io { action, path, .. } => {
+                assert_eq!(action, "update shell config");
+                assert_eq!(path, &zshenv.display().to_string());
+            }
+            other => panic!("expected an Io error, got {other:?}"),
+        }
+        assert!(err.to_string().starts_with(&format!(
+            "Failed to update shell config at {}",
+            zshenv.display()
+        )));
+    }
+
+    #[test]
+    #[serial]
+    fn test_prepend_path_moves_the_install_ahead_of_another_wasmedge() {
+        use std::os::unix::fs::PermissionsExt;
+        use wasmedgeup::environment::Environment;
+
+        let (_tmp_home, _home) = setup_test_environment();
+        let root = tempfile::tempdir().unwrap();
+        let install_dir = root.path().join("wasmedge");
+        let system_bin = root.path().join("usr-bin");
+        for dir in [install_dir.join("bin"), system_bin.clone()] {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("wasmedge"), "#!/bin/sh\n").unwrap();
+            fs::set_permissions(dir.join("wasmedge"), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let bin = install_dir.join("bin");
+        let shadowed_path = format!("{}:{}", system_bin.display(), bin.display());
+
+        let found = {
+            let _active = Environment::current()
+                .with_var("PATH", &shadowed_path)
+                .activate();
+            shell_utils::shadowing_wasmedge(&install_dir)
+        };
+        assert_eq!(found, Some(system_bin.join("wasmedge")));
+        let _active = Environment::current()
+            .with_var(
+                "PATH",
+                format!("{}:{}", bin.display(), system_bin.display()),
+            )
+            .activate();
+        assert_eq!(shell_utils::shadowing_wasmedge(&install_dir), None);
+
+        let path_after_sourcing = || {
+            let output = std::process::Command::new("/bin/sh")
+                .arg("-c")
+                .arg(format!(
+                    ". \"{}/env\" && printf %s \"$PATH\"",
+                    install_dir.display()
+                ))
+                .env("PATH", &shadowed_path)
+                .output()
+                .unwrap();
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        // Already on PATH, so the default setup leaves it behind the other one.
+        shell_utils::setup_path(&install_dir).unwrap();
+        assert_eq!(path_after_sourcing(), shadowed_path);
+
+        shell_utils::setup_path_with(&install_dir, true).unwrap();
+        assert_eq!(
+            path_after_sourcing(),
+            format!("{}:{shadowed_path}", bin.display())
+        );
+
+        // The rc files source the same script either way, so it comes out the same.
+        shell_utils::uninstall_path(&install_dir).unwrap();
+        assert!(!install_dir.join("env").exists());
+        let source_line = format!(r#". "{}/env""#, install_dir.display());
+        for shell in shell_utils::get_available_shells() {
+            for rc in shell.effective_rc_files() {
+                let content = fs::read_to_string(&rc).unwrap_or_default();
+                assert!(!content.contains(&source_line), "{}", rc.display());
+            }
+        }
+    }
 }
 
 #[cfg(all(test, windows))]
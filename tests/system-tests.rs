@@ -136,3 +136,44 @@ fn test_plugin_platform_key_darwin_major() {
     let key_fallback = plugin_platform_key(&os, &v).expect("darwin generic key");
     assert_eq!(key_fallback, "darwin_x86_64");
 }
+
+#[test]
+fn test_emulation_markers() {
+    use wasmedgeup::system::emulation::emulation_from;
+
+    let (build, other, other_name) = match TargetArch::default() {
+        TargetArch::X86_64 => ("amd64", TargetArch::Aarch64, "arm64"),
+        TargetArch::Aarch64 => ("arm64", TargetArch::X86_64, "amd64"),
+    };
+    let no_vars = |_: &str| None;
+    assert_eq!(emulation_from(no_vars, None), None);
+
+    let emulation = emulation_from(|name| (name == "QEMU_LD_PREFIX").then(|| "/".into()), None);
+    assert_eq!(emulation.unwrap().marker, "QEMU_LD_PREFIX is set");
+
+    let platform = format!("linux/{other_name}/v8");
+    let emulation = emulation_from(
+        |name| (name == "TARGETPLATFORM").then(|| platform.clone()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(emulation.target, Some(other));
+    assert_eq!(
+        emulation_from(|name| (name == "TARGETARCH").then(|| build.into()), None),
+        None
+    );
+
+    let x86_cpuinfo = "processor\t: 0\nvendor_id\t: GenuineIntel\nflags\t\t: fpu sse2\n";
+    let arm_cpuinfo = "processor\t: 0\nFeatures\t: fp asimd\nCPU implementer\t: 0x41\n";
+    let (native, foreign) = match other {
+        TargetArch::Aarch64 => (x86_cpuinfo, arm_cpuinfo),
+        TargetArch::X86_64 => (arm_cpuinfo, x86_cpuinfo),
+    };
+    assert_eq!(emulation_from(no_vars, Some(native)), None);
+    let emulation = emulation_from(no_vars, Some(foreign)).unwrap();
+    assert!(
+        emulation.marker.starts_with("/proc/cpuinfo"),
+        "{emulation:?}"
+    );
+    assert_eq!(emulation.target, None);
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use wasmedgeup::units::{ByteSize, Seconds, Ttl};
+
+fn parse(s: &str) -> Result<u64, String> {
+    s.parse::<ByteSize>().map(|size| size.0)
+}
+
+#[test]
+fn test_byte_size_display_uses_binary_units() {
+    assert_eq!(ByteSize(0).to_string(), "0 B");
+    assert_eq!(ByteSize(1023).to_string(), "1023 B");
+    assert_eq!(ByteSize(1024).to_string(), "1.00 KiB");
+    assert_eq!(ByteSize(3 * 1024 * 1024 / 2).to_string(), "1.50 MiB");
+    assert_eq!(ByteSize(5 << 30).to_string(), "5.00 GiB");
+    assert_eq!(ByteSize(u64::MAX).to_string(), "16384.00 PiB");
+}
+
+#[test]
+fn test_byte_size_parsing_is_lenient() {
+    assert_eq!(parse("512"), Ok(512));
+    assert_eq!(parse(" 512 B "), Ok(512));
+    assert_eq!(parse("2 KiB"), Ok(2048));
+    assert_eq!(parse("2kib"), Ok(2048));
+    assert_eq!(parse("1,5MiB"), Ok(3 * 1024 * 1024 / 2));
+    assert_eq!(parse("1.5mib"), Ok(3 * 1024 * 1024 / 2));
+    assert_eq!(parse("1GiB"), Ok(1 << 30));
+    assert_eq!(parse(".5KiB"), Ok(512));
+}
+
+#[test]
+fn test_byte_size_parsing_rejects_ambiguous_input() {
+    for input in [
+        "",
+        "MiB",
+        "1.5 XB",
+        "1,5.0MiB",
+        "1,000MiB",
+        "1..5KiB",
+        "-1KiB",
+        "99999999PiB",
+    ] {
+        assert!(parse(input).is_err(), "{input:?} should not parse");
+    }
+    // Decimal-looking units could mean 1000 or 1024 bytes.
+    for input in ["2k", "2KB", "1g", "1.5 MB"] {
+        let err = parse(input).unwrap_err();
+        assert!(err.contains("ambiguous"), "{input:?}: {err}");
+    }
+    assert!(parse("2KB").unwrap_err().contains("KiB"));
+    let err = parse("1,000KiB").unwrap_err();
+    assert!(err.contains("digit grouping"), "{err}");
+}
+
+#[test]
+fn test_seconds_display() {
+    assert_eq!(Seconds(Duration::from_millis(3420)).to_string(), "3.4s");
+    assert_eq!(Seconds(Duration::ZERO).to_string(), "0.0s");
+}
+
+#[test]
+fn test_ttl_parsing() {
+    let ttl = |s: &str| s.parse::<Ttl>().map(|ttl| ttl.0);
+    assert_eq!(ttl("never"), Ok(None));
+    assert_eq!(ttl("Never"), Ok(None));
+    assert_eq!(ttl("0"), Ok(Some(Duration::ZERO)));
+    assert_eq!(ttl("90"), Ok(Some(Duration::from_secs(90))));
+    assert_eq!(ttl("90s"), Ok(Some(Duration::from_secs(90))));
+    assert_eq!(ttl("30m"), Ok(Some(Duration::from_secs(30 * 60))));
+    assert_eq!(ttl("12 h"), Ok(Some(Duration::from_secs(12 * 3600))));
+    assert_eq!(ttl("7D"), Ok(Some(Duration::from_secs(7 * 86400))));
+    for input in ["", "h", "1.5h", "-1d", "1w", "99999999999999999999d"] {
+        assert!(ttl(input).is_err(), "{input:?} should not parse");
+    }
+}
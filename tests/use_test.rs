@@ -47,6 +47,40 @@ async fn test_use_version() {
     verify_symlinks(&test_home, "0.15.0").await;
 }
 
+#[tokio::test]
+async fn test_use_partial_version_picks_newest_installed() {
+    let (_tempdir, test_home) = test_utils::setup_test_environment();
+
+    for version in ["0.13.5", "0.14.0", "0.14.1", "0.15.0"] {
+        let bin_dir = test_home.join("versions").join(version).join("bin");
+        tokio::fs::create_dir_all(&bin_dir).await.unwrap();
+        tokio::fs::create_dir_all(test_home.join("versions").join(version).join("lib"))
+            .await
+            .unwrap();
+        tokio::fs::create_dir_all(test_home.join("versions").join(version).join("include"))
+            .await
+            .unwrap();
+    }
+
+    for (requested, expected) in [("0.14", "0.14.1"), ("0", "0.15.0"), ("0.14.0", "0.14.0")] {
+        let args = UseArgs {
+            version: requested.to_string(),
+            path: Some(test_home.clone()),
+        };
+        args.execute(CommandContext::default()).await.unwrap();
+        verify_symlinks(&test_home, expected).await;
+    }
+
+    let args = UseArgs {
+        version: "0.12".to_string(),
+        path: Some(test_home.clone()),
+    };
+    assert!(matches!(
+        args.execute(CommandContext::default()).await,
+        Err(wasmedgeup::error::Error::VersionNotFound { .. })
+    ));
+}
+
 #[tokio::test]
 async fn test_use_latest_version() {
     let (_tempdir, test_home) = test_utils::setup_test_environment();
@@ -0,0 +1,509 @@
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+
+use semver::Version;
+use serial_test::serial;
+use tempfile::{tempdir, TempDir};
+use wasmedgeup::{
+    api::{Asset, ChecksumSource, Published},
+    cli::{CommandContext, CommandExecutor},
+    commands::{install::InstallArgs, verify::VerifyArgs, verify_file::VerifyFileArgs},
+    error::Error,
+    manifest::{InstallManifest, MANIFEST_FILE_NAME},
+    target::{TargetArch, TargetOS},
+};
+
+mod mock_server;
+mod test_utils;
+use mock_server::{linux_archive, linux_asset, sha256_hex, MockRelease};
+use test_utils::setup_test_environment;
+
+const VERSION: &str = "0.14.1";
+
+async fn install_fixture(tmpdir: &TempDir) -> PathBuf {
+    let mock = MockRelease::start().await;
+    let asset = Asset::new(
+        &Version::parse(VERSION).unwrap(),
+        &TargetOS::Linux,
+        &TargetArch::X86_64,
+    );
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive, &checksum)
+        .await;
+
+    let install_dir = tmpdir.path().join("install_target");
+    let args = InstallArgs {
+        version: VERSION.to_string(),
+        path: Some(install_dir.clone()),
+        tmpdir: Some(tmpdir.path().join("work")),
+        os: Some(TargetOS::Linux),
+        arch: Some(TargetArch::X86_64),
+        no_cache: true,
+        ..Default::default()
+    };
+    let ctx = CommandContext {
+        client: mock.client(),
+        no_progress: true,
+    };
+    args.execute(ctx).await.expect("install failed");
+    install_dir
+}
+
+async fn verify(install_dir: &Path, version: Option<&str>) -> Result<(), Error> {
+    let args = VerifyArgs {
+        version: version.map(str::to_string),
+        path: Some(install_dir.to_path_buf()),
+    };
+    args.execute(CommandContext::default()).await
+}
+
+#[tokio::test]
+#[serial]
+async fn test_install_writes_manifest() {
+    let (_home, _home_path) = setup_test_environment();
+    let tmpdir = tempdir().unwrap();
+    let install_dir = install_fixture(&tmpdir).await;
+
+    let version_dir = install_dir.join("versions").join(VERSION);
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert_eq!(manifest.version, VERSION);
+    assert!(manifest.files.contains_key("bin/wasmedge"));
+    assert!(manifest.files.contains_key("include/wasmedge/wasmedge.h"));
+    assert!(!manifest.files.contains_key(MANIFEST_FILE_NAME));
+
+    verify(&install_dir, Some(VERSION)).await.unwrap();
+    verify(&install_dir, None).await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_verify_detects_modified_and_missing_files() {
+    let (_home, _home_path) = setup_test_environment();
+    let tmpdir = tempdir().unwrap();
+    let install_dir = install_fixture(&tmpdir).await;
+
+    let version_dir = install_dir.join("versions").join(VERSION);
+    std::fs::write(version_dir.join("bin").join("wasmedge"), "tampered").unwrap();
+    std::fs::remove_file(
+        version_dir
+            .join("include")
+            .join("wasmedge")
+            .join("wasmedge.h"),
+    )
+    .unwrap();
+    // Files added after installation, such as plugins, are not reported.
+    std::fs::create_dir_all(version_dir.join("plugin")).unwrap();
+    std::fs::write(version_dir.join("plugin").join("libextra.so"), "plugin").unwrap();
+
+    let manifest = InstallManifest::load(&version_dir, VERSION).unwrap();
+    assert_eq!(manifest.verify(&version_dir).unwrap().len(), 2);
+
+    let result = verify(&install_dir, Some(VERSION)).await;
+    assert!(matches!(
+        result,
+        Err(Error::VerificationFailed { count: 2 })
+    ));
+}
+
+#[tokio::test]
+async fn test_verify_without_manifest() {
+    let tmpdir = tempdir().unwrap();
+    let install_dir = tmpdir.path().to_path_buf();
+    std::fs::create_dir_all(install_dir.join("versions").join(VERSION).join("bin")).unwrap();
+
+    let result = verify(&install_dir, Some(VERSION)).await;
+    assert!(matches!(result, Err(Error::ManifestNotFound { .. })));
+
+    // When verifying everything, versions without a manifest are skipped.
+    verify(&install_dir, None).await.unwrap();
+
+    let result = verify(&install_dir, Some("0.13.0")).await;
+    assert!(matches!(result, Err(Error::VersionNotFound { .. })));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_files_lists_manifest_entries() {
+    let (_home, _home_path) = setup_test_environment();
+    let tmpdir = tempdir().unwrap();
+    let install_dir = install_fixture(&tmpdir).await;
+
+    let files = |extra: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+            .arg("files")
+            .arg("--path")
+            .arg(&install_dir)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // Without a version, the active one is listed.
+    let relative = files(&[]);
+    assert_eq!(
+        relative.lines().collect::<Vec<_>>(),
+        [
+            "bin/wasmedge",
+            "include/wasmedge/wasmedge.h",
+            "lib/libwasmedge.so"
+        ]
+    );
+
+    let version_dir = install_dir.join("versions").join(VERSION);
+    let absolute = files(&[VERSION, "--absolute"]);
+    for line in absolute.lines() {
+        assert!(Path::new(line).is_absolute(), "{line}");
+        assert!(Path::new(line).starts_with(&version_dir), "{line}");
+        assert!(Path::new(line).is_file(), "{line}");
+    }
+
+    let args = wasmedgeup::commands::files::FilesArgs {
+        version: Some("0.13.0".to_string()),
+        path: Some(install_dir),
+        absolute: false,
+    };
+    let result = args.execute(CommandContext::default()).await;
+    assert!(matches!(result, Err(Error::VersionNotFound { .. })));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_list_json_includes_manifest_metadata() {
+    let (_home, _home_path) = setup_test_environment();
+    let tmpdir = tempdir().unwrap();
+    let install_dir = install_fixture(&tmpdir).await;
+    std::fs::create_dir_all(install_dir.join("versions").join("0.13.5")).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+        .args(["list", "--installed", "--json", "--path"])
+        .arg(&install_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let installed = &listing[0];
+    assert_eq!(installed["version"], VERSION);
+    assert_eq!(installed["active"], true);
+    assert_eq!(installed["os"], "Linux");
+    assert_eq!(installed["arch"], "X86_64");
+    let manifest =
+        InstallManifest::load(&install_dir.join("versions").join(VERSION), VERSION).unwrap();
+    assert_eq!(installed["checksum"], manifest.archive_checksum.as_str());
+    assert_eq!(installed["installed_at"], manifest.installed_at);
+    assert!(Path::new(installed["path"].as_str().unwrap()).ends_with(format!("versions/{VERSION}")));
+
+    // Versions without a manifest are still listed, without metadata.
+    let bare = &listing[1];
+    assert_eq!(bare["version"], "0.13.5");
+    assert_eq!(bare["active"], false);
+    assert!(bare["checksum"].is_null());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_version_prints_tool_and_active_runtime() {
+    let (_home, _home_path) = setup_test_environment();
+    let tmpdir = tempdir().unwrap();
+    let version = |install_dir: &Path, extra: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasmedgeup"))
+            .arg("version")
+            .arg("--path")
+            .arg(install_dir)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let empty = tmpdir.path().join("empty");
+    let text = version(&empty, &[]);
+    assert_eq!(
+        text,
+        format!(
+            "wasmedgeup: {}\nwasmedge: none (no active version in {})\n",
+            env!("CARGO_PKG_VERSION"),
+            empty.display()
+        )
+    );
+    let json: serde_json::Value = serde_json::from_str(&version(&empty, &["--json"])).unwrap();
+    assert!(json["wasmedge"].is_null());
+
+    let install_dir = install_fixture(&tmpdir).await;
+    let version_dir = install_dir.join("versions").join(VERSION);
+    let text = version(&install_dir, &[]);
+    assert_eq!(
+        text.lines().collect::<Vec<_>>(),
+        [
+            format!("wasmedgeup: {}", env!("CARGO_PKG_VERSION")),
+            format!("wasmedge: {VERSION}"),
+            format!("path: {}", version_dir.display()),
+            "target: Linux X86_64".to_string(),
+        ]
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&version(&install_dir, &["--json"])).unwrap();
+    assert_eq!(json["wasmedgeup"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(json["wasmedge"]["version"], VERSION);
+    assert_eq!(json["wasmedge"]["path"], version_dir.display().to_string());
+    assert_eq!(json["wasmedge"]["os"], "Linux");
+    assert_eq!(json["wasmedge"]["arch"], "X86_64");
+}
+
+/// Initializes a repository in `dir` with one commit, tagged `0.14.0` (lightweight) and
+/// `0.14.1` (annotated, unsigned); returns it with the commit.
+fn tagged_repo(dir: &Path) -> (git2::Repository, git2::Oid) {
+    let repo = git2::Repository::init(dir).unwrap();
+    let signature =
+        git2::Signature::new("Test", "test@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree = repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = repo.find_tree(tree).unwrap();
+    let commit = repo
+        .commit(Some("HEAD"), &signature, &signature, "Release", &tree, &[])
+        .unwrap();
+    let object = repo.find_object(commit, None).unwrap();
+    repo.tag_lightweight("0.14.0", &object, false).unwrap();
+    repo.tag("0.14.1", &object, &signature, "Release 0.14.1", false)
+        .unwrap();
+    drop((object, tree));
+    (repo, commit)
+}
+
+fn gpg(home: &Path, args: &[&str], stdin: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut child = std::process::Command::new("gpg")
+        .arg("--homedir")
+        .arg(home)
+        .args(["--batch", "--quiet", "--pinentry-mode", "loopback"])
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "gpg {args:?} failed");
+    output.stdout
+}
+
+/// Generates a signing key in a new GnuPG home under `dir` and returns the home and the
+/// exported public key.
+fn gpg_key(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+    let home = dir.join(format!("gnupg-{name}"));
+    std::fs::create_dir(&home).unwrap();
+    std::fs::set_permissions(&home, std::os::unix::fs::PermissionsExt::from_mode(0o700)).unwrap();
+    let uid = format!("{name} <{name}@example.com>");
+    gpg(
+        &home,
+        &[
+            "--passphrase",
+            "",
+            "--quick-gen-key",
+            &uid,
+            "ed25519",
+            "sign",
+            "never",
+        ],
+        b"",
+    );
+    let key = dir.join(format!("{name}.asc"));
+    std::fs::write(&key, gpg(&home, &["--armor", "--export"], b"")).unwrap();
+    (home, key)
+}
+
+#[tokio::test]
+async fn test_verify_tag_requires_annotated_tag() {
+    let tmpdir = tempdir().unwrap();
+    let repo_dir = tmpdir.path().join("WasmEdge");
+    tagged_repo(&repo_dir);
+    let client =
+        wasmedgeup::api::WasmEdgeApiClient::default().with_git_url(repo_dir.display().to_string());
+
+    client
+        .verify_release_tag(&Version::new(0, 14, 1), None)
+        .await
+        .expect("annotated tag should verify");
+
+    let err = client
+        .verify_release_tag(&Version::new(0, 14, 0), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Git { .. }), "{err:?}");
+    assert!(err.to_string().contains("lightweight tag"), "{err}");
+
+    let err = client
+        .verify_release_tag(&Version::new(0, 99, 0), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Git { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_verify_tag_checks_signature_against_key() {
+    if std::process::Command::new("gpg")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        eprintln!("gpg not available; skipping");
+        return;
+    }
+    let tmpdir = tempdir().unwrap();
+    let repo_dir = tmpdir.path().join("WasmEdge");
+    let (repo, commit) = tagged_repo(&repo_dir);
+
+    let (home, key) = gpg_key(tmpdir.path(), "maintainer");
+    let (_, other_key) = gpg_key(tmpdir.path(), "someone-else");
+    let payload = format!(
+        "object {commit}\ntype commit\ntag 0.15.0\ntagger Test <test@example.com> 0 +0000\n\nRelease 0.15.0\n"
+    );
+    let signature = gpg(&home, &["--armor", "--detach-sign"], payload.as_bytes());
+    let tag = repo
+        .odb()
+        .unwrap()
+        .write(
+            git2::ObjectType::Tag,
+            &[payload.as_bytes(), &signature].concat(),
+        )
+        .unwrap();
+    repo.reference("refs/tags/0.15.0", tag, false, "signed tag")
+        .unwrap();
+
+    let client =
+        wasmedgeup::api::WasmEdgeApiClient::default().with_git_url(repo_dir.display().to_string());
+    let signed = Version::new(0, 15, 0);
+    client
+        .verify_release_tag(&signed, Some(&key))
+        .await
+        .expect("signature should verify");
+
+    let err = client
+        .verify_release_tag(&signed, Some(&other_key))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("was not made with the key"),
+        "{err}"
+    );
+
+    let err = client
+        .verify_release_tag(&Version::new(0, 14, 1), Some(&key))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("is not signed"), "{err}");
+}
+
+#[tokio::test]
+async fn test_verify_signature_checks_checksum_file() {
+    if std::process::Command::new("gpg")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        eprintln!("gpg not available; skipping");
+        return;
+    }
+    let tmpdir = tempdir().unwrap();
+    let (home, key) = gpg_key(tmpdir.path(), "release-signer");
+    let (_, other_key) = gpg_key(tmpdir.path(), "someone-else");
+    let version = Version::parse(VERSION).unwrap();
+    let asset = Asset::new(&version, &TargetOS::Linux, &TargetArch::X86_64);
+    let checksum = sha256_hex(&linux_archive(&version));
+    let sums = format!("{checksum}  {}\n", asset.archive_name);
+    let signature = gpg(&home, &["--armor", "--detach-sign"], sums.as_bytes());
+
+    // A release whose checksum file is `served`, next to a signature of `signed`.
+    let release = |served: String, signature: Option<Vec<u8>>| async move {
+        let mock = MockRelease::start().await;
+        mock.mount_file(VERSION, "SHA256SUM", served.into_bytes())
+            .await;
+        if let Some(signature) = signature {
+            mock.mount_file(VERSION, "SHA256SUM.asc", signature).await;
+        }
+        mock
+    };
+
+    let mock = release(sums.clone(), Some(signature.clone())).await;
+    let client = mock.client().with_checksums_key(&key);
+    let verified = Published.checksum(&client, &version, &asset).await.unwrap();
+    assert_eq!(verified, checksum);
+
+    let client = mock.client().with_checksums_key(&other_key);
+    let err = Published
+        .checksum(&client, &version, &asset)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::SignatureInvalid { .. }), "{err:?}");
+    assert!(
+        err.to_string().contains("was not made with the key"),
+        "{err}"
+    );
+
+    let tampered = format!("{}  {}\n", "0".repeat(64), asset.archive_name);
+    let mock = release(tampered, Some(signature)).await;
+    let client = mock.client().with_checksums_key(&key);
+    let err = Published
+        .checksum(&client, &version, &asset)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::SignatureInvalid { .. }), "{err:?}");
+
+    let mock = release(sums, None).await;
+    let client = mock.client().with_checksums_key(&key);
+    let err = Published
+        .checksum(&client, &version, &asset)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::SignatureInvalid { .. }), "{err:?}");
+    assert!(err.to_string().contains("no SHA256SUM.asc"), "{err}");
+    // Without a key the unsigned checksum file is still read.
+    let unsigned = Published
+        .checksum(&mock.client(), &version, &asset)
+        .await
+        .unwrap();
+    assert_eq!(unsigned, checksum);
+}
+
+#[tokio::test]
+async fn test_verify_file_against_published_checksum() {
+    let mock = MockRelease::start().await;
+    let asset = linux_asset();
+    let archive = linux_archive(&asset.version);
+    let checksum = sha256_hex(&archive);
+    mock.mount_release(VERSION, &asset.archive_name, archive.clone(), &checksum)
+        .await;
+
+    let tmpdir = tempdir().unwrap();
+    let verify_file = |contents: &[u8]| {
+        let file = tmpdir.path().join(&asset.archive_name);
+        std::fs::write(&file, contents).unwrap();
+        let args = VerifyFileArgs {
+            file,
+            version: VERSION.to_string(),
+            os: Some(TargetOS::Linux),
+            arch: Some(TargetArch::X86_64),
+        };
+        let ctx = CommandContext {
+            client: mock.client(),
+            no_progress: true,
+        };
+        args.execute(ctx)
+    };
+
+    verify_file(&archive).await.expect("archive should verify");
+
+    let result = verify_file(b"tampered").await;
+    assert!(
+        matches!(result, Err(Error::ChecksumMismatch { .. })),
+        "{result:?}"
+    );
+    // Nothing is extracted next to the file.
+    assert_eq!(std::fs::read_dir(tmpdir.path()).unwrap().count(), 1);
+}